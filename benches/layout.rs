@@ -0,0 +1,91 @@
+//! Measures how long the layout pass of [`egui_tiles::Tree::ui`] takes for very large trees,
+//! see [`egui_tiles::bench::layout_tree`].
+//!
+//! Run with `cargo bench --features bench --bench layout`.
+//! Without `--bench` (e.g. with `cargo test --benches`) the layout is only run once, as a smoke test.
+//!
+//! Set `EGUI_TILES_ENFORCE_BUDGET=1` to fail the benchmark when the layout is over [`BUDGET`],
+//! e.g. to catch performance regressions on a dedicated machine.
+
+use std::time::{Duration, Instant};
+
+use egui_tiles::{Tiles, Tree, UiResponse};
+
+/// We want the layout of a densely stored tree with 10k tiles to take less than this.
+const BUDGET: Duration = Duration::from_millis(1);
+
+struct Pane;
+
+struct NoopBehavior;
+
+impl egui_tiles::Behavior<Pane> for NoopBehavior {
+    fn pane_ui(
+        &mut self,
+        _ui: &mut egui::Ui,
+        _tile_id: egui_tiles::TileId,
+        _pane: &mut Pane,
+    ) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &Pane) -> egui::WidgetText {
+        "".into()
+    }
+}
+
+/// A vertical container of `rows` horizontal containers, each with `columns` panes.
+fn create_tree(rows: usize, columns: usize, dense: bool) -> Tree<Pane> {
+    let mut tiles = Tiles::default();
+    tiles.set_dense_storage(dense);
+    let rows = (0..rows)
+        .map(|_| {
+            let panes = (0..columns).map(|_| tiles.insert_pane(Pane)).collect();
+            tiles.insert_horizontal_tile(panes)
+        })
+        .collect();
+    let root = tiles.insert_vertical_tile(rows);
+    Tree::new("bench_tree", root, tiles)
+}
+
+fn bench_layout(rows: usize, columns: usize, dense: bool, iterations: u32) -> Duration {
+    let mut tree = create_tree(rows, columns, dense);
+    let style = egui::Style::default();
+    let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1920.0, 1080.0));
+    egui_tiles::bench::layout_tree(&mut tree, &mut NoopBehavior, &style, rect); // warm up
+
+    let start = Instant::now();
+    for _ in 0..iterations {
+        egui_tiles::bench::layout_tree(&mut tree, &mut NoopBehavior, &style, rect);
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let is_bench = std::env::args().any(|arg| arg == "--bench");
+    let iterations = if is_bench { 1000 } else { 1 };
+    let enforce_budget = std::env::var_os("EGUI_TILES_ENFORCE_BUDGET").is_some_and(|v| v != "0");
+    let mut over_budget_layouts = vec![];
+
+    for (rows, columns) in [(1, 9), (10, 9), (10, 99), (100, 99)] {
+        let num_tiles = 1 + rows * (1 + columns);
+        for dense in [false, true] {
+            let storage = if dense { "dense" } else { "hash map" };
+            let layout_time = bench_layout(rows, columns, dense, iterations);
+            let line = format!(
+                "{num_tiles:>6} tiles, {storage:<8} storage: {layout_time:>10.1?} per layout"
+            );
+            if is_bench && dense && 10_000 <= num_tiles && BUDGET < layout_time {
+                println!("{line} (over budget!)");
+                over_budget_layouts.push(line);
+            } else {
+                println!("{line}");
+            }
+        }
+    }
+
+    assert!(
+        !enforce_budget || over_budget_layouts.is_empty(),
+        "Layout is over the budget of {BUDGET:?}:\n{}",
+        over_budget_layouts.join("\n")
+    );
+}
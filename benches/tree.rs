@@ -0,0 +1,92 @@
+//! Measures the most common operations on trees of different sizes, see [`egui_tiles::bench`].
+//!
+//! Run with `cargo bench --features bench --bench tree`.
+//! Without `--bench` (e.g. with `cargo test --benches`) each operation is only run once, as a smoke test.
+//!
+//! Set `EGUI_TILES_ENFORCE_BUDGET=1` to fail the benchmark when a frame is over [`FRAME_BUDGET`],
+//! e.g. to catch performance regressions on a dedicated machine.
+
+use std::time::Duration;
+
+use egui_tiles::{
+    bench::{synthetic_tree, Harness},
+    Behavior, TileId, UiResponse,
+};
+
+/// A frame of [`egui_tiles::Tree::ui`] with up to this many tiles should fit in a 60 Hz frame, with room to spare.
+const FRAME_BUDGET: Duration = Duration::from_millis(4);
+
+/// The largest (requested) tree size [`FRAME_BUDGET`] applies to.
+const FRAME_BUDGET_MAX_TILES: usize = 1_000;
+
+/// Panes that show nothing, to measure the overhead of the tree itself.
+struct EmptyBehavior;
+
+impl Behavior<usize> for EmptyBehavior {
+    fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut usize) -> UiResponse {
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &usize) -> egui::WidgetText {
+        "".into()
+    }
+}
+
+/// Panes with a few widgets each, closer to a real application.
+struct WidgetBehavior;
+
+impl Behavior<usize> for WidgetBehavior {
+    fn pane_ui(&mut self, ui: &mut egui::Ui, _tile_id: TileId, pane: &mut usize) -> UiResponse {
+        ui.label(format!("Pane {pane}"));
+        if ui.button("Increment").clicked() {
+            *pane += 1;
+        }
+        UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, pane: &usize) -> egui::WidgetText {
+        format!("Pane {pane}").into()
+    }
+
+    fn is_tab_closable(&self, _tiles: &egui_tiles::Tiles<usize>, _tile_id: TileId) -> bool {
+        true
+    }
+}
+
+fn main() {
+    let is_bench = std::env::args().any(|arg| arg == "--bench");
+    let iterations = if is_bench { 50 } else { 1 };
+    let enforce_budget = std::env::var_os("EGUI_TILES_ENFORCE_BUDGET").is_some_and(|v| v != "0");
+    let mut over_budget = vec![];
+
+    let behaviors: [(&str, &mut dyn Behavior<usize>); 2] = [
+        ("empty", &mut EmptyBehavior),
+        ("widgets", &mut WidgetBehavior),
+    ];
+
+    for (behavior_name, behavior) in behaviors {
+        println!("{behavior_name} panes:");
+        for num_tiles in [10, 100, 1_000, 10_000] {
+            let mut tree = synthetic_tree("bench_tree", num_tiles, |i| i);
+            for measurement in Harness::default().measure_tree(&mut tree, behavior, iterations) {
+                let is_frame = measurement.name == "ui" || measurement.name == "drag_hover";
+                if is_bench
+                    && is_frame
+                    && num_tiles <= FRAME_BUDGET_MAX_TILES
+                    && FRAME_BUDGET < measurement.mean
+                {
+                    println!("  {measurement} (over budget!)");
+                    over_budget.push(format!("{behavior_name} panes: {measurement}"));
+                } else {
+                    println!("  {measurement}");
+                }
+            }
+        }
+    }
+
+    assert!(
+        !enforce_budget || over_budget.is_empty(),
+        "Frames are over the budget of {FRAME_BUDGET:?}:\n{}",
+        over_budget.join("\n")
+    );
+}
@@ -98,6 +98,10 @@ impl TreeBehavior {
                 );
                 ui.end_row();
 
+                ui.label("Join single-line grids:");
+                ui.checkbox(&mut simplification_options.join_single_line_grids, "");
+                ui.end_row();
+
                 ui.label("Tab bar height:");
                 ui.add(
                     egui::DragValue::new(tab_bar_height)
@@ -159,7 +163,11 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
         true
     }
 
-    fn on_tab_close(&mut self, tiles: &mut Tiles<Pane>, tile_id: TileId) -> bool {
+    fn on_tab_close(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        tile_id: TileId,
+    ) -> egui_tiles::CloseResponse {
         if let Some(tile) = tiles.get(tile_id) {
             match tile {
                 Tile::Pane(pane) => {
@@ -167,6 +175,7 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
                     let tab_title = self.tab_title_for_pane(pane);
                     log::debug!("Closing tab: {}, tile ID: {tile_id:?}", tab_title.text());
                 }
+                Tile::Placeholder => {}
                 Tile::Container(container) => {
                     // Container removal
                     log::debug!("Closing container: {:?}", container.kind());
@@ -182,7 +191,7 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
         }
 
         // Proceed to removing the tab
-        true
+        egui_tiles::CloseResponse::Close
     }
 }
 
@@ -265,9 +274,10 @@ impl eframe::App for MyApp {
 
             ui.separator();
 
-            if let Some(root) = self.tree.root() {
-                tree_ui(ui, &mut self.behavior, &mut self.tree.tiles, root);
-            }
+            #[cfg(feature = "inspector")]
+            egui_tiles::debug::tree_inspector(ui, &mut self.tree, &mut self.behavior);
+            #[cfg(not(feature = "inspector"))]
+            ui.label("Run with `--features inspector` to inspect and edit the tree here.");
 
             if let Some(parent) = self.behavior.add_child_to.take() {
                 let new_child = self.tree.tiles.insert_pane(Pane::with_nr(100));
@@ -290,59 +300,3 @@ impl eframe::App for MyApp {
         eframe::set_value(_storage, eframe::APP_KEY, &self);
     }
 }
-
-fn tree_ui(
-    ui: &mut egui::Ui,
-    behavior: &mut dyn egui_tiles::Behavior<Pane>,
-    tiles: &mut egui_tiles::Tiles<Pane>,
-    tile_id: egui_tiles::TileId,
-) {
-    // Get the name BEFORE we remove the tile below!
-    let text = format!(
-        "{} - {tile_id:?}",
-        behavior.tab_title_for_tile(tiles, tile_id).text()
-    );
-
-    // Temporarily remove the tile to circumvent the borrowchecker
-    let Some(mut tile) = tiles.remove(tile_id) else {
-        log::debug!("Missing tile {tile_id:?}");
-        return;
-    };
-
-    let default_open = true;
-    egui::collapsing_header::CollapsingState::load_with_default_open(
-        ui.ctx(),
-        ui.id().with((tile_id, "tree")),
-        default_open,
-    )
-    .show_header(ui, |ui| {
-        ui.label(text);
-        let mut visible = tiles.is_visible(tile_id);
-        ui.checkbox(&mut visible, "Visible");
-        tiles.set_visible(tile_id, visible);
-    })
-    .body(|ui| match &mut tile {
-        egui_tiles::Tile::Pane(_) => {}
-        egui_tiles::Tile::Container(container) => {
-            let mut kind = container.kind();
-            egui::ComboBox::from_label("Kind")
-                .selected_text(format!("{kind:?}"))
-                .show_ui(ui, |ui| {
-                    for typ in egui_tiles::ContainerKind::ALL {
-                        ui.selectable_value(&mut kind, typ, format!("{typ:?}"))
-                            .clicked();
-                    }
-                });
-            if kind != container.kind() {
-                container.set_kind(kind);
-            }
-
-            for &child in container.children() {
-                tree_ui(ui, behavior, tiles, child);
-            }
-        }
-    });
-
-    // Put the tile back
-    tiles.insert(tile_id, tile);
-}
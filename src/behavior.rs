@@ -1,9 +1,14 @@
+use std::sync::Arc;
+
 use egui::{
-    vec2, Color32, Id, Rect, Response, Rgba, Sense, Stroke, TextStyle, Ui, Vec2, Visuals,
-    WidgetText,
+    vec2, Color32, Id, NumExt as _, Pos2, Rect, Response, Rgba, Sense, Stroke, TextStyle, Ui, Vec2,
+    Visuals, WidgetText,
 };
 
-use super::{ResizeState, SimplificationOptions, Tile, TileId, Tiles, UiResponse};
+use super::{
+    ContainerKind, LinearDir, ResizeState, SimplificationOptions, Tile, TileId, Tiles,
+    TreeGeometry, TreeKeymap, UiResponse,
+};
 
 /// The kind of edit that triggered the call to [`Behavior::on_edit`].
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -14,12 +19,361 @@ pub enum EditAction {
     /// A drag with a tile started.
     TileDragged,
 
-    /// A tile was dropped and its position changed accordingly.
+    /// A tile was dropped and its position changed accordingly,
+    /// or it was removed or detached by dropping it outside the tree (see [`Behavior::on_drop_outside`]).
     TileDropped,
 
     /// A tab was selected by a click, or by hovering a dragged tile over it,
     /// or there was no active tab and egui picked an arbitrary one.
     TabSelected,
+
+    /// A tab was pinned or unpinned.
+    TabPinned,
+
+    /// A tab was inserted with [`crate::Tabs::insert_child`].
+    TabInserted,
+
+    /// A tab was moved with [`crate::Tabs::move_child`].
+    TabMoved,
+
+    /// A tab was closed with [`crate::Tabs::close`].
+    TabClosed,
+
+    /// A tile was moved into a new split using a keyboard shortcut (see [`crate::TreeKeymap`]).
+    TileMoved,
+
+    /// A whole column or row of a grid was moved.
+    GridReordered,
+
+    /// A container was converted to a different [`crate::ContainerKind`].
+    ContainerKindChanged,
+
+    /// A container was replaced by its children with [`crate::Tree::flatten_container`].
+    ContainerFlattened,
+
+    /// A tile was put in a new container with [`crate::Tree::wrap_in_container`].
+    ContainerWrapped,
+
+    /// The tree was changed with [`crate::Tree::edit`] or with queued [`crate::TreeCommand`]s.
+    TreeEdited,
+}
+
+/// What happens when the user double-clicks a resize line.
+///
+/// See [`Behavior::splitter_double_click_action`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SplitterDoubleClick {
+    /// Do nothing.
+    None,
+
+    /// Give the two tiles on either side of the line the same size.
+    #[default]
+    Equalize,
+
+    /// Give all the tiles along the same axis in the container the same size.
+    Reset,
+
+    /// Restore the sizes from before the line was last dragged.
+    RestoreLast,
+}
+
+/// When to show the close button of a closable tab.
+///
+/// Space is always left for the button, so that the tabs don't move when it appears.
+///
+/// See [`Behavior::close_button_visibility`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CloseButtonVisibility {
+    /// Show the close button on every closable tab.
+    #[default]
+    Always,
+
+    /// Only show the close button on the tab under the mouse pointer.
+    Hover,
+
+    /// Only show the close button on the active tab.
+    ActiveOnly,
+}
+
+/// What happens when the user scrolls the mouse wheel over a tab bar.
+///
+/// See [`Behavior::tab_bar_wheel_action`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TabBarWheel {
+    /// Scroll the tabs sideways, if they don't all fit.
+    ///
+    /// The wheel is left to a surrounding [`egui::ScrollArea`] when the tabs fit,
+    /// or when they can't be scrolled any further in that direction.
+    #[default]
+    ScrollStrip,
+
+    /// Activate the next or previous tab.
+    SwitchTab,
+
+    /// Ignore the wheel, letting e.g. a surrounding [`egui::ScrollArea`] scroll.
+    None,
+}
+
+/// Which of the scroll arrows of a tab bar to show.
+///
+/// See [`Behavior::scroll_arrow_ui`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TabScrollArrow {
+    /// Scrolls the tabs to the left.
+    Left,
+
+    /// Scrolls the tabs to the right.
+    Right,
+}
+
+/// How strongly a tile asks for the attention of the user.
+///
+/// See [`crate::Tree::request_attention`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AttentionLevel {
+    /// Show a badge on the tab.
+    Low,
+
+    /// Show a badge and make the tab flash.
+    High,
+}
+
+/// What to do when the tree doesn't fit its panes at their minimum size.
+///
+/// See [`Behavior::overflow_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Make the panes smaller than their minimum size.
+    #[default]
+    Squish,
+
+    /// Show the tree in a scroll area, big enough for every pane to get its minimum size.
+    Scroll,
+}
+
+/// Where the tabs are placed in a tab bar that is wider than they need.
+///
+/// See [`Behavior::tab_alignment`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TabAlignment {
+    /// Pack the tabs to the left.
+    #[default]
+    Left,
+
+    /// Put the tabs in the middle of the tab bar.
+    Center,
+
+    /// Pack the tabs to the right.
+    Right,
+
+    /// Stretch the tabs so that they fill the whole tab bar,
+    /// distributing the extra width evenly among them.
+    Justified,
+
+    /// Give every tab the same share of the tab bar, like a segmented control,
+    /// truncating titles that don't fit.
+    ///
+    /// Tabs are never made narrower than they are high; if there are too many tabs
+    /// for that, the tab bar scrolls as usual.
+    EqualWidth,
+}
+
+/// What to do with tiles that are not reachable from the root of the tree.
+///
+/// See [`Behavior::on_unreachable_tiles`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OrphanPolicy {
+    /// Remove the tiles.
+    #[default]
+    Drop,
+
+    /// Add the tiles as tabs to the given [`crate::Tabs`] container, which must be part of the tree.
+    Adopt(TileId),
+
+    /// Leave the tiles in [`Tiles`], where you can take care of them yourself.
+    ///
+    /// As long as they stay unreachable, [`Behavior::on_unreachable_tiles`] will be called again on the next GC.
+    Keep,
+}
+
+/// What to do when the user asks to close a tab, see [`Behavior::on_tab_close`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseResponse {
+    /// Close the tab.
+    Close,
+
+    /// Keep the tab open.
+    Keep,
+
+    /// Keep the tab open, and ask the user to confirm the close in a modal dialog.
+    ///
+    /// The dialog shows [`Behavior::close_confirmation_title`] and [`Behavior::close_confirmation_message`],
+    /// and the answer is passed to [`Behavior::on_close_confirmation`].
+    /// The tab is closed if the user confirms.
+    Pending,
+}
+
+impl From<bool> for CloseResponse {
+    /// `true` means [`Self::Close`], and `false` means [`Self::Keep`].
+    fn from(close: bool) -> Self {
+        if close {
+            Self::Close
+        } else {
+            Self::Keep
+        }
+    }
+}
+
+/// What to do with a tile that is dropped outside the tree.
+///
+/// See [`Behavior::on_drop_outside`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum DropOutsideAction {
+    /// Leave the tile where it was.
+    #[default]
+    Ignore,
+
+    /// Remove the tile and all its children from the tree.
+    Remove,
+
+    /// Take the tile out of its parent, but keep it (and its children) in [`Tiles`],
+    /// e.g. to show it in a floating window.
+    ///
+    /// The tile is then no longer reachable from the root, so you need to return [`OrphanPolicy::Keep`]
+    /// from [`Behavior::on_unreachable_tiles`], or take it out of the tree in [`Behavior::on_drag_ended`].
+    Detach,
+}
+
+/// Where a dragged tile ended up, see [`Behavior::on_tile_dropped`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DropResult {
+    /// The dropped tile, or its copy if it was copied (see [`Behavior::clone_pane`]).
+    pub tile_id: TileId,
+
+    /// The container the tile was dropped into.
+    pub parent_id: TileId,
+
+    /// If the tile was dropped onto a tile that was not a container of the right kind,
+    /// a new container was created in its place, taking over its id ([`Self::parent_id`]).
+    /// The tile that was there before then got this new id,
+    /// and its name moved along with it (see [`Behavior::on_tile_id_changed`]).
+    pub wrapped_tile: Option<TileId>,
+}
+
+impl DropResult {
+    /// The container that was created to hold the dropped tile, if any.
+    pub fn created_container(&self) -> Option<TileId> {
+        self.wrapped_tile.map(|_| self.parent_id)
+    }
+}
+
+/// What will happen when a dragged tile is dropped into a cell of a [`crate::Grid`].
+///
+/// See [`GridDropPreview`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridDrop {
+    /// The cell is empty, and the tile will be put there.
+    Empty,
+
+    /// The dragged tile comes from the same grid, and will swap places with the tile in the cell.
+    Swap,
+
+    /// The tile will be inserted before the tile in the cell, moving it and all following tiles one step.
+    Insert,
+}
+
+/// Context for [`Behavior::paint_grid_drop_preview`].
+#[derive(Clone, Copy, Debug)]
+pub struct GridDropPreview {
+    /// The grid being dropped into.
+    pub grid_id: TileId,
+
+    /// The destination cell.
+    pub cell_rect: Rect,
+
+    /// The tile currently in the destination cell, if any.
+    pub occupant: Option<TileId>,
+
+    /// The rect of [`Self::occupant`].
+    pub occupant_rect: Option<Rect>,
+
+    /// What will happen on drop.
+    pub drop: GridDrop,
+}
+
+/// How the drag of a tile was started.
+///
+/// See [`crate::Tree::drag_origin`] and [`Behavior::is_drop_allowed`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DragOrigin {
+    /// The tab button of the tile.
+    TabButton,
+
+    /// The body of a pane, by returning [`UiResponse::DragStarted`] from [`Behavior::pane_ui`].
+    PaneBody,
+
+    /// The empty part of a tab bar, dragging the whole [`crate::Tabs`] container.
+    TabBar,
+
+    /// A column or row header of a [`crate::Grid`] that was dragged out of the grid,
+    /// dragging the whole column or row as a new [`crate::Linear`] container.
+    GridHeader,
+
+    /// [`crate::Tree::start_drag`], e.g. from a widget outside the tree.
+    #[default]
+    External,
+}
+
+/// How the drag of a tile ended.
+///
+/// See [`Behavior::on_drag_ended`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DragEnd {
+    /// The tile was dropped somewhere in the tree, see [`Behavior::on_tile_dropped`].
+    Dropped,
+
+    /// The tile was dropped on this pane, which handles the drop itself (see [`crate::PaneDrop`]).
+    DroppedOnPane(TileId),
+
+    /// The tile was dropped outside the tree, and removed or detached (see [`Behavior::on_drop_outside`]).
+    DroppedOutside(DropOutsideAction),
+
+    /// The tile was released where it can't be dropped, or the drag was cancelled.
+    Cancelled,
+}
+
+impl DragOrigin {
+    /// The key in [`egui::UiTags`] used for the tag.
+    pub const KEY: &'static str = "egui_tiles::DragOrigin";
+
+    /// The origin of the drag, when called from [`Behavior::drag_ui`].
+    pub fn from_ui(ui: &Ui) -> Option<Self> {
+        ui.stack()
+            .iter()
+            .find_map(|stack| stack.tags().get_downcast::<Self>(Self::KEY).copied())
+    }
+}
+
+/// Where a dragged tab was grabbed, relative to the top left corner of the tab.
+///
+/// The preview of the drag is placed so that this point stays under the pointer.
+/// Use [`Self::from_ui`] in [`Behavior::drag_ui`] to position a custom preview the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GrabOffset(pub Vec2);
+
+impl GrabOffset {
+    /// The key in [`egui::UiTags`] used for the tag.
+    pub const KEY: &'static str = "egui_tiles::GrabOffset";
+
+    /// The grab offset of the drag, when called from [`Behavior::drag_ui`].
+    ///
+    /// `None` if the drag wasn't started from a tab, e.g. with [`crate::Tree::start_drag`].
+    pub fn from_ui(ui: &Ui) -> Option<Self> {
+        ui.stack()
+            .iter()
+            .find_map(|stack| stack.tags().get_downcast::<Self>(Self::KEY).copied())
+    }
 }
 
 /// The state of a tab, used to inform the rendering of the tab.
@@ -33,6 +387,31 @@ pub struct TabState {
 
     /// Should the tab have a close button?
     pub closable: bool,
+
+    /// Is the tree shown read-only (see [`crate::Tree::ui_readonly`])?
+    ///
+    /// Then the tab can be clicked, but not dragged.
+    pub read_only: bool,
+
+    /// Is the tab pinned (see [`crate::Tabs::pin`])?
+    ///
+    /// You can use this in [`Behavior::tab_ui`] to show pinned tabs more compactly, e.g. icon-only.
+    pub pinned: bool,
+
+    /// Extra width to give the tab button, beyond what its title needs.
+    ///
+    /// Non-zero when the tabs are stretched to fill the tab bar, see [`TabAlignment::Justified`].
+    pub extra_width: f32,
+
+    /// If set, the tab button must be exactly this wide, truncating the title if needed.
+    ///
+    /// Set when all tabs get an equal share of the tab bar, see [`TabAlignment::EqualWidth`].
+    pub fixed_width: Option<f32>,
+
+    /// Does the tile, or some tile inside it, want the attention of the user?
+    ///
+    /// See [`crate::Tree::request_attention`].
+    pub attention: Option<AttentionLevel>,
 }
 
 /// Trait defining how the [`super::Tree`] and its panes should be shown.
@@ -46,6 +425,31 @@ pub trait Behavior<Pane> {
     /// The title of a pane tab.
     fn tab_title_for_pane(&mut self, pane: &Pane) -> WidgetText;
 
+    /// Create the pane of a [`Tile::Placeholder`], the first time it is shown.
+    ///
+    /// This lets you restore a session with hundreds of tabs
+    /// without creating the panes of the tabs that are never looked at.
+    /// See [`Tiles::insert_placeholder`].
+    ///
+    /// If this returns `None` the placeholder is left as is, and shown with [`Self::missing_pane_ui`].
+    /// It is then asked again the next time the placeholder is shown.
+    fn instantiate_pane(&mut self, _tile_id: TileId) -> Option<Pane> {
+        None
+    }
+
+    /// Show a [`Tile::Placeholder`] whose pane [`Self::instantiate_pane`] could not create,
+    /// e.g. because the plugin providing it is no longer loaded.
+    ///
+    /// The placeholder keeps the place of the pane in the tree,
+    /// see [`crate::Tree::replace_pane_with_placeholder`].
+    /// The default shows nothing.
+    fn missing_pane_ui(&mut self, _ui: &mut Ui, _tile_id: TileId) {}
+
+    /// The title of the tab of a [`Tile::Placeholder`].
+    fn tab_title_for_placeholder(&mut self, _tile_id: TileId) -> WidgetText {
+        "…".into()
+    }
+
     /// Should the tab have a close-button?
     fn is_tab_closable(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
         false
@@ -53,9 +457,47 @@ pub trait Behavior<Pane> {
 
     /// Called when the close-button on a tab is pressed.
     ///
-    /// Return `false` to abort the closing of a tab (e.g. after showing a message box).
-    fn on_tab_close(&mut self, _tiles: &mut Tiles<Pane>, _tile_id: TileId) -> bool {
-        true
+    /// Return [`CloseResponse::Keep`] to abort the closing of a tab,
+    /// or [`CloseResponse::Pending`] to let the user confirm it first (e.g. "Close without saving?").
+    fn on_tab_close(&mut self, _tiles: &mut Tiles<Pane>, _tile_id: TileId) -> CloseResponse {
+        CloseResponse::Close
+    }
+
+    /// The title of the dialog shown after [`Self::on_tab_close`] returned [`CloseResponse::Pending`].
+    fn close_confirmation_title(&mut self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> WidgetText {
+        "Close tab".into()
+    }
+
+    /// The message of the dialog shown after [`Self::on_tab_close`] returned [`CloseResponse::Pending`].
+    fn close_confirmation_message(&mut self, tiles: &Tiles<Pane>, tile_id: TileId) -> WidgetText {
+        let title = self.tab_title_for_tile(tiles, tile_id);
+        format!("Do you want to close {:?}?", title.text()).into()
+    }
+
+    /// Called with the answer of the user to the close confirmation dialog,
+    /// right before the tab is closed (if `confirmed`).
+    fn on_close_confirmation(
+        &mut self,
+        _tiles: &mut Tiles<Pane>,
+        _tile_id: TileId,
+        _confirmed: bool,
+    ) {
+    }
+
+    /// Should the close-button on a tab hide the tile instead of removing it?
+    ///
+    /// If `true`, a closed tile is made invisible with [`Tiles::set_visible`],
+    /// so that it can be reopened later. Use [`Tiles::hidden_tiles`] to list them,
+    /// e.g. for a "Reopen closed panel" menu.
+    fn hide_on_close(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+        false
+    }
+
+    /// Should the tab have a "Pin tab"/"Unpin tab" action in its context menu?
+    ///
+    /// See [`crate::Tabs::pin`].
+    fn show_pin_action(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+        false
     }
 
     /// The size of the close button in the tab.
@@ -69,21 +511,55 @@ pub trait Behavior<Pane> {
         2.0
     }
 
+    /// The smallest size of the area that reacts to clicks on the close button,
+    /// which may be bigger than [`Self::close_button_outer_size`] to make it easier to hit.
+    fn close_button_min_interact_size(&self) -> f32 {
+        16.0
+    }
+
+    /// When to show the close buttons of closable tabs.
+    fn close_button_visibility(&self) -> CloseButtonVisibility {
+        CloseButtonVisibility::Always
+    }
+
+    /// For how many seconds after a drag ends the close buttons ignore clicks,
+    /// so that a tab dropped onto a tab bar doesn't close another tab by accident.
+    ///
+    /// The close buttons also ignore clicks while a tile is being dragged.
+    fn close_button_drag_cooldown(&self) -> f64 {
+        0.3
+    }
+
     /// The title of a general tab.
     ///
-    /// The default implementation calls [`Self::tab_title_for_pane`] for panes and
+    /// The default implementation calls [`Self::tab_title_for_pane`] for panes,
+    /// [`Self::tab_title_for_placeholder`] for placeholders, and
     /// uses the name of the [`crate::ContainerKind`] for [`crate::Container`]s.
     fn tab_title_for_tile(&mut self, tiles: &Tiles<Pane>, tile_id: TileId) -> WidgetText {
         if let Some(tile) = tiles.get(tile_id) {
             match tile {
                 Tile::Pane(pane) => self.tab_title_for_pane(pane),
                 Tile::Container(container) => format!("{:?}", container.kind()).into(),
+                Tile::Placeholder => self.tab_title_for_placeholder(tile_id),
             }
         } else {
             "MISSING TILE".into()
         }
     }
 
+    /// The progress of some long-running work in this tile, shown in its tab by the default [`Self::tab_ui`].
+    ///
+    /// A value in `0.0..=1.0` is shown as a thin progress strip along the bottom of the tab.
+    /// Any other value, e.g. `-1.0`, means the tile is busy but the progress is unknown,
+    /// and is shown as a spinner before the title.
+    ///
+    /// egui keeps repainting while a spinner is shown.
+    /// A known progress is only updated when egui repaints for some other reason,
+    /// so call [`egui::Context::request_repaint`] when it changes.
+    fn tab_progress_for_tile(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> Option<f32> {
+        None
+    }
+
     /// Show the ui for the a tab of some tile.
     ///
     /// The default implementation shows a clickable button with the title for that tile,
@@ -105,22 +581,51 @@ pub trait Behavior<Pane> {
         let close_btn_size = Vec2::splat(self.close_button_outer_size());
         let close_btn_left_padding = 4.0;
         let font_id = TextStyle::Button.resolve(ui.style());
-        let galley = text.into_galley(ui, Some(egui::TextWrapMode::Extend), f32::INFINITY, font_id);
 
         let x_margin = self.tab_title_spacing(ui.visuals());
 
-        let button_width = galley.size().x
-            + 2.0 * x_margin
-            + f32::from(state.closable) * (close_btn_left_padding + close_btn_size.x);
+        let close_btn_width =
+            f32::from(state.closable) * (close_btn_left_padding + close_btn_size.x);
+
+        let progress = self.tab_progress_for_tile(tiles, tile_id);
+        let is_busy = progress.is_some_and(|progress| !(0.0..=1.0).contains(&progress));
+        let spinner_size = ui.text_style_height(&TextStyle::Button);
+        let spinner_width = f32::from(is_busy) * (spinner_size + close_btn_left_padding);
+
+        let (galley, button_width) = if let Some(fixed_width) = state.fixed_width {
+            let title_width =
+                (fixed_width - 2.0 * x_margin - spinner_width - close_btn_width).at_least(0.0);
+            let galley =
+                text.into_galley(ui, Some(egui::TextWrapMode::Truncate), title_width, font_id);
+            (galley, fixed_width)
+        } else {
+            let galley =
+                text.into_galley(ui, Some(egui::TextWrapMode::Extend), f32::INFINITY, font_id);
+            let width = galley.size().x
+                + 2.0 * x_margin
+                + spinner_width
+                + close_btn_width
+                + state.extra_width.at_least(0.0);
+            (galley, width)
+        };
         let (_, tab_rect) = ui.allocate_space(vec2(button_width, ui.available_height()));
 
-        let tab_response = ui
-            .interact(tab_rect, id, Sense::click_and_drag())
-            .on_hover_cursor(egui::CursorIcon::Grab);
+        let tab_response = if state.read_only {
+            ui.interact(tab_rect, id, Sense::click())
+        } else {
+            ui.interact(tab_rect, id, Sense::click_and_drag())
+                .on_hover_cursor(egui::CursorIcon::Grab)
+        };
 
         // Show a gap when dragged
         if ui.is_rect_visible(tab_rect) && !state.is_being_dragged {
-            let bg_color = self.tab_bg_color(ui.visuals(), tiles, tile_id, state);
+            let mut bg_color = self.tab_bg_color(ui.visuals(), tiles, tile_id, state);
+            if state.attention == Some(AttentionLevel::High) {
+                let time = ui.input(|i| i.time);
+                let flash = 0.5 - 0.5 * (time * std::f64::consts::TAU).cos();
+                bg_color = bg_color.lerp_to_gamma(ui.visuals().warn_fg_color, 0.4 * flash as f32);
+                ui.ctx().request_repaint();
+            }
             let stroke = self.tab_outline_stroke(ui.visuals(), tiles, tile_id, state);
             ui.painter()
                 .rect(tab_rect.shrink(0.5), 0.0, bg_color, stroke);
@@ -136,22 +641,71 @@ pub trait Behavior<Pane> {
 
             // Prepare title's text for rendering
             let text_color = self.tab_text_color(ui.visuals(), tiles, tile_id, state);
-            let text_position = egui::Align2::LEFT_CENTER
-                .align_size_within_rect(galley.size(), tab_rect.shrink(x_margin))
+            let mut title_rect = tab_rect.shrink(x_margin);
+            title_rect.min.x += spinner_width;
+            title_rect.max.x -= close_btn_width;
+            let text_position = egui::Align2::CENTER_CENTER
+                .align_size_within_rect(galley.size(), title_rect)
                 .min;
 
             // Render the title
             ui.painter().galley(text_position, galley, text_color);
 
-            // Conditionally render the close button
-            if state.closable {
+            // Render the progress, if any
+            if is_busy {
+                let spinner_rect = egui::Align2::LEFT_CENTER
+                    .align_size_within_rect(Vec2::splat(spinner_size), tab_rect.shrink(x_margin));
+                egui::Spinner::new()
+                    .size(spinner_size)
+                    .color(text_color)
+                    .paint_at(ui, spinner_rect);
+            } else if let Some(progress) = progress {
+                let mut strip_rect = tab_rect.shrink(1.0);
+                strip_rect.min.y = strip_rect.max.y - 2.0;
+                strip_rect.max.x = strip_rect.lerp_inside(vec2(progress, 0.0)).x;
+                ui.painter()
+                    .rect_filled(strip_rect, 0.0, ui.visuals().selection.bg_fill);
+            }
+
+            // Render the attention badge, if any
+            if let Some(attention) = state.attention {
+                let color = match attention {
+                    AttentionLevel::Low => ui.visuals().selection.bg_fill,
+                    AttentionLevel::High => ui.visuals().warn_fg_color,
+                };
+                ui.painter()
+                    .circle_filled(tab_rect.right_top() + vec2(-5.0, 5.0), 3.0, color);
+            }
+
+            // Conditionally render the close button (its space is reserved either way)
+            let show_close_btn = match self.close_button_visibility() {
+                CloseButtonVisibility::Always => true,
+                CloseButtonVisibility::Hover => ui.rect_contains_pointer(tab_rect),
+                CloseButtonVisibility::ActiveOnly => state.active,
+            };
+            if state.closable && show_close_btn {
                 let close_btn_rect = egui::Align2::RIGHT_CENTER
                     .align_size_within_rect(close_btn_size, tab_rect.shrink(x_margin));
+                let interact_rect = Rect::from_center_size(
+                    close_btn_rect.center(),
+                    close_btn_size.max(Vec2::splat(self.close_button_min_interact_size())),
+                )
+                .intersect(tab_rect);
+
+                // Ignore clicks during and right after a drag, which are likely accidental:
+                let recently_dragged = tiles.drag_ended_at.is_some_and(|drag_ended_at| {
+                    ui.input(|i| i.time) - drag_ended_at < self.close_button_drag_cooldown()
+                });
+                let sense = if tiles.is_dragging || recently_dragged {
+                    Sense::hover()
+                } else {
+                    Sense::click_and_drag()
+                };
 
                 // Allocate
-                let close_btn_id = ui.auto_id_with("tab_close_btn");
+                let close_btn_id = id.with("tab_close_btn");
                 let close_btn_response = ui
-                    .interact(close_btn_rect, close_btn_id, Sense::click_and_drag())
+                    .interact(interact_rect, close_btn_id, sense)
                     .on_hover_cursor(egui::CursorIcon::Default);
 
                 let visuals = ui.style().interact(&close_btn_response);
@@ -169,18 +723,8 @@ pub trait Behavior<Pane> {
                     .line_segment([rect.right_top(), rect.left_bottom()], stroke);
 
                 // Give the user a chance to react to the close button being clicked
-                // Only close if the user returns true (handled)
                 if close_btn_response.clicked() {
-                    log::debug!("Tab close requested for tile: {tile_id:?}");
-
-                    // Close the tab if the implementation wants to
-                    if self.on_tab_close(tiles, tile_id) {
-                        log::debug!("Implementation confirmed close request for tile: {tile_id:?}");
-
-                        tiles.remove(tile_id);
-                    } else {
-                        log::debug!("Implementation denied close request for tile: {tile_id:?}");
-                    }
+                    tiles.request_tab_close(self, tile_id);
                 }
             }
         }
@@ -188,7 +732,59 @@ pub trait Behavior<Pane> {
         self.on_tab_button(tiles, tile_id, tab_response)
     }
 
+    /// Show a see-through tab button for a tile that is being dragged over a tab bar,
+    /// at the place where it would be dropped.
+    ///
+    /// Returns the rect of the button. See [`Self::ghost_tab_drop_preview`].
+    fn ghost_tab_ui(
+        &mut self,
+        tiles: &Tiles<Pane>,
+        ui: &mut Ui,
+        tile_id: TileId,
+        state: &TabState,
+    ) -> Rect {
+        let text = self.tab_title_for_tile(tiles, tile_id);
+        let font_id = TextStyle::Button.resolve(ui.style());
+        let x_margin = self.tab_title_spacing(ui.visuals());
+
+        let (galley, button_width) = if let Some(fixed_width) = state.fixed_width {
+            let title_width = (fixed_width - 2.0 * x_margin).at_least(0.0);
+            let galley =
+                text.into_galley(ui, Some(egui::TextWrapMode::Truncate), title_width, font_id);
+            (galley, fixed_width)
+        } else {
+            let galley =
+                text.into_galley(ui, Some(egui::TextWrapMode::Extend), f32::INFINITY, font_id);
+            let width = galley.size().x + 2.0 * x_margin + state.extra_width.at_least(0.0);
+            (galley, width)
+        };
+        let (_, tab_rect) = ui.allocate_space(vec2(button_width, ui.available_height()));
+
+        if ui.is_rect_visible(tab_rect) {
+            let opacity = 0.5;
+            let bg_color = self.tab_bg_color(ui.visuals(), tiles, tile_id, state);
+            let stroke = self.tab_outline_stroke(ui.visuals(), tiles, tile_id, state);
+            let text_color = self.tab_text_color(ui.visuals(), tiles, tile_id, state);
+            ui.painter().rect(
+                tab_rect.shrink(0.5),
+                0.0,
+                bg_color.gamma_multiply(opacity),
+                Stroke::new(stroke.width, stroke.color.gamma_multiply(opacity)),
+            );
+            let text_position = egui::Align2::CENTER_CENTER
+                .align_size_within_rect(galley.size(), tab_rect.shrink(x_margin))
+                .min;
+            ui.painter()
+                .galley(text_position, galley, text_color.gamma_multiply(opacity));
+        }
+
+        tab_rect
+    }
+
     /// Show the ui for the tab being dragged.
+    ///
+    /// Use [`DragOrigin::from_ui`] to find out how the drag was started,
+    /// and [`GrabOffset::from_ui`] to find out where the tab was grabbed.
     fn drag_ui(&mut self, tiles: &Tiles<Pane>, ui: &mut Ui, tile_id: TileId) {
         let mut frame = egui::Frame::popup(ui.style());
         frame.fill = frame.fill.gamma_multiply(0.5); // Make see-through
@@ -209,11 +805,121 @@ pub trait Behavior<Pane> {
         button_response
     }
 
-    /// Return `false` if a given pane should be removed from its parent.
+    /// Called after a dragged tile has been dropped, and the tree updated accordingly.
+    ///
+    /// Use this to e.g. configure a container that was created by the drop.
+    /// This is called after [`Self::on_edit`] with [`EditAction::TileDropped`].
+    fn on_tile_dropped(&mut self, _tiles: &Tiles<Pane>, _drop: &DropResult) {}
+
+    /// Called when a tile got a new id, because a new container took over its old one.
+    ///
+    /// This happens when a tile is dropped onto a tile that is not a container of the right kind
+    /// (see [`DropResult::wrapped_tile`]), or when a pane gets a [`crate::Tabs`] parent
+    /// because of [`SimplificationOptions::all_panes_must_have_tabs`].
+    /// Use this to update any state you keep by [`TileId`].
+    fn on_tile_id_changed(&mut self, _old: TileId, _new: TileId) {}
+
+    /// Called when a dragged tile is released outside the tree, where it can't be dropped.
+    ///
+    /// `screen_pos` is where the pointer was released.
+    /// The default leaves the tile where it was.
+    fn on_drop_outside(
+        &mut self,
+        _tiles: &Tiles<Pane>,
+        _tile_id: TileId,
+        _screen_pos: Pos2,
+    ) -> DropOutsideAction {
+        DropOutsideAction::Ignore
+    }
+
+    /// Called when the user starts dragging a tile.
+    ///
+    /// Use this to e.g. pause expensive pane rendering, show docking guides,
+    /// or switch to a layout-editing look for the duration of the drag.
+    /// [`Self::on_drag_ended`] is always called when the drag ends.
+    fn on_drag_started(&mut self, _tiles: &Tiles<Pane>, _tile_id: TileId, _origin: DragOrigin) {}
+
+    /// Called when the drag of a tile ends, however it ends.
+    ///
+    /// On a drop this is called after [`Self::on_tile_dropped`].
+    fn on_drag_ended(&mut self, _tiles: &Tiles<Pane>, _tile_id: TileId, _end: DragEnd) {}
+
+    /// Can the pane be copied with [`Self::clone_pane`]?
+    ///
+    /// Only then does holding down `Ctrl` (`Cmd` on Mac) or `Alt` while dragging the pane
+    /// show the copy cursor and indicator, and copy the pane on drop.
+    /// The default is `false`, meaning panes are always moved.
+    fn can_clone_pane(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+        false
+    }
+
+    /// Create a copy of a pane, for when the user drops a pane while holding down `Ctrl` (`Cmd` on Mac) or `Alt`.
+    ///
+    /// The copy is then inserted where the pane was dropped, and the original pane is left where it was.
+    /// This is only called if [`Self::can_clone_pane`] returns `true`.
+    /// If this returns `None`, the pane is moved instead.
+    fn clone_pane(&mut self, _pane: &Pane) -> Option<Pane> {
+        None
+    }
+
+    /// May the given container be converted from one kind to another with [`crate::Tree::set_container_kind`]?
+    fn allow_kind_change(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _tile_id: TileId,
+        _from: ContainerKind,
+        _to: ContainerKind,
+    ) -> bool {
+        true
+    }
+
+    /// Called during GC with the tiles that are not reachable from the root.
+    ///
+    /// This should only happen if the tree was set up in a bad state,
+    /// or restored from a bad state via serde.
+    /// Only the top-most unreachable tiles are listed; their children follow them.
+    ///
+    /// The default drops them.
+    fn on_unreachable_tiles(
+        &mut self,
+        _tiles: &Tiles<Pane>,
+        _unreachable: Vec<TileId>,
+    ) -> OrphanPolicy {
+        OrphanPolicy::Drop
+    }
+
+    /// Return `false` if a given pane should be removed from its parent,
+    /// or replaced by a placeholder, see [`Self::keep_placeholder`].
     fn retain_pane(&mut self, _pane: &Pane) -> bool {
         true
     }
 
+    /// Should a pane rejected by [`Self::retain_pane`] be turned into a [`Tile::Placeholder`]
+    /// instead of being removed?
+    ///
+    /// The placeholder keeps the place of the pane, e.g. for a pane the app can no longer build,
+    /// see [`crate::Tree::replace_pane_with_placeholder`].
+    fn keep_placeholder(&mut self, _pane: &Pane) -> bool {
+        false
+    }
+
+    /// Lightweight view state of a pane (scroll offset, selection, …) to persist with the tree.
+    ///
+    /// This is called by [`crate::Tree::save_pane_states`], and the state is
+    /// given back to [`Self::restore_pane_state`] after the tree has been deserialized.
+    /// This way also panes that are not serializable can keep some state.
+    #[cfg(feature = "serde")]
+    fn save_pane_state(&mut self, _pane: &Pane) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Apply the state saved by [`Self::save_pane_state`] to a pane.
+    ///
+    /// This is called by [`crate::Tree::restore_pane_states`],
+    /// and when a [`Tile::Placeholder`] is turned into a pane.
+    #[cfg(feature = "serde")]
+    fn restore_pane_state(&mut self, _pane: &mut Pane, _state: serde_json::Value) {}
+
     /// Adds some UI to the top right of each tab bar.
     ///
     /// You can use this to, for instance, add a button for adding new tabs.
@@ -234,28 +940,211 @@ pub trait Behavior<Pane> {
         // }
     }
 
+    /// Adds some UI to the top left of each tab bar, before the tabs and the scroll arrows.
+    ///
+    /// You can use this to, for instance, add a menu button or an icon for the container.
+    ///
+    /// The widgets will be added left-to-right.
+    /// The tabs are scrolled within the space that is left over.
+    ///
+    /// `_scroll_offset` is a mutable reference to the tab scroll value.
+    /// Adding to this value will scroll the tabs to the right, subtracting to the left.
+    fn top_bar_left_ui(
+        &mut self,
+        _tiles: &Tiles<Pane>,
+        _ui: &mut Ui,
+        _tile_id: TileId,
+        _tabs: &crate::Tabs,
+        _scroll_offset: &mut f32,
+    ) {
+    }
+
+    /// Adds some UI to the content area of a [`crate::Tabs`] container that has no visible tabs,
+    /// e.g. because all its children are hidden, or because it is a
+    /// [`crate::Tabs::document_area`] with no open documents.
+    ///
+    /// You can use this to, for instance, show a hint or a button for showing the hidden panes,
+    /// or a welcome screen.
+    /// By default the area is left empty.
+    fn tabs_empty_content_ui(&mut self, _ui: &mut Ui, _tile_id: TileId) {}
+
+    /// How long, in seconds, something needs to be dragged over an inactive tab before it is activated.
+    ///
+    /// This lets the user drop things into other tabs, without every tab they pass over becoming active.
+    /// If the drag leaves the [`crate::Tabs`] container without dropping, the original tab is activated again.
+    ///
+    /// The default of `0.0` activates tabs as soon as something is dragged over them.
+    fn drag_tab_activate_delay(&self) -> f32 {
+        0.0
+    }
+
+    /// What scrolling the mouse wheel over a tab bar does.
+    ///
+    /// Both vertical and horizontal scrolling are used.
+    fn tab_bar_wheel_action(&self) -> TabBarWheel {
+        TabBarWheel::ScrollStrip
+    }
+
+    /// The width of the arrows for scrolling the tabs, shown when they don't all fit in the tab bar.
+    ///
+    /// Return `0.0` to hide the arrows, and fade out the edges of the tabs instead.
+    /// The tabs can then still be scrolled with the mouse wheel (see [`Self::tab_bar_wheel_action`]).
+    fn scroll_arrow_width(&self, _style: &egui::Style) -> f32 {
+        20.0
+    }
+
+    /// Should both scroll arrows be shown whenever the tabs don't fit,
+    /// with the one that can't scroll any further disabled?
+    ///
+    /// This keeps the tabs from shifting as the arrows come and go.
+    /// By default, each arrow is only shown while the tabs can be scrolled in its direction.
+    fn show_disabled_scroll_arrows(&self) -> bool {
+        false
+    }
+
+    /// Show an arrow for scrolling the tabs, [`Self::scroll_arrow_width`] wide.
+    ///
+    /// Clicking the returned [`Response`] scrolls the tabs.
+    /// The arrow is disabled when the tabs can't be scrolled any further in its direction
+    /// (see [`Self::show_disabled_scroll_arrows`]).
+    fn scroll_arrow_ui(&mut self, ui: &mut Ui, arrow: TabScrollArrow, enabled: bool) -> Response {
+        let text = match arrow {
+            TabScrollArrow::Left => "⏴",
+            TabScrollArrow::Right => "⏵",
+        };
+        let size = vec2(self.scroll_arrow_width(ui.style()), 20.0);
+        ui.add_enabled_ui(enabled, |ui| ui.add_sized(size, egui::Button::new(text)))
+            .inner
+    }
+
+    /// Where to put the tabs of this [`crate::Tabs`] container when they don't fill the tab bar.
+    ///
+    /// Pinned tabs always stay on the left.
+    fn tab_alignment(&self, _tile_id: TileId) -> TabAlignment {
+        TabAlignment::Left
+    }
+
     /// The height of the bar holding tab titles.
     fn tab_bar_height(&self, _style: &egui::Style) -> f32 {
         24.0
     }
 
+    /// Should the tab bar of this [`crate::Tabs`] container be painted on top of the active tab,
+    /// instead of taking up space above it?
+    ///
+    /// The active tab then gets the full rect of the container.
+    /// You probably want to make [`Self::tab_bar_color`] translucent when using this.
+    fn tab_bar_overlay(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> bool {
+        false
+    }
+
     /// Width of the gap between tiles in a horizontal or vertical layout,
     /// and between rows/columns in a grid layout.
     fn gap_width(&self, _style: &egui::Style) -> f32 {
         1.0
     }
 
+    /// Round the edges of tiles and the positions of the resize lines to physical pixels?
+    ///
+    /// This makes gaps and lines look crisp and consistent at non-integer zoom factors.
+    fn pixel_snap(&self) -> bool {
+        true
+    }
+
+    /// The mouse cursor to show when hovering or dragging a resize line.
+    ///
+    /// [`LinearDir::Horizontal`] is for lines between tiles laid out horizontally, i.e. vertical lines.
+    fn resize_cursor(&self, dir: LinearDir) -> egui::CursorIcon {
+        match dir {
+            LinearDir::Horizontal => egui::CursorIcon::ResizeHorizontal,
+            LinearDir::Vertical => egui::CursorIcon::ResizeVertical,
+        }
+    }
+
+    /// The mouse cursor to show when hovering or dragging the intersection of
+    /// a column and a row boundary in a grid.
+    fn grid_intersection_cursor(&self) -> egui::CursorIcon {
+        egui::CursorIcon::ResizeNwSe
+    }
+
+    /// The mouse cursor to show while dragging a tile, or a grid column or row header.
+    fn drag_cursor(&self) -> egui::CursorIcon {
+        egui::CursorIcon::Grabbing
+    }
+
+    /// The mouse cursor to show while dragging a pane that will be copied when dropped.
+    ///
+    /// See [`Self::clone_pane`].
+    fn copy_drag_cursor(&self) -> egui::CursorIcon {
+        egui::CursorIcon::Copy
+    }
+
+    /// What should happen when the user double-clicks a resize line of a linear or grid container?
+    ///
+    /// The `boundary_index` is the same as for [`Self::resize_stroke`].
+    fn splitter_double_click_action(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _container_id: TileId,
+        _boundary_index: usize,
+    ) -> SplitterDoubleClick {
+        SplitterDoubleClick::Equalize
+    }
+
+    /// Can the user resize the children of this linear or grid container?
+    ///
+    /// If `false`, the lines between the children are still painted, but cannot be dragged.
+    fn is_resizable(&self, _tiles: &Tiles<Pane>, _container_id: TileId) -> bool {
+        true
+    }
+
     /// No child should shrink below this width nor height.
     fn min_size(&self) -> f32 {
         32.0
     }
 
+    /// The smallest size of a pane, used with [`OverflowPolicy::Scroll`].
+    ///
+    /// The default is [`Self::min_size`] in both directions.
+    fn min_pane_size(&self, _tiles: &Tiles<Pane>, _tile_id: TileId) -> Vec2 {
+        Vec2::splat(self.min_size())
+    }
+
+    /// What to do when the tree is too small to give every pane its [`Self::min_pane_size`].
+    fn overflow_policy(&self) -> OverflowPolicy {
+        OverflowPolicy::Squish
+    }
+
+    /// How many seconds to animate a tile appearing or disappearing in a linear container.
+    ///
+    /// When a tile is made visible or invisible (e.g. with [`crate::Tree::set_visible`]),
+    /// the shares of its neighbors are smoothly animated instead of snapping.
+    ///
+    /// The default is `0.0`, which means no animation.
+    fn visibility_animation_time(&self) -> f32 {
+        0.0
+    }
+
+    /// Fade in tiles that are appearing?
+    ///
+    /// Only used if [`Self::visibility_animation_time`] is positive.
+    fn fade_in_appearing_tiles(&self) -> bool {
+        true
+    }
+
     /// Show we preview panes that are being dragged,
     /// i.e. show their ui in the region where they will end up?
     fn preview_dragged_panes(&self) -> bool {
         false
     }
 
+    /// When a tile from elsewhere is dragged over a tab bar, show where it would be dropped
+    /// as a tab button (see [`Self::ghost_tab_ui`]), with the other tabs moving aside to make room,
+    /// instead of with [`Self::paint_drag_preview`]?
+    fn ghost_tab_drop_preview(&self) -> bool {
+        true
+    }
+
     /// Cover the tile that is being dragged with this color.
     fn dragged_overlay_color(&self, visuals: &Visuals) -> Color32 {
         visuals.panel_fill.gamma_multiply(0.5)
@@ -266,6 +1155,76 @@ pub trait Behavior<Pane> {
         SimplificationOptions::default()
     }
 
+    /// Should [`crate::Tree::ui`] simplify the tree and garbage-collect unreachable tiles each frame?
+    ///
+    /// Return `false` if you edit the tree in several steps across frames,
+    /// and the intermediate states must not be simplified away.
+    /// Call [`crate::Tree::simplify`] and [`crate::Tree::gc`] yourself when you are done.
+    fn auto_simplify(&self) -> bool {
+        true
+    }
+
+    /// The keyboard shortcuts handled by [`crate::Tree::ui`].
+    ///
+    /// They are only handled while the tree is hovered or was the last thing clicked,
+    /// and not while a widget (e.g. a text edit) has keyboard focus.
+    /// The default is no shortcuts. Return [`TreeKeymap::standard`] for a common set.
+    fn keybindings(&self) -> TreeKeymap {
+        TreeKeymap::default()
+    }
+
+    /// Can the dragged tile be dropped into a container of the given kind?
+    ///
+    /// For instance, you can return `kind == ContainerKind::Tabs` for [`DragOrigin::TabButton`]
+    /// to only allow tabs to be moved to other tab bars.
+    fn is_drop_allowed(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _dragged_tile_id: TileId,
+        _origin: DragOrigin,
+        _kind: ContainerKind,
+    ) -> bool {
+        true
+    }
+
+    /// The kind of root container to create when a tile is dropped on a tree without a root.
+    ///
+    /// `None` means an empty tree does not accept any drops.
+    /// The kind must also be allowed by [`Self::is_drop_allowed`].
+    fn empty_tree_root_kind(&self) -> Option<ContainerKind> {
+        Some(ContainerKind::Tabs)
+    }
+
+    /// Does this pane handle drops of the dragged tile itself?
+    ///
+    /// If `true`, dropping the dragged tile onto the pane does not change the layout.
+    /// Instead, [`crate::PaneDrop::from_ui`] tells [`Self::pane_ui`] about the drop,
+    /// e.g. so that a comparison view can add the dropped content to itself.
+    fn pane_accepts_drop(
+        &self,
+        _tiles: &Tiles<Pane>,
+        _tile_id: TileId,
+        _dragged_tile_id: TileId,
+    ) -> bool {
+        false
+    }
+
+    /// The style of the [`Ui`] of a pane, e.g. a monospace style for a terminal pane.
+    ///
+    /// `None` (the default) means the pane uses the style of the tree.
+    /// The style only applies to the contents of the pane, not to its tab.
+    fn style_for_pane(&self, _tile_id: TileId) -> Option<Arc<egui::Style>> {
+        None
+    }
+
+    /// Adjust how the [`Ui`] of a pane is created, before [`Self::pane_ui`] is called.
+    ///
+    /// The builder already has the layer, `max_rect` and [`crate::TileUiTag`] of the pane set,
+    /// and the style from [`Self::style_for_pane`], if any.
+    /// You can use this to e.g. put some panes on a different layer,
+    /// change their style, or disable them.
+    fn configure_pane_ui(&mut self, _ui_builder: &mut egui::UiBuilder, _tile_id: TileId) {}
+
     /// Add some custom painting on top of a tile (container or pane), e.g. draw an outline on top of it.
     fn paint_on_top_of_tile(
         &self,
@@ -276,8 +1235,57 @@ pub trait Behavior<Pane> {
     ) {
     }
 
+    /// The outline of the pane under the mouse pointer, e.g. to let the user pick a pane.
+    ///
+    /// The default is [`Stroke::NONE`], i.e. no highlight.
+    /// Nothing is highlighted while a tile is dragged.
+    /// The hovered pane is also returned in [`crate::TreeResponse::hovered_pane`].
+    fn hover_highlight_stroke(&self, _visuals: &Visuals) -> Stroke {
+        Stroke::NONE
+    }
+
+    /// A tint painted on top of the pane under the mouse pointer, see [`Self::hover_highlight_stroke`].
+    ///
+    /// The default is [`Color32::TRANSPARENT`], i.e. no tint.
+    fn hover_highlight_fill(&self, _visuals: &Visuals) -> Color32 {
+        Color32::TRANSPARENT
+    }
+
+    /// Return `true` to have [`Self::paint_on_top_of_tree`] called each frame.
+    ///
+    /// Off by default, since it needs a [`TreeGeometry`] of the whole tree.
+    fn paints_on_top_of_tree(&self) -> bool {
+        false
+    }
+
+    /// Add some custom painting on top of the whole tree, after all tiles and the drag preview,
+    /// e.g. alignment guides or highlights that span several tiles.
+    ///
+    /// Only called if [`Self::paints_on_top_of_tree`] returns `true`.
+    /// `geometry` is where all tiles were laid out this frame, see [`crate::Tree::geometry_snapshot`].
+    /// The painter is clipped to the rect of the tree.
+    fn paint_on_top_of_tree(
+        &self,
+        _painter: &egui::Painter,
+        _style: &egui::Style,
+        _geometry: &TreeGeometry,
+    ) {
+    }
+
     /// The stroke used for the lines in horizontal, vertical, and grid layouts.
-    fn resize_stroke(&self, style: &egui::Style, resize_state: ResizeState) -> Stroke {
+    ///
+    /// `container_id` is the container the line is in, and `boundary_index` is which of its lines it is,
+    /// counting from the left/top. For grids, the column boundaries are counted first,
+    /// followed by the row boundaries.
+    ///
+    /// Return [`Stroke::NONE`] to hide the line.
+    fn resize_stroke(
+        &self,
+        style: &egui::Style,
+        resize_state: ResizeState,
+        _container_id: TileId,
+        _boundary_index: usize,
+    ) -> Stroke {
         match resize_state {
             ResizeState::Idle => {
                 Stroke::new(self.gap_width(style), self.tab_bar_color(&style.visuals))
@@ -331,6 +1339,20 @@ pub trait Behavior<Pane> {
         }
     }
 
+    /// How many seconds the indicator under the active tab takes to slide to a newly activated tab.
+    ///
+    /// The default is `0.0`, which means no indicator is shown.
+    /// See also [`Self::active_tab_indicator_stroke`].
+    fn active_tab_indicator_animation_time(&self) -> f32 {
+        0.0
+    }
+
+    /// Stroke of the line under the active tab,
+    /// if [`Self::active_tab_indicator_animation_time`] is positive.
+    fn active_tab_indicator_stroke(&self, visuals: &Visuals) -> Stroke {
+        Stroke::new(2.0, visuals.selection.bg_fill)
+    }
+
     /// Stroke of the line separating the tab title bar and the content of the active tab.
     fn tab_bar_hline_stroke(&self, visuals: &Visuals) -> Stroke {
         Stroke::new(1.0, visuals.widgets.noninteractive.bg_stroke.color)
@@ -364,6 +1386,66 @@ pub trait Behavior<Pane> {
         visuals.selection.stroke.color.gamma_multiply(0.5)
     }
 
+    /// When drag-and-dropping a tile into a [`crate::Grid`], this is painted on top of [`Self::paint_drag_preview`].
+    ///
+    /// The default outlines the destination cell,
+    /// and dims the tile in it if it will swap places with the dragged tile,
+    /// or marks the leading edge of the cell if the dragged tile will be inserted before it.
+    fn paint_grid_drop_preview(
+        &self,
+        visuals: &Visuals,
+        painter: &egui::Painter,
+        preview: &GridDropPreview,
+    ) {
+        let stroke = Stroke::new(2.0, self.drag_preview_stroke(visuals).color);
+        painter.rect_stroke(preview.cell_rect, 2.0, stroke);
+
+        match preview.drop {
+            GridDrop::Empty => {}
+            GridDrop::Swap => {
+                if let Some(occupant_rect) = preview.occupant_rect {
+                    painter.rect_filled(occupant_rect, 0.0, Color32::from_black_alpha(96));
+                }
+            }
+            GridDrop::Insert => {
+                let rect = preview.cell_rect;
+                painter.line_segment(
+                    [rect.left_top(), rect.left_bottom()],
+                    Stroke::new(4.0, stroke.color),
+                );
+            }
+        }
+    }
+
+    /// How thick the drop zones between the children of a [`crate::Linear`] container are, in points.
+    ///
+    /// Increase this for touch screens, or decrease it for dense layouts.
+    fn linear_drop_zone_thickness(&self) -> f32 {
+        12.0
+    }
+
+    /// How wide the drop zones between the tab buttons of a [`crate::Tabs`] container are, in points.
+    fn tab_drop_zone_thickness(&self) -> f32 {
+        6.0
+    }
+
+    /// When dragging a tile over a pane, its center is a drop zone for putting both in tabs.
+    ///
+    /// The preview of that drop leaves out a band this thick at the top of the pane, where the new tab bar goes.
+    /// Changing it also changes how close to the top of the pane the drop zone is.
+    /// Defaults to [`Self::tab_bar_height`].
+    fn tab_bar_drop_band_thickness(&self, style: &egui::Style) -> f32 {
+        self.tab_bar_height(style)
+    }
+
+    /// When dragging a tile over another tile, the edges of the target tile are drop zones for splitting it.
+    ///
+    /// This is how large a fraction of the target tile each such zone covers,
+    /// which is used both for the preview and for picking the closest drop zone.
+    fn split_drop_fraction(&self) -> f32 {
+        0.5
+    }
+
     /// When drag-and-dropping a tile, how do we preview what is about to happen?
     fn paint_drag_preview(
         &self,
@@ -383,6 +1465,70 @@ pub trait Behavior<Pane> {
         painter.rect(preview_rect, 1.0, preview_color, preview_stroke);
     }
 
+    /// When drag-and-dropping a pane that will be copied, paint this on top of [`Self::paint_drag_preview`].
+    ///
+    /// See [`Self::clone_pane`].
+    fn paint_copy_drop_indicator(
+        &self,
+        visuals: &Visuals,
+        painter: &egui::Painter,
+        preview_rect: Rect,
+    ) {
+        let radius = 8.0;
+        let center = preview_rect.right_top() + vec2(-radius - 4.0, radius + 4.0);
+        if !preview_rect.contains(center) {
+            return;
+        }
+        let stroke = self.drag_preview_stroke(visuals);
+        painter.circle_filled(center, radius, stroke.color);
+        let arm = 0.5 * radius;
+        let plus_stroke = Stroke::new(2.0, visuals.extreme_bg_color);
+        painter.line_segment(
+            [center - vec2(arm, 0.0), center + vec2(arm, 0.0)],
+            plus_stroke,
+        );
+        painter.line_segment(
+            [center - vec2(0.0, arm), center + vec2(0.0, arm)],
+            plus_stroke,
+        );
+    }
+
+    /// The height of the header strip above the columns of a grid.
+    ///
+    /// The default is `0.0`, meaning no column headers.
+    /// See [`Self::grid_col_header_ui`].
+    fn grid_col_header_height(&self, _tiles: &Tiles<Pane>, _grid_id: TileId) -> f32 {
+        0.0
+    }
+
+    /// The width of the header strip to the left of the rows of a grid.
+    ///
+    /// The default is `0.0`, meaning no row headers.
+    /// See [`Self::grid_row_header_ui`].
+    fn grid_row_header_width(&self, _tiles: &Tiles<Pane>, _grid_id: TileId) -> f32 {
+        0.0
+    }
+
+    /// Show the header of a grid column.
+    ///
+    /// Only called if [`Self::grid_col_header_height`] is positive.
+    /// The user can drag the header to move the whole column.
+    fn grid_col_header_ui(&mut self, ui: &mut Ui, _grid_id: TileId, col: usize) {
+        ui.centered_and_justified(|ui| {
+            ui.add(egui::Label::new(format!("{}", col + 1)).selectable(false));
+        });
+    }
+
+    /// Show the header of a grid row.
+    ///
+    /// Only called if [`Self::grid_row_header_width`] is positive.
+    /// The user can drag the header to move the whole row.
+    fn grid_row_header_ui(&mut self, ui: &mut Ui, _grid_id: TileId, row: usize) {
+        ui.centered_and_justified(|ui| {
+            ui.add(egui::Label::new(format!("{}", row + 1)).selectable(false));
+        });
+    }
+
     /// How many columns should we use for a [`crate::Grid`] put into [`crate::GridLayout::Auto`]?
     ///
     /// The default heuristic tried to find a good column count that results in a per-tile aspect-ratio
@@ -399,6 +1545,13 @@ pub trait Behavior<Pane> {
         )
     }
 
+    /// When to collapse the holes that drag-and-drop leaves in the given [`crate::Grid`].
+    ///
+    /// Only used for grids whose [`crate::Grid::hole_collapse`] is `None`.
+    fn grid_hole_collapse(&self, _grid_id: TileId) -> crate::GridHoleCollapse {
+        crate::GridHoleCollapse::Auto
+    }
+
     /// When using [`crate::GridLayout::Auto`], what is the ideal aspect ratio of a tile?
     fn ideal_tile_aspect_ratio(&self) -> f32 {
         4.0 / 3.0
@@ -409,6 +1562,28 @@ pub trait Behavior<Pane> {
     /// Called if the user edits the tree somehow, e.g. changes the size of some container,
     /// clicks a tab, or drags a tile.
     fn on_edit(&mut self, _edit_action: EditAction) {}
+
+    /// Called when a tile starts or stops being shown,
+    /// e.g. because of [`crate::Tree::set_visible`], or because the tab it is in (or one of its ancestors) was activated or deactivated.
+    ///
+    /// This is called during [`crate::Tree::ui`], for every tile that was shown last frame but not this frame, or vice versa.
+    /// Use it to e.g. pause background work of panes that can't be seen.
+    fn on_visibility_changed(&mut self, _tiles: &Tiles<Pane>, _tile_id: TileId, _visible: bool) {}
+
+    /// Called when a [`crate::Tabs`] container is shown with another active tab than last time,
+    /// for whatever reason the tab was activated: a click, hovering a dragged tile over it,
+    /// the previously active tab being removed, or a programmatic change.
+    ///
+    /// `previous` is `None` the first time the container is shown with an active tab.
+    /// Use this to e.g. lazily load the contents of a pane when it is first shown.
+    fn on_tab_selected(
+        &mut self,
+        _tiles: &Tiles<Pane>,
+        _tabs_tile_id: TileId,
+        _previous: Option<TileId>,
+        _next: TileId,
+    ) {
+    }
 }
 
 /// How many columns should we use to fit `n` children in a grid?
@@ -0,0 +1,287 @@
+//! Tools for measuring the performance of a [`Tree`], without a window.
+//!
+//! Requires the `bench` feature.
+//!
+//! This is what the benchmarks in `benches/` use,
+//! and you can use it to profile trees with your own panes and [`Behavior`]:
+//!
+//! ```
+//! use egui_tiles::bench::{synthetic_tree, Harness};
+//!
+//! struct MyBehavior;
+//!
+//! impl egui_tiles::Behavior<String> for MyBehavior {
+//!     fn pane_ui(&mut self, ui: &mut egui::Ui, _: egui_tiles::TileId, pane: &mut String) -> egui_tiles::UiResponse {
+//!         ui.label(pane.as_str());
+//!         Default::default()
+//!     }
+//!
+//!     fn tab_title_for_pane(&mut self, pane: &String) -> egui::WidgetText {
+//!         pane.as_str().into()
+//!     }
+//! }
+//!
+//! let mut tree = synthetic_tree("my_tree", 100, |i| format!("Pane {i}"));
+//! for measurement in Harness::default().measure_tree(&mut tree, &mut MyBehavior, 10) {
+//!     println!("{measurement}");
+//! }
+//! ```
+
+use std::time::{Duration, Instant};
+
+use egui::{Pos2, Rect, Vec2};
+
+use crate::{Behavior, Container, ContainerKind, Tile, TileId, Tiles, Tree};
+
+/// Number of children of each container in a [`synthetic_tree`].
+const BRANCHING: usize = 8;
+
+/// Create a tree with roughly `num_tiles` tiles, for benchmarking.
+///
+/// The panes are grouped into containers of up to eight children,
+/// which are grouped into containers in turn, until there is a single root.
+/// The containers cycle through all the [`ContainerKind`]s.
+///
+/// `make_pane` is called with the index of each pane.
+pub fn synthetic_tree<Pane>(
+    id: impl Into<egui::Id>,
+    num_tiles: usize,
+    mut make_pane: impl FnMut(usize) -> Pane,
+) -> Tree<Pane> {
+    let mut tiles = Tiles::default();
+
+    // Each level has 1/BRANCHING as many tiles as the one below it:
+    let num_panes = (num_tiles * (BRANCHING - 1) / BRANCHING).max(1);
+    let mut level: Vec<TileId> = (0..num_panes)
+        .map(|i| tiles.insert_pane(make_pane(i)))
+        .collect();
+
+    let mut num_containers = 0;
+    while level.len() > 1 {
+        level = level
+            .chunks(BRANCHING)
+            .map(|children| {
+                let kind = ContainerKind::ALL[num_containers % ContainerKind::ALL.len()];
+                num_containers += 1;
+                tiles.insert_container(Container::new(kind, children.to_vec()))
+            })
+            .collect();
+    }
+
+    Tree::new(id, level[0], tiles)
+}
+
+/// The timing of an operation, see [`measure`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    /// What was measured.
+    pub name: String,
+
+    /// Number of tiles in the tree that was measured.
+    pub num_tiles: usize,
+
+    /// How many times the operation was run.
+    pub iterations: u32,
+
+    /// Mean time of a single run.
+    pub mean: Duration,
+
+    /// Fastest run.
+    pub min: Duration,
+
+    /// Slowest run.
+    pub max: Duration,
+}
+
+impl std::fmt::Display for Measurement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            name,
+            num_tiles,
+            iterations,
+            mean,
+            min,
+            max,
+        } = self;
+        write!(
+            f,
+            "{name:<12} {num_tiles:>6} tiles: mean {mean:>10.1?}, min {min:>10.1?}, max {max:>10.1?} ({iterations} runs)"
+        )
+    }
+}
+
+/// Run `f` `iterations` times (at least once) and time it.
+pub fn measure(
+    name: impl Into<String>,
+    num_tiles: usize,
+    iterations: u32,
+    mut f: impl FnMut(),
+) -> Measurement {
+    let iterations = iterations.max(1);
+    let mut total = Duration::ZERO;
+    let mut min = Duration::MAX;
+    let mut max = Duration::ZERO;
+    for _ in 0..iterations {
+        let start = Instant::now();
+        f();
+        let elapsed = start.elapsed();
+        total += elapsed;
+        min = min.min(elapsed);
+        max = max.max(elapsed);
+    }
+    Measurement {
+        name: name.into(),
+        num_tiles,
+        iterations,
+        mean: total / iterations,
+        min,
+        max,
+    }
+}
+
+/// Lay out the tree in `rect` without showing it, i.e. only compute where each tile goes.
+///
+/// This is the layout pass at the start of [`Tree::ui`], which is what [`Tiles::set_dense_storage`] speeds up.
+pub fn layout_tree<Pane>(
+    tree: &mut Tree<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    style: &egui::Style,
+    rect: Rect,
+) {
+    if let Some(root) = tree.root {
+        tree.tiles.layout_root(style, behavior, rect, root);
+    }
+}
+
+/// Runs egui frames without a window.
+pub struct Harness {
+    pub ctx: egui::Context,
+
+    /// Size of the screen, in points.
+    pub screen_size: Vec2,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self {
+            ctx: egui::Context::default(),
+            screen_size: egui::vec2(1920.0, 1080.0),
+        }
+    }
+}
+
+impl Harness {
+    /// Run a single egui frame, showing the tree in a central panel.
+    pub fn run_frame<Pane>(&self, tree: &mut Tree<Pane>, behavior: &mut dyn Behavior<Pane>) {
+        self.run_frame_with_events(tree, behavior, vec![]);
+    }
+
+    /// Run a single egui frame where `tile_id` is being dragged over `pointer_pos`.
+    ///
+    /// The tile is never dropped: use [`Self::cancel_drag`] when you are done.
+    pub fn run_drag_frame<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        pointer_pos: Pos2,
+    ) {
+        let mut events = vec![egui::Event::PointerMoved(pointer_pos)];
+        if tree.dragged_id(&self.ctx) != Some(tile_id) {
+            tree.start_drag(&self.ctx, tile_id);
+            // egui stops all drags when no button is held down:
+            events.push(pointer_button_event(pointer_pos, true));
+        }
+        self.run_frame_with_events(tree, behavior, events);
+    }
+
+    /// Stop a drag started by [`Self::run_drag_frame`], without dropping the tile.
+    pub fn cancel_drag<Pane>(&self, tree: &mut Tree<Pane>, behavior: &mut dyn Behavior<Pane>) {
+        self.ctx.stop_dragging();
+        let pointer_pos = self
+            .ctx
+            .input(|i| i.pointer.latest_pos())
+            .unwrap_or_default();
+        self.run_frame_with_events(
+            tree,
+            behavior,
+            vec![pointer_button_event(pointer_pos, false)],
+        );
+    }
+
+    fn run_frame_with_events<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        events: Vec<egui::Event>,
+    ) {
+        let input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, self.screen_size)),
+            events,
+            ..Default::default()
+        };
+        let _output = self.ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                tree.ui(behavior, ui);
+            });
+        });
+    }
+
+    /// Measure the most common operations on the tree:
+    ///
+    /// * `ui`: a whole frame of [`Tree::ui`]
+    /// * `simplify`: [`Tree::simplify`] with [`Behavior::simplification_options`]
+    /// * `gc`: [`Tree::gc`]
+    /// * `drag_hover`: a frame of [`Tree::ui`] while a pane is dragged across the tree
+    ///
+    /// Each operation is run `iterations` times, after a warm-up frame.
+    pub fn measure_tree<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        iterations: u32,
+    ) -> Vec<Measurement> {
+        let num_tiles = tree.tiles.len();
+        self.run_frame(tree, behavior); // warm up, so that all rects are known
+
+        let mut measurements = vec![measure("ui", num_tiles, iterations, || {
+            self.run_frame(tree, behavior);
+        })];
+
+        let options = behavior.simplification_options();
+        measurements.push(measure("simplify", num_tiles, iterations, || {
+            tree.simplify(&options);
+        }));
+
+        measurements.push(measure("gc", num_tiles, iterations, || {
+            tree.gc(behavior);
+        }));
+
+        let dragged = tree.tiles.iter().find_map(|(&tile_id, tile)| {
+            (matches!(tile, Tile::Pane(_)) && !tree.is_root(tile_id)).then_some(tile_id)
+        });
+        if let Some(dragged) = dragged {
+            let screen = Rect::from_min_size(Pos2::ZERO, self.screen_size);
+            let mut i = 0;
+            measurements.push(measure("drag_hover", num_tiles, iterations, || {
+                // Sweep the pointer diagonally across the screen:
+                let t = (i % 100) as f32 / 100.0;
+                i += 1;
+                let pointer_pos = screen.lerp_inside(egui::vec2(t, t));
+                self.run_drag_frame(tree, behavior, dragged, pointer_pos);
+            }));
+            self.cancel_drag(tree, behavior);
+        }
+
+        measurements
+    }
+}
+
+fn pointer_button_event(pos: Pos2, pressed: bool) -> egui::Event {
+    egui::Event::PointerButton {
+        pos,
+        button: egui::PointerButton::Primary,
+        pressed,
+        modifiers: Default::default(),
+    }
+}
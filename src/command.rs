@@ -0,0 +1,301 @@
+use std::sync::mpsc;
+
+use egui::mutex::Mutex;
+
+use crate::behavior::EditAction;
+use crate::{Behavior, TileId, Tree};
+
+/// A change to a [`Tree`] that is applied later, see [`Tree::queue`] and [`TreeCommandSender`].
+#[derive(Clone, Debug)]
+pub enum TreeCommand<Pane> {
+    /// Move a tile to the given index of a container, see [`Tree::move_tile_to_container`].
+    Move {
+        tile_id: TileId,
+        container_id: TileId,
+        index: usize,
+    },
+
+    /// Close a tile like the close button of its tab does,
+    /// asking [`Behavior::on_tab_close`] first,
+    /// and then hiding it if [`Behavior::hide_on_close`], and otherwise removing it.
+    Close(TileId),
+
+    /// Insert a new pane into a container, or into the root if `container_id` is `None`.
+    ///
+    /// If that is a pane, it is put in tabs together with the new pane.
+    /// The pane becomes the root of an empty tree.
+    Insert {
+        pane: Pane,
+        container_id: Option<TileId>,
+
+        /// Where among the children of the container, or last if `None`.
+        index: Option<usize>,
+    },
+
+    /// Make the tile visible by activating it and all its parent tabs, see [`Tree::make_active`].
+    Activate(TileId),
+}
+
+/// Sends [`TreeCommand`]s to a [`Tree`] from anywhere, e.g. from a background task.
+///
+/// Created with [`Tree::command_sender`]. The commands are applied at the start of the next
+/// [`Tree::ui`], or by [`Tree::apply_queued_commands`].
+pub struct TreeCommandSender<Pane> {
+    sender: mpsc::Sender<TreeCommand<Pane>>,
+    repaint: Option<egui::Context>,
+}
+
+impl<Pane> Clone for TreeCommandSender<Pane> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            repaint: self.repaint.clone(),
+        }
+    }
+}
+
+impl<Pane> std::fmt::Debug for TreeCommandSender<Pane> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TreeCommandSender").finish_non_exhaustive()
+    }
+}
+
+impl<Pane> TreeCommandSender<Pane> {
+    /// Request a repaint of the given context after each command, so that it is applied right away.
+    pub fn with_repaint(mut self, ctx: egui::Context) -> Self {
+        self.repaint = Some(ctx);
+        self
+    }
+
+    /// Queue a command for the tree.
+    ///
+    /// Returns `false` if the tree no longer exists.
+    pub fn send(&self, command: TreeCommand<Pane>) -> bool {
+        let sent = self.sender.send(command).is_ok();
+        if sent {
+            if let Some(ctx) = &self.repaint {
+                ctx.request_repaint();
+            }
+        }
+        sent
+    }
+}
+
+/// The commands waiting to be applied to a [`Tree`].
+pub(crate) struct CommandQueue<Pane> {
+    sender: mpsc::Sender<TreeCommand<Pane>>,
+
+    /// Behind a mutex only to make the queue `Sync`.
+    receiver: Mutex<mpsc::Receiver<TreeCommand<Pane>>>,
+}
+
+impl<Pane> Default for CommandQueue<Pane> {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Mutex::new(receiver),
+        }
+    }
+}
+
+/// A clone of a tree gets its own, empty queue.
+impl<Pane> Clone for CommandQueue<Pane> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+/// The queued commands are not part of the state of the tree.
+impl<Pane> PartialEq for CommandQueue<Pane> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<Pane> CommandQueue<Pane> {
+    fn take(&self) -> Vec<TreeCommand<Pane>> {
+        self.receiver.lock().try_iter().collect()
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Apply the command at the start of the next [`Self::ui`].
+    ///
+    /// Use [`Self::command_sender`] to queue commands from other threads.
+    /// Queued commands are not carried over to clones of the tree, nor by [`Self::map_panes`].
+    pub fn queue(&self, command: TreeCommand<Pane>) {
+        // Can't fail, since we hold the receiver:
+        self.commands.sender.send(command).ok();
+    }
+
+    /// A handle for queueing commands from anywhere, e.g. a background task.
+    ///
+    /// The sender is [`Send`] if the pane is.
+    pub fn command_sender(&self) -> TreeCommandSender<Pane> {
+        TreeCommandSender {
+            sender: self.commands.sender.clone(),
+            repaint: None,
+        }
+    }
+
+    /// Apply all queued commands now, in the order they were queued.
+    ///
+    /// This is done automatically at the start of [`Self::ui`].
+    /// Calls [`Behavior::on_edit`] with [`EditAction::TreeEdited`] if there were any commands.
+    pub fn apply_queued_commands(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        let commands = self.commands.take();
+        if commands.is_empty() {
+            return;
+        }
+        for command in commands {
+            self.apply_command(behavior, command);
+        }
+        behavior.on_edit(EditAction::TreeEdited);
+    }
+
+    fn apply_command(&mut self, behavior: &mut dyn Behavior<Pane>, command: TreeCommand<Pane>) {
+        match command {
+            TreeCommand::Move {
+                tile_id,
+                container_id,
+                index,
+            } => {
+                self.move_tile_to_container(tile_id, container_id, index, false);
+            }
+            TreeCommand::Close(tile_id) => {
+                if self.tiles.get(tile_id).is_none() {
+                    log::debug!("Cannot close {tile_id:?}: it is not in the tree");
+                } else {
+                    self.tiles.request_tab_close(behavior, tile_id);
+                }
+            }
+            TreeCommand::Insert {
+                pane,
+                container_id,
+                index,
+            } => {
+                let tile_id = self.tiles.insert_pane(pane);
+                let Some(container_id) = container_id.or(self.root) else {
+                    self.root = Some(tile_id);
+                    return;
+                };
+                self.insert_into_tile(tile_id, container_id, index.unwrap_or(usize::MAX));
+            }
+            TreeCommand::Activate(tile_id) => {
+                self.make_active(|id, _| id == tile_id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Container, UiResponse};
+
+    use super::*;
+
+    struct TestBehavior;
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+    }
+
+    #[test]
+    fn test_commands_from_other_thread() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let first = tree.tiles.get_container(root).unwrap().children_vec()[0];
+
+        let sender = tree.command_sender();
+        std::thread::Builder::new()
+            .name("test_sender".to_owned())
+            .spawn(move || {
+                sender.send(TreeCommand::Insert {
+                    pane: 3,
+                    container_id: None,
+                    index: Some(0),
+                });
+                sender.send(TreeCommand::Close(first));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        tree.queue(TreeCommand::Activate(first));
+
+        tree.apply_queued_commands(&mut TestBehavior);
+        let Some(Container::Tabs(tabs)) = tree.tiles.get_container(root) else {
+            panic!()
+        };
+        let panes: Vec<u32> = tabs
+            .children
+            .iter()
+            .filter_map(|&child| tree.tiles.get_pane(&child).copied())
+            .collect();
+        assert_eq!(panes, vec![3, 2]);
+    }
+
+    #[test]
+    fn test_insert_next_to_pane_root() {
+        let mut tiles = crate::Tiles::default();
+        let root = tiles.insert_pane(1);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        tree.queue(TreeCommand::Insert {
+            pane: 2,
+            container_id: None,
+            index: None,
+        });
+        tree.apply_queued_commands(&mut TestBehavior);
+        let Some(Container::Tabs(tabs)) = tree.tiles.get_container(root) else {
+            panic!("Expected the root pane to be put in tabs")
+        };
+        let panes: Vec<u32> = tabs
+            .children
+            .iter()
+            .filter_map(|&child| tree.tiles.get_pane(&child).copied())
+            .collect();
+        assert_eq!(panes, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_close_asks_behavior() {
+        struct KeepBehavior;
+
+        impl Behavior<u32> for KeepBehavior {
+            fn pane_ui(
+                &mut self,
+                _ui: &mut egui::Ui,
+                _tile_id: TileId,
+                _pane: &mut u32,
+            ) -> UiResponse {
+                UiResponse::None
+            }
+
+            fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+                pane.to_string().into()
+            }
+
+            fn on_tab_close(
+                &mut self,
+                _tiles: &mut crate::Tiles<u32>,
+                _tile_id: TileId,
+            ) -> crate::CloseResponse {
+                crate::CloseResponse::Keep
+            }
+        }
+
+        let mut tree = Tree::new_tabs("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let first = tree.tiles.get_container(root).unwrap().children_vec()[0];
+        tree.queue(TreeCommand::Close(first));
+        tree.apply_queued_commands(&mut KeepBehavior);
+        assert_eq!(tree.tiles.get_pane(&first), Some(&1));
+    }
+}
@@ -1,10 +1,10 @@
-use egui::{emath::Rangef, pos2, vec2, NumExt as _, Rect};
+use egui::{emath::Rangef, pos2, vec2, NumExt as _, Rect, Vec2};
 use itertools::Itertools as _;
 
-use crate::behavior::EditAction;
+use crate::behavior::{DragOrigin, EditAction, SplitterDoubleClick};
 use crate::{
-    Behavior, ContainerInsertion, DropContext, InsertionPoint, ResizeState, SimplifyAction, TileId,
-    Tiles, Tree,
+    Behavior, ContainerInsertion, DropContext, InsertionPoint, Linear, LinearDir, ResizeState,
+    SimplifyAction, TileId, Tiles, Tree,
 };
 
 /// How to lay out the children of a grid.
@@ -21,6 +21,31 @@ pub enum GridLayout {
     Columns(usize),
 }
 
+/// When to collapse the holes that drag-and-drop leaves in a [`Grid`], moving the following tiles back.
+///
+/// Set it per grid with [`Grid::hole_collapse`], or for all grids with [`Behavior::grid_hole_collapse`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum GridHoleCollapse {
+    /// Collapse all holes when there are more empty cells than there are columns or rows,
+    /// so that the grid can shrink.
+    #[default]
+    Auto,
+
+    /// Keep all holes until the grid is rearranged in some other way.
+    Never,
+
+    /// Collapse all holes as soon as there is one.
+    Aggressive,
+
+    /// Collapse all holes when there are at least this many.
+    Threshold(usize),
+
+    /// Collapse all holes once nothing is being dragged any more,
+    /// so that the grid never reshuffles during a drag.
+    OnDropEnd,
+}
+
 /// A grid of tiles.
 #[derive(Clone, Debug, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -28,7 +53,7 @@ pub struct Grid {
     /// The order of the children, row-major.
     ///
     /// We allow holes (for easier drag-dropping).
-    /// We collapse all holes if they become too numerous.
+    /// We collapse all holes according to [`Self::hole_collapse`].
     children: Vec<Option<TileId>>,
 
     /// Determines the number of columns.
@@ -40,6 +65,36 @@ pub struct Grid {
     /// Share of the available height assigned to each row.
     pub row_shares: Vec<f32>,
 
+    /// Use the columns of the nearest ancestor [`Grid`], like a CSS subgrid.
+    ///
+    /// The grid then gets as many columns as that ancestor, with the same shares,
+    /// so nested grids line up with each other and with the proportions of the ancestor.
+    /// The columns of a subgrid can only be resized through the ancestor.
+    ///
+    /// Ignored if there is no ancestor grid.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub subgrid: bool,
+
+    /// The number of rows at the top that stay in view when the tree is scrolled,
+    /// like the freeze panes of a spreadsheet.
+    ///
+    /// Only has an effect when the tree is larger than the [`egui::ScrollArea`] it is in.
+    /// At least one row always scrolls.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub frozen_rows: usize,
+
+    /// The number of columns on the left that stay in view when the tree is scrolled.
+    ///
+    /// See [`Self::frozen_rows`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub frozen_cols: usize,
+
+    /// When to collapse holes in this grid.
+    ///
+    /// `None` means [`Behavior::grid_hole_collapse`] decides.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hole_collapse: Option<GridHoleCollapse>,
+
     /// ui point x ranges for each column, recomputed during layout
     #[cfg_attr(feature = "serde", serde(skip))]
     col_ranges: Vec<Rangef>,
@@ -56,6 +111,10 @@ impl PartialEq for Grid {
             layout,
             col_shares,
             row_shares,
+            subgrid,
+            frozen_rows,
+            frozen_cols,
+            hole_collapse,
             col_ranges: _, // ignored because they are recomputed each frame
             row_ranges: _, // ignored because they are recomputed each frame
         } = self;
@@ -64,6 +123,10 @@ impl PartialEq for Grid {
             && children == &other.children
             && col_shares == &other.col_shares
             && row_shares == &other.row_shares
+            && subgrid == &other.subgrid
+            && frozen_rows == &other.frozen_rows
+            && frozen_cols == &other.frozen_cols
+            && hole_collapse == &other.hole_collapse
     }
 }
 
@@ -75,6 +138,50 @@ impl Grid {
         }
     }
 
+    /// The share of each child in the given direction, based on the column or row it is in.
+    ///
+    /// Used to keep the proportions when converting the grid to a [`crate::Linear`].
+    pub(super) fn child_shares(&self, dir: LinearDir) -> Vec<(TileId, f32)> {
+        let num_cols = match self.layout {
+            GridLayout::Auto => self.col_shares.len(),
+            GridLayout::Columns(num_columns) => num_columns,
+        }
+        .at_least(1);
+
+        self.children
+            .iter()
+            .enumerate()
+            .filter_map(|(i, child)| {
+                let share = match dir {
+                    LinearDir::Horizontal => self.col_shares.get(i % num_cols),
+                    LinearDir::Vertical => self.row_shares.get(i / num_cols),
+                };
+                Some(((*child)?, share.copied().unwrap_or(1.0)))
+            })
+            .collect()
+    }
+
+    /// If the children are all in one row (horizontal) or one column (vertical), which one?
+    ///
+    /// `None` for [`GridLayout::Auto`], whose shape depends on the available space,
+    /// and for grids with fewer than two children.
+    pub(crate) fn single_line_dir(&self) -> Option<LinearDir> {
+        let GridLayout::Columns(num_cols) = self.layout else {
+            return None;
+        };
+        if self.num_children() < 2 {
+            return None;
+        }
+        let num_slots = self.children.iter().rposition(Option::is_some)? + 1;
+        if num_slots <= num_cols {
+            Some(LinearDir::Horizontal)
+        } else if num_cols <= 1 {
+            Some(LinearDir::Vertical)
+        } else {
+            None
+        }
+    }
+
     pub fn num_children(&self) -> usize {
         self.children().count()
     }
@@ -117,6 +224,18 @@ impl Grid {
         }
     }
 
+    /// The rect of the cell at the given drop index, as laid out last, and the tile currently there.
+    pub(crate) fn drop_cell(&self, index: usize) -> Option<(Rect, Option<TileId>)> {
+        let num_cols = self.col_ranges.len();
+        if num_cols == 0 {
+            return None;
+        }
+        let col_range = self.col_ranges.get(index % num_cols)?;
+        let row_range = self.row_ranges.get(index / num_cols)?;
+        let occupant = self.children.get(index).copied().flatten();
+        Some((Rect::from_x_y_ranges(*col_range, *row_range), occupant))
+    }
+
     fn collapse_holes(&mut self) {
         log::trace!("Collaping grid holes");
         self.children.retain(|child| child.is_some());
@@ -136,6 +255,7 @@ impl Grid {
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
+        tile_id: TileId,
     ) {
         // clean up any empty holes at the end
         while self.children.last() == Some(&None) {
@@ -144,17 +264,30 @@ impl Grid {
 
         let gap = behavior.gap_width(style);
 
+        // Make room for the headers:
+        let rect = content_rect(tiles, behavior, rect, tile_id, gap);
+
         let visible_children_and_holes = self.visible_children_and_holes(tiles);
 
+        let inherited_col_shares = if self.subgrid {
+            tiles.grid_col_shares.last().cloned()
+        } else {
+            None
+        };
+
         // Calculate grid dimensions:
         let (num_cols, num_rows) = {
             let num_visible_children = visible_children_and_holes.len();
 
-            let num_cols = match self.layout {
-                GridLayout::Auto => {
-                    behavior.grid_auto_column_count(num_visible_children, rect, gap)
+            let num_cols = if let Some(col_shares) = &inherited_col_shares {
+                col_shares.len()
+            } else {
+                match self.layout {
+                    GridLayout::Auto => {
+                        behavior.grid_auto_column_count(num_visible_children, rect, gap)
+                    }
+                    GridLayout::Columns(num_columns) => num_columns,
                 }
-                GridLayout::Columns(num_columns) => num_columns,
             };
             let num_cols = num_cols.at_least(1);
             let num_rows = (num_visible_children + num_cols - 1) / num_cols;
@@ -167,6 +300,9 @@ impl Grid {
         );
 
         // Figure out where each column and row goes:
+        if let Some(col_shares) = inherited_col_shares {
+            self.col_shares = col_shares;
+        }
         self.col_shares.resize(num_cols, 1.0);
         self.row_shares.resize(num_rows, 1.0);
 
@@ -188,7 +324,10 @@ impl Grid {
             let mut x = rect.left();
             self.col_ranges.clear();
             for &width in &col_widths {
-                self.col_ranges.push(Rangef::new(x, x + width));
+                self.col_ranges.push(Rangef::new(
+                    tiles.round_to_pixel(x),
+                    tiles.round_to_pixel(x + width),
+                ));
                 x += width + gap;
             }
         }
@@ -196,7 +335,10 @@ impl Grid {
             let mut y = rect.top();
             self.row_ranges.clear();
             for &height in &row_heights {
-                self.row_ranges.push(Rangef::new(y, y + height));
+                self.row_ranges.push(Rangef::new(
+                    tiles.round_to_pixel(y),
+                    tiles.round_to_pixel(y + height),
+                ));
                 y += height + gap;
             }
         }
@@ -212,32 +354,116 @@ impl Grid {
             "Bug in egui_tiles::Grid::layout"
         );
 
+        let (num_frozen_cols, frozen_right) = stick_frozen_lines(
+            &mut self.col_ranges,
+            self.frozen_cols,
+            tiles.viewport.map(|v| v.x_range()),
+            rect.x_range(),
+        );
+        let (num_frozen_rows, frozen_bottom) = stick_frozen_lines(
+            &mut self.row_ranges,
+            self.frozen_rows,
+            tiles.viewport.map(|v| v.y_range()),
+            rect.y_range(),
+        );
+
         // Layout each child:
+        tiles.grid_col_shares.push(self.col_shares.clone()); // for any subgrids
         for (i, &child) in visible_children_and_holes.iter().enumerate() {
             if let Some(child) = child {
                 let col = i % num_cols;
                 let row = i / num_cols;
                 let child_rect = Rect::from_x_y_ranges(self.col_ranges[col], self.row_ranges[row]);
+
+                // Scrolling cells must not be painted over the frozen ones:
+                let mut clip_rect = Rect::EVERYTHING;
+                if num_frozen_cols <= col {
+                    clip_rect.min.x = frozen_right;
+                }
+                if num_frozen_rows <= row {
+                    clip_rect.min.y = frozen_bottom;
+                }
+                if clip_rect != Rect::EVERYTHING {
+                    tiles.clip_rects.insert(child, clip_rect);
+                }
+
                 tiles.layout_tile(style, behavior, child_rect, child);
             }
         }
+        tiles.grid_col_shares.pop();
 
         // Check if we should collapse some holes:
         {
             let num_holes = visible_children_and_holes
                 .iter()
                 .filter(|c| c.is_none())
-                .count()
-                + (num_cols * num_rows - visible_children_and_holes.len());
-
-            if num_cols.min(num_rows) <= num_holes {
-                // More holes than there are columns or rows - let's collapse all holes
-                // so that we can shrink for next frame:
+                .count();
+            let policy = self
+                .hole_collapse
+                .unwrap_or_else(|| behavior.grid_hole_collapse(tile_id));
+            let collapse = match policy {
+                GridHoleCollapse::Auto => {
+                    // More empty cells than there are columns or rows - collapse all holes
+                    // so that we can shrink for next frame:
+                    let num_empty_cells =
+                        num_holes + (num_cols * num_rows - visible_children_and_holes.len());
+                    num_cols.min(num_rows) <= num_empty_cells
+                }
+                GridHoleCollapse::Never => false,
+                GridHoleCollapse::Aggressive => 0 < num_holes,
+                GridHoleCollapse::Threshold(threshold) => 0 < num_holes && threshold <= num_holes,
+                GridHoleCollapse::OnDropEnd => 0 < num_holes && !tiles.is_dragging,
+            };
+            if collapse {
                 self.collapse_holes();
             }
         }
     }
 
+    /// See [`Tiles::min_size`].
+    ///
+    /// Uses the number of columns from the last layout.
+    pub(crate) fn min_size<Pane>(
+        &self,
+        tiles: &Tiles<Pane>,
+        style: &egui::Style,
+        behavior: &dyn Behavior<Pane>,
+    ) -> Vec2 {
+        let num_cols = self.col_shares.len().at_least(1);
+        let visible_children_and_holes = self.visible_children_and_holes(tiles);
+        let num_rows = visible_children_and_holes.len().div_ceil(num_cols);
+
+        let mut col_min = vec![0.0_f32; num_cols];
+        let mut row_min = vec![0.0_f32; num_rows];
+        for (i, &child) in visible_children_and_holes.iter().enumerate() {
+            if let Some(child) = child {
+                let min_size = tiles.min_size(style, behavior, child);
+                let (col, row) = (i % num_cols, i / num_cols);
+                col_min[col] = col_min[col].max(min_size.x);
+                row_min[row] = row_min[row].max(min_size.y);
+            }
+        }
+
+        // Each line gets its share of the space after the gaps:
+        let gap = behavior.gap_width(style);
+        let min_total = |mins: &[f32], shares: &[f32]| {
+            let share = |i: usize| shares.get(i).copied().unwrap_or(1.0);
+            let total_shares: f32 = (0..mins.len()).map(share).sum();
+            let mut total = 0.0_f32;
+            for (i, &min) in mins.iter().enumerate() {
+                let fraction = share(i) / total_shares;
+                if 0.0 < fraction {
+                    total = total.max(min / fraction);
+                }
+            }
+            total + gap * mins.len().saturating_sub(1) as f32
+        };
+        vec2(
+            min_total(&col_min, &self.col_shares),
+            min_total(&row_min, &self.row_shares),
+        )
+    }
+
     pub(super) fn ui<Pane>(
         &mut self,
         tree: &mut Tree<Pane>,
@@ -266,22 +492,368 @@ impl Grid {
             );
         }
 
-        self.resize_columns(&tree.tiles, behavior, ui, tile_id);
-        self.resize_rows(&tree.tiles, behavior, ui, tile_id);
+        self.resize_columns(tree, behavior, ui, tile_id);
+        self.resize_rows(tree, behavior, ui, tile_id);
+        self.resize_intersections(tree, behavior, ui, tile_id);
+
+        self.headers_ui(tree, behavior, ui, tile_id, GridAxis::Col);
+        self.headers_ui(tree, behavior, ui, tile_id, GridAxis::Row);
     }
 
-    fn resize_columns<Pane>(
+    /// Show the column or row headers, and let the user drag them to move whole columns or rows.
+    ///
+    /// Dragging a header out of the grid drags the whole column or row as a [`Linear`] container,
+    /// so it can be dropped elsewhere in the tree.
+    fn headers_ui<Pane>(
+        &mut self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        tile_id: TileId,
+        axis: GridAxis,
+    ) {
+        let parent_rect = tree.tiles.rect_or_die(tile_id);
+        let header_size = match axis {
+            GridAxis::Col => behavior.grid_col_header_height(&tree.tiles, tile_id),
+            GridAxis::Row => behavior.grid_row_header_width(&tree.tiles, tile_id),
+        };
+        if header_size <= 0.0 {
+            return;
+        }
+
+        let ranges = match axis {
+            GridAxis::Col => self.col_ranges.clone(),
+            GridAxis::Row => self.row_ranges.clone(),
+        };
+        let header_rect = |range: Rangef| match axis {
+            GridAxis::Col => Rect::from_x_y_ranges(
+                range,
+                parent_rect.top()..=(parent_rect.top() + header_size).at_most(parent_rect.bottom()),
+            ),
+            GridAxis::Row => Rect::from_x_y_ranges(
+                parent_rect.left()
+                    ..=(parent_rect.left() + header_size).at_most(parent_rect.right()),
+                range,
+            ),
+        };
+
+        let mut moved = None;
+        let mut dragged_out = None;
+
+        for (index, &range) in ranges.iter().enumerate() {
+            let rect = header_rect(range);
+            let id = ui.id().with((tile_id, "grid_header", axis, index));
+
+            // Interact before showing the contents, so that any widgets in the header get priority:
+            if !tree.read_only {
+                let response = ui.interact(rect, id, egui::Sense::drag());
+                if response.hovered() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::Grab);
+                }
+
+                if let Some(pointer_pos) = ui.ctx().pointer_interact_pos() {
+                    let pointer = match axis {
+                        GridAxis::Col => pointer_pos.x,
+                        GridAxis::Row => pointer_pos.y,
+                    };
+                    let target = ranges
+                        .iter()
+                        .position(|range| pointer <= range.max)
+                        .unwrap_or(ranges.len() - 1);
+
+                    if response.dragged() && !parent_rect.contains(pointer_pos) {
+                        dragged_out = Some(index);
+                    } else if response.dragged() {
+                        ui.ctx().set_cursor_icon(behavior.drag_cursor());
+
+                        let target_rect = match axis {
+                            GridAxis::Col => {
+                                Rect::from_x_y_ranges(ranges[target], parent_rect.y_range())
+                            }
+                            GridAxis::Row => {
+                                Rect::from_x_y_ranges(parent_rect.x_range(), ranges[target])
+                            }
+                        };
+                        ui.painter().rect(
+                            target_rect,
+                            1.0,
+                            behavior.drag_preview_color(ui.visuals()),
+                            behavior.drag_preview_stroke(ui.visuals()),
+                        );
+                    }
+
+                    if response.drag_stopped() && target != index {
+                        moved = Some((index, target));
+                    }
+                }
+            }
+
+            let mut header_ui = egui::Ui::new(
+                ui.ctx().clone(),
+                id.with("ui"),
+                egui::UiBuilder::new()
+                    .layer_id(ui.layer_id())
+                    .max_rect(rect),
+            );
+            header_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+            match axis {
+                GridAxis::Col => behavior.grid_col_header_ui(&mut header_ui, tile_id, index),
+                GridAxis::Row => behavior.grid_row_header_ui(&mut header_ui, tile_id, index),
+            }
+        }
+
+        if let Some((from, to)) = moved {
+            if self.move_line(&tree.tiles, axis, from, to) {
+                behavior.on_edit(EditAction::GridReordered);
+            }
+        }
+
+        if let Some(index) = dragged_out {
+            let children = self.children.clone();
+            if let Some(dragged_id) = self.group_line(&mut tree.tiles, axis, index) {
+                tree.start_drag_from(ui.ctx(), dragged_id, DragOrigin::GridHeader);
+                let group_slot = self.children.iter().position(|&c| c == Some(dragged_id));
+                if let (true, Some(group_slot)) = (self.children != children, group_slot) {
+                    // A new container was made for the line:
+                    tree.remember_grid_line_drag(
+                        ui.ctx(),
+                        tile_id,
+                        dragged_id,
+                        group_slot,
+                        children,
+                    );
+                }
+            }
+        }
+    }
+
+    /// The fraction of the width and height of the grid that the column and row of the given child get,
+    /// e.g. `vec2(0.5, 0.25)`.
+    ///
+    /// Based on the layout of the last frame.
+    /// Returns `None` if the child is not a visible child of this grid.
+    pub fn share_fraction<Pane>(&self, tiles: &Tiles<Pane>, child: TileId) -> Option<Vec2> {
+        let (num_cols, slots) = self.laid_out_cells(tiles);
+        let index = slots
+            .iter()
+            .position(|&slot| self.children[slot] == Some(child))?;
+        let (col, row) = (index % num_cols.at_least(1), index / num_cols.at_least(1));
+        let fraction = |shares: &[f32], i: usize| {
+            let total: f32 = shares.iter().sum();
+            let share = shares.get(i).copied().unwrap_or(1.0);
+            if total == 0.0 {
+                1.0 / shares.len().at_least(1) as f32
+            } else {
+                share / total
+            }
+        };
+        Some(vec2(
+            fraction(&self.col_shares, col),
+            fraction(&self.row_shares, row),
+        ))
+    }
+
+    /// Give the column the given fraction of the width of the grid, e.g. `0.2` for 20%,
+    /// by changing its share relative to the current shares of the other columns.
+    ///
+    /// Returns `false` if there is no such column (as laid out during the last frame),
+    /// or if the fraction is not finite.
+    pub fn set_col_fraction(&mut self, col: usize, fraction: f32) -> bool {
+        set_fraction(&mut self.col_shares, col, fraction)
+    }
+
+    /// Give the row the given fraction of the height of the grid, e.g. `0.2` for 20%,
+    /// by changing its share relative to the current shares of the other rows.
+    ///
+    /// Returns `false` if there is no such row (as laid out during the last frame),
+    /// or if the fraction is not finite.
+    pub fn set_row_fraction(&mut self, row: usize, fraction: f32) -> bool {
+        set_fraction(&mut self.row_shares, row, fraction)
+    }
+
+    /// The number of columns as laid out during the last frame,
+    /// and the indices into `self.children` of the cells, in layout order.
+    fn laid_out_cells<Pane>(&self, tiles: &Tiles<Pane>) -> (usize, Vec<usize>) {
+        let num_cols = match self.layout {
+            GridLayout::Auto => self.col_ranges.len(),
+            GridLayout::Columns(num_columns) => num_columns.at_least(1),
+        };
+        let slots = (0..self.children.len())
+            .filter(|&i| self.children[i].map_or(true, |id| tiles.is_visible(id)))
+            .collect_vec();
+        (num_cols, slots)
+    }
+
+    /// Move a whole column to a new index, moving all its tiles and its share.
+    ///
+    /// The columns are as laid out during the last frame, or as set by [`GridLayout::Columns`].
+    ///
+    /// Returns `false` if the indices are out of bounds.
+    pub fn move_col<Pane>(&mut self, tiles: &Tiles<Pane>, from: usize, to: usize) -> bool {
+        self.move_line(tiles, GridAxis::Col, from, to)
+    }
+
+    /// Move a whole row to a new index, moving all its tiles and its share.
+    ///
+    /// See [`Self::move_col`].
+    pub fn move_row<Pane>(&mut self, tiles: &Tiles<Pane>, from: usize, to: usize) -> bool {
+        self.move_line(tiles, GridAxis::Row, from, to)
+    }
+
+    /// Move a whole column or row, as laid out during the last frame.
+    ///
+    /// Returns `false` if the indices are out of bounds.
+    pub(crate) fn move_line<Pane>(
         &mut self,
         tiles: &Tiles<Pane>,
+        axis: GridAxis,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        let (num_cols, slots) = self.laid_out_cells(tiles);
+        if num_cols == 0 {
+            return false;
+        }
+
+        let num_rows = (slots.len() + num_cols - 1) / num_cols;
+        let num_lines = match axis {
+            GridAxis::Col => num_cols,
+            GridAxis::Row => num_rows,
+        };
+        if num_lines <= from.max(to) {
+            return false;
+        }
+
+        // The new order of the columns or rows:
+        let mut order = (0..num_lines).collect_vec();
+        let moved = order.remove(from);
+        order.insert(to, moved);
+
+        let num_cells = num_cols * num_rows;
+        let cell = |i: usize| slots.get(i).and_then(|&slot| self.children[slot]);
+
+        let new_cells = (0..num_cells)
+            .map(|i| {
+                let (col, row) = (i % num_cols, i / num_cols);
+                match axis {
+                    GridAxis::Col => cell(row * num_cols + order[col]),
+                    GridAxis::Row => cell(order[row] * num_cols + col),
+                }
+            })
+            .collect_vec();
+
+        for (i, new_cell) in new_cells.into_iter().enumerate() {
+            if let Some(&slot) = slots.get(i) {
+                self.children[slot] = new_cell;
+            } else {
+                self.children.push(new_cell);
+            }
+        }
+        while self.children.last() == Some(&None) {
+            self.children.pop();
+        }
+
+        let shares = match axis {
+            GridAxis::Col => &mut self.col_shares,
+            GridAxis::Row => &mut self.row_shares,
+        };
+        if shares.len() == num_lines {
+            let moved = shares.remove(from);
+            shares.insert(to, moved);
+        }
+
+        true
+    }
+
+    /// Put the tiles of a whole column or row, as laid out during the last frame,
+    /// into a new [`Linear`] container, keeping their relative sizes.
+    ///
+    /// The new container takes the place of the first of the tiles, and the others leave holes.
+    /// Returns the new container, or the tile itself if there is only one in the line.
+    pub(crate) fn group_line<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        axis: GridAxis,
+        index: usize,
+    ) -> Option<TileId> {
+        let (num_cols, slots) = self.laid_out_cells(tiles);
+        if num_cols == 0 {
+            return None;
+        }
+
+        let line = slots
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| match axis {
+                GridAxis::Col => i % num_cols == index,
+                GridAxis::Row => i / num_cols == index,
+            })
+            .filter_map(|(i, &slot)| {
+                let share = match axis {
+                    GridAxis::Col => self.row_shares.get(i / num_cols),
+                    GridAxis::Row => self.col_shares.get(i % num_cols),
+                };
+                Some((slot, self.children[slot]?, share.copied().unwrap_or(1.0)))
+            })
+            .collect_vec();
+
+        match line.as_slice() {
+            [] => None,
+            [(_, child, _)] => Some(*child),
+            [(first_slot, ..), ..] => {
+                let dir = match axis {
+                    GridAxis::Col => LinearDir::Vertical,
+                    GridAxis::Row => LinearDir::Horizontal,
+                };
+                let mut linear = Linear::new(dir, line.iter().map(|&(_, id, _)| id).collect());
+                for &(slot, id, share) in &line {
+                    linear.shares.set_share(id, share);
+                    self.children[slot] = None;
+                }
+                let group = tiles.insert_container(linear);
+                self.children[*first_slot] = Some(group);
+                Some(group)
+            }
+        }
+    }
+
+    /// Put back the children from before [`Self::group_line`],
+    /// if the new container is still in the slot it was put in.
+    ///
+    /// Returns `false` if the container has moved, in which case nothing is changed.
+    pub(crate) fn restore_children(
+        &mut self,
+        group_slot: usize,
+        group_id: TileId,
+        children: Vec<Option<TileId>>,
+    ) -> bool {
+        if self.children.get(group_slot) == Some(&Some(group_id)) {
+            self.children = children;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn resize_columns<Pane>(
+        &mut self,
+        tree: &Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         ui: &egui::Ui,
         parent_id: TileId,
     ) {
-        let parent_rect = tiles.rect_or_die(parent_id);
+        let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let resizable = !tree.read_only
+            && !self.subgrid
+            && !crate::is_degenerate(parent_rect)
+            && behavior.is_resizable(&tree.tiles, parent_id);
         for (i, (left, right)) in self.col_ranges.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize_col", i));
 
-            let x = egui::lerp(left.max..=right.min, 0.5);
+            let mut x = egui::lerp(left.max..=right.min, 0.5);
+            if behavior.pixel_snap() {
+                x = ui.painter().round_to_pixel_center(x);
+            }
 
             let mut resize_state = ResizeState::Idle;
             let line_rect = Rect::from_center_size(
@@ -291,41 +863,54 @@ impl Grid {
                     parent_rect.height(),
                 ),
             );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
-                    behavior,
-                    &self.col_ranges,
-                    &mut self.col_shares,
-                    &response,
-                    ui.painter().round_to_pixel(pointer.x) - x,
-                    i,
-                );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+            if resizable {
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    let double_click =
+                        behavior.splitter_double_click_action(&tree.tiles, parent_id, i);
+                    resize_state = resize_interaction(
+                        behavior,
+                        GridAxis::Col,
+                        &self.col_ranges,
+                        &mut self.col_shares,
+                        &response,
+                        double_click,
+                        ui.painter().round_to_pixel(pointer.x) - x,
+                        i,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx()
+                            .set_cursor_icon(behavior.resize_cursor(LinearDir::Horizontal));
+                    }
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
+            let stroke = behavior.resize_stroke(ui.style(), resize_state, parent_id, i);
             ui.painter().vline(x, parent_rect.y_range(), stroke);
         }
     }
 
     fn resize_rows<Pane>(
         &mut self,
-        tiles: &Tiles<Pane>,
+        tree: &Tree<Pane>,
         behavior: &mut dyn Behavior<Pane>,
         ui: &egui::Ui,
         parent_id: TileId,
     ) {
-        let parent_rect = tiles.rect_or_die(parent_id);
+        let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let resizable = !tree.read_only
+            && !crate::is_degenerate(parent_rect)
+            && behavior.is_resizable(&tree.tiles, parent_id);
         for (i, (top, bottom)) in self.row_ranges.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize_row", i));
 
-            let y = egui::lerp(top.max..=bottom.min, 0.5);
+            let mut y = egui::lerp(top.max..=bottom.min, 0.5);
+            if behavior.pixel_snap() {
+                y = ui.painter().round_to_pixel_center(y);
+            }
 
             let mut resize_state = ResizeState::Idle;
             let line_rect = Rect::from_center_size(
@@ -335,26 +920,131 @@ impl Grid {
                     2.0 * ui.style().interaction.resize_grab_radius_side,
                 ),
             );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
+            if resizable {
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    let double_click = behavior.splitter_double_click_action(
+                        &tree.tiles,
+                        parent_id,
+                        self.col_ranges.len().saturating_sub(1) + i,
+                    );
+                    resize_state = resize_interaction(
+                        behavior,
+                        GridAxis::Row,
+                        &self.row_ranges,
+                        &mut self.row_shares,
+                        &response,
+                        double_click,
+                        ui.painter().round_to_pixel(pointer.y) - y,
+                        i,
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx()
+                            .set_cursor_icon(behavior.resize_cursor(LinearDir::Vertical));
+                    }
+                }
+            }
+
+            let boundary_index = self.col_ranges.len().saturating_sub(1) + i;
+            let stroke =
+                behavior.resize_stroke(ui.style(), resize_state, parent_id, boundary_index);
+            ui.painter().hline(parent_rect.x_range(), y, stroke);
+        }
+    }
+
+    /// Dragging the point where a column and row boundary cross resizes both at once.
+    ///
+    /// This must be called after [`Self::resize_columns`] and [`Self::resize_rows`],
+    /// so that the intersections are on top of the lines.
+    fn resize_intersections<Pane>(
+        &mut self,
+        tree: &Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        parent_id: TileId,
+    ) {
+        let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let resizable = !tree.read_only
+            && !self.subgrid
+            && !crate::is_degenerate(parent_rect)
+            && behavior.is_resizable(&tree.tiles, parent_id);
+        if !resizable {
+            return;
+        }
+
+        let num_col_boundaries = self.col_ranges.len().saturating_sub(1);
+        let col_boundaries = self.col_ranges.iter().copied().tuple_windows().enumerate();
+        for (col, (left, right)) in col_boundaries {
+            let row_boundaries = self.row_ranges.iter().copied().tuple_windows().enumerate();
+            for (row, (top, bottom)) in row_boundaries {
+                let resize_id = ui.id().with((parent_id, "resize_intersection", col, row));
+
+                let mut center = pos2(
+                    egui::lerp(left.max..=right.min, 0.5),
+                    egui::lerp(top.max..=bottom.min, 0.5),
+                );
+                if behavior.pixel_snap() {
+                    center = ui.painter().round_pos_to_pixel_center(center);
+                }
+
+                let grab_rect = Rect::from_center_size(
+                    center,
+                    egui::Vec2::splat(2.0 * ui.style().interaction.resize_grab_radius_side),
+                );
+                let response = ui.interact(grab_rect, resize_id, egui::Sense::click_and_drag());
+                let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+                    continue;
+                };
+
+                let double_click =
+                    behavior.splitter_double_click_action(&tree.tiles, parent_id, col);
+                let col_state = resize_interaction(
+                    behavior,
+                    GridAxis::Col,
+                    &self.col_ranges,
+                    &mut self.col_shares,
+                    &response,
+                    double_click,
+                    ui.painter().round_to_pixel(pointer.x) - center.x,
+                    col,
+                );
+                let double_click = behavior.splitter_double_click_action(
+                    &tree.tiles,
+                    parent_id,
+                    num_col_boundaries + row,
+                );
+                let row_state = resize_interaction(
                     behavior,
+                    GridAxis::Row,
                     &self.row_ranges,
                     &mut self.row_shares,
                     &response,
-                    ui.painter().round_to_pixel(pointer.y) - y,
-                    i,
+                    double_click,
+                    ui.painter().round_to_pixel(pointer.y) - center.y,
+                    row,
                 );
 
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+                if col_state != ResizeState::Idle || row_state != ResizeState::Idle {
+                    ui.ctx()
+                        .set_cursor_icon(behavior.grid_intersection_cursor());
+
+                    // Highlight both lines:
+                    let col_stroke = behavior.resize_stroke(ui.style(), col_state, parent_id, col);
+                    ui.painter()
+                        .vline(center.x, parent_rect.y_range(), col_stroke);
+                    let row_stroke = behavior.resize_stroke(
+                        ui.style(),
+                        row_state,
+                        parent_id,
+                        num_col_boundaries + row,
+                    );
+                    ui.painter()
+                        .hline(parent_rect.x_range(), center.y, row_stroke);
                 }
             }
-
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
-            ui.painter().hline(parent_rect.x_range(), y, stroke);
         }
     }
 
@@ -395,11 +1085,40 @@ impl Grid {
     }
 }
 
+#[derive(Clone, Copy, Debug, Hash)]
+pub(crate) enum GridAxis {
+    Col,
+    Row,
+}
+
+/// The part of the grid rect that is left for the children, after making room for the headers.
+fn content_rect<Pane>(
+    tiles: &Tiles<Pane>,
+    behavior: &dyn Behavior<Pane>,
+    rect: Rect,
+    tile_id: TileId,
+    gap: f32,
+) -> Rect {
+    let mut rect = rect;
+    let header_height = behavior.grid_col_header_height(tiles, tile_id);
+    if 0.0 < header_height {
+        rect.min.y = (rect.min.y + header_height + gap).at_most(rect.max.y);
+    }
+    let header_width = behavior.grid_row_header_width(tiles, tile_id);
+    if 0.0 < header_width {
+        rect.min.x = (rect.min.x + header_width + gap).at_most(rect.max.x);
+    }
+    rect
+}
+
+#[allow(clippy::too_many_arguments)]
 fn resize_interaction<Pane>(
     behavior: &mut dyn Behavior<Pane>,
+    axis: GridAxis,
     ranges: &[Rangef],
     shares: &mut [f32],
     splitter_response: &egui::Response,
+    double_click: SplitterDoubleClick,
     dx: f32,
     i: usize,
 ) -> ResizeState {
@@ -410,17 +1129,46 @@ fn resize_interaction<Pane>(
     let left = i;
     let right = i + 1;
 
-    if splitter_response.double_clicked() {
-        behavior.on_edit(EditAction::TileResized);
+    let shares_before_drag_id = splitter_response.id.with(("shares_before_drag", axis));
 
-        // double-click to center the split between left and right:
-        let mean = 0.5 * (shares[left] + shares[right]);
-        shares[left] = mean;
-        shares[right] = mean;
+    if splitter_response.double_clicked() {
+        match double_click {
+            SplitterDoubleClick::None => {}
+            SplitterDoubleClick::Equalize => {
+                // double-click to center the split between left and right:
+                let mean = 0.5 * (shares[left] + shares[right]);
+                shares[left] = mean;
+                shares[right] = mean;
+            }
+            SplitterDoubleClick::Reset => {
+                shares.fill(1.0);
+            }
+            SplitterDoubleClick::RestoreLast => {
+                let shares_before_drag = splitter_response
+                    .ctx
+                    .data(|data| data.get_temp::<Vec<f32>>(shares_before_drag_id));
+                if let Some(shares_before_drag) = shares_before_drag {
+                    if shares_before_drag.len() == shares.len() {
+                        shares.copy_from_slice(&shares_before_drag);
+                    }
+                }
+            }
+        }
+        if double_click != SplitterDoubleClick::None {
+            behavior.on_edit(EditAction::TileResized);
+        }
         ResizeState::Hovering
     } else if splitter_response.dragged() {
         behavior.on_edit(EditAction::TileResized);
 
+        if splitter_response.drag_started() {
+            // Remember, for `SplitterDoubleClick::RestoreLast`:
+            let shares_before_drag = shares.to_vec();
+            splitter_response
+                .ctx
+                .data_mut(|data| data.insert_temp(shares_before_drag_id, shares_before_drag));
+        }
+
         if dx < 0.0 {
             // Expand right, shrink stuff to the left:
             shares[right] += shrink_shares(
@@ -488,6 +1236,34 @@ fn shrink_shares<Pane>(
     total_shares_lost
 }
 
+/// Move the first `num_frozen` column or row ranges so that they stay in the visible range,
+/// without leaving the range of the whole grid.
+///
+/// Returns the number of frozen lines, and where they end,
+/// which is where the scrolling lines should be clipped.
+fn stick_frozen_lines(
+    ranges: &mut [Rangef],
+    num_frozen: usize,
+    visible: Option<Rangef>,
+    grid_range: Rangef,
+) -> (usize, f32) {
+    // At least one line must scroll:
+    let num_frozen = num_frozen.min(ranges.len().saturating_sub(1));
+    if num_frozen == 0 {
+        return (0, f32::NEG_INFINITY);
+    }
+
+    let frozen_end = ranges[num_frozen - 1].max;
+    let max_shift = (grid_range.max - frozen_end).at_least(0.0);
+    let shift = visible.map_or(0.0, |visible| {
+        (visible.min - grid_range.min).clamp(0.0, max_shift)
+    });
+    for range in &mut ranges[..num_frozen] {
+        *range = Rangef::new(range.min + shift, range.max + shift);
+    }
+    (num_frozen, frozen_end + shift)
+}
+
 fn sizes_from_shares(shares: &[f32], available_size: f32, gap_width: f32) -> Vec<f32> {
     if shares.is_empty() {
         return vec![];
@@ -507,6 +1283,18 @@ fn sizes_from_shares(shares: &[f32], available_size: f32, gap_width: f32) -> Vec
     }
 }
 
+fn set_fraction(shares: &mut [f32], index: usize, fraction: f32) -> bool {
+    if shares.len() <= index {
+        return false;
+    }
+    let others = shares.iter().sum::<f32>() - shares[index];
+    let Some(share) = super::linear::share_for_fraction(fraction, others) else {
+        return false;
+    };
+    shares[index] = share;
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Container, Tile};
@@ -538,7 +1326,11 @@ mod tests {
                 panic!()
             }
 
-            fn on_tab_close(&mut self, _tiles: &mut Tiles<Pane>, _tile_id: TileId) -> bool {
+            fn on_tab_close(
+                &mut self,
+                _tiles: &mut Tiles<Pane>,
+                _tile_id: TileId,
+            ) -> crate::CloseResponse {
                 panic!()
             }
         }
@@ -553,6 +1345,7 @@ mod tests {
         let style = egui::Style::default();
         let mut behavior = TestBehavior {};
         let area = egui::Rect::from_min_size(egui::Pos2::ZERO, vec2(1024.0, 768.0));
+        let degenerate_area = egui::Rect::from_min_size(egui::Pos2::ZERO, vec2(-10.0, 0.0));
 
         // Go crazy on it to make sure we never crash:
         let mut rng = Pcg64::new_seed(123_456_789_012);
@@ -560,6 +1353,8 @@ mod tests {
         for _ in 0..1000 {
             let root = tree.root.unwrap();
             tree.tiles.layout_tile(&style, &mut behavior, area, root);
+            tree.tiles
+                .layout_tile(&style, &mut behavior, degenerate_area, root);
 
             // Add some tiles:
             for _ in 0..rng.rand_u64() % 3 {
@@ -628,6 +1423,142 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_move_grid_col_and_row() {
+        struct TestBehavior {}
+
+        impl Behavior<&'static str> for TestBehavior {
+            fn pane_ui(
+                &mut self,
+                _ui: &mut egui::Ui,
+                _tile_id: TileId,
+                _pane: &mut &'static str,
+            ) -> crate::UiResponse {
+                panic!()
+            }
+
+            fn tab_title_for_pane(&mut self, _pane: &&'static str) -> egui::WidgetText {
+                panic!()
+            }
+        }
+
+        let mut tiles = Tiles::default();
+        let panes = ["a", "b", "c", "d"].map(|pane| tiles.insert_pane(pane));
+        let mut grid = Grid::new(panes.to_vec());
+        grid.layout = GridLayout::Columns(2);
+        grid.col_shares = vec![1.0, 2.0];
+        let root = tiles.insert_new(Tile::Container(Container::Grid(grid)));
+        let mut tree = Tree::new("test_tree", root, tiles);
+        let mut behavior = TestBehavior {};
+
+        let grid_children = |tree: &Tree<&'static str>| {
+            let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get(root) else {
+                panic!()
+            };
+            let names = grid
+                .children()
+                .map(|&child| *tree.tiles.get_pane(&child).unwrap())
+                .collect_vec();
+            (names, grid.col_shares.clone())
+        };
+
+        assert!(tree.move_grid_col(&mut behavior, root, 0, 1));
+        assert_eq!(
+            grid_children(&tree),
+            (vec!["b", "a", "d", "c"], vec![2.0, 1.0])
+        );
+
+        assert!(tree.move_grid_row(&mut behavior, root, 1, 0));
+        assert_eq!(
+            grid_children(&tree),
+            (vec!["d", "c", "b", "a"], vec![2.0, 1.0])
+        );
+
+        assert!(!tree.move_grid_col(&mut behavior, root, 0, 2));
+    }
+
+    #[test]
+    fn test_group_grid_row() {
+        let mut tiles = Tiles::default();
+        let panes = ["a", "b", "c", "d"].map(|pane| tiles.insert_pane(pane));
+        let mut grid = Grid::new(panes.to_vec());
+        grid.layout = GridLayout::Columns(2);
+        grid.col_shares = vec![1.0, 2.0];
+
+        let children = grid.children.clone();
+        let group = grid.group_line(&mut tiles, GridAxis::Row, 0).unwrap();
+        assert_eq!(
+            grid.children,
+            vec![Some(group), None, Some(panes[2]), Some(panes[3])]
+        );
+
+        // Only put back if the group didn't move:
+        let grouped = grid.children.clone();
+        grid.children.swap(0, 2);
+        assert!(!grid.restore_children(0, group, children.clone()));
+        grid.children = grouped;
+        assert!(grid.restore_children(0, group, children.clone()));
+        assert_eq!(grid.children, children);
+        let group = grid.group_line(&mut tiles, GridAxis::Row, 0).unwrap();
+
+        let Some(Tile::Container(Container::Linear(linear))) = tiles.get(group) else {
+            panic!()
+        };
+        assert_eq!(linear.dir, LinearDir::Horizontal);
+        assert_eq!(linear.children, vec![panes[0], panes[1]]);
+        assert_eq!(linear.shares[panes[1]] / linear.shares[panes[0]], 2.0);
+
+        // A single tile is dragged as it is:
+        assert_eq!(
+            grid.group_line(&mut tiles, GridAxis::Col, 1),
+            Some(panes[3])
+        );
+    }
+
+    #[test]
+    fn test_set_col_fraction() {
+        let mut grid = Grid::new(vec![]);
+        grid.col_shares = vec![1.0, 1.0, 2.0];
+
+        assert!(grid.set_col_fraction(0, 0.2));
+        let total: f32 = grid.col_shares.iter().sum();
+        assert!((grid.col_shares[0] / total - 0.2).abs() < 1e-6);
+        assert_eq!(grid.col_shares[2] / grid.col_shares[1], 2.0);
+
+        assert!(!grid.set_col_fraction(3, 0.2));
+        assert!(!grid.set_col_fraction(0, f32::NAN));
+    }
+
+    #[test]
+    fn test_join_single_line_grid() {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..3).map(|i| tiles.insert_pane(i)).collect();
+        let mut grid = Grid::new(panes[1..].to_vec());
+        grid.layout = GridLayout::Columns(3);
+        grid.col_shares = vec![1.0, 3.0, 1.0];
+        let grid_id = tiles.insert_container(grid);
+        let root = tiles.insert_horizontal_tile(vec![panes[0], grid_id]);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        tree.simplify(&crate::SimplificationOptions {
+            join_single_line_grids: true,
+            join_nested_linear_containers: true,
+            ..crate::SimplificationOptions::OFF
+        });
+
+        let Some(Container::Linear(linear)) = tree.tiles.get_container(root) else {
+            panic!()
+        };
+        assert_eq!(linear.children, panes);
+        assert_eq!(
+            linear.shares.sum_for(&panes[1..]),
+            linear.shares[panes[0]],
+            "The grid kept its share of the parent"
+        );
+        assert_eq!(linear.shares[panes[2]] / linear.shares[panes[1]], 3.0);
+        assert!(tree.tiles.get(grid_id).is_none());
+    }
+
     // We want a simple RNG, but don't want to pull in any deps just for a test.
     // Code from adapted from https://docs.rs/nanorand/latest/src/nanorand/rand/pcg64.rs.html#15-19
     pub struct Pcg64 {
@@ -3,7 +3,7 @@
 use egui::{pos2, vec2, NumExt, Rect};
 use itertools::Itertools as _;
 
-use crate::behavior::EditAction;
+use crate::behavior::{EditAction, SplitterDoubleClick};
 use crate::{
     is_being_dragged, Behavior, ContainerInsertion, DropContext, InsertionPoint, ResizeState,
     SimplifyAction, TileId, Tiles, Tree,
@@ -41,12 +41,34 @@ impl Shares {
         self.shares.insert(id, share);
     }
 
+    /// The share of the given child, if it has been set.
+    ///
+    /// Children without a share get `1.0` in the layout.
+    pub fn get(&self, id: TileId) -> Option<f32> {
+        if self.shares.is_empty() {
+            return None; // skip hashing the id
+        }
+        self.shares.get(&id).copied()
+    }
+
+    /// The shares of the given children, in order, using `1.0` for children without a share.
+    ///
+    /// Unlike [`Self::iter`], this skips shares of tiles that are no longer children.
+    pub fn iter_children<'a>(
+        &'a self,
+        children: &'a [TileId],
+    ) -> impl Iterator<Item = (TileId, f32)> + 'a {
+        children.iter().map(|&child| (child, self[child]))
+    }
+
+    /// The sum of the shares of the given children, using `1.0` for children without a share.
+    pub fn sum_for<'a>(&self, children: impl IntoIterator<Item = &'a TileId>) -> f32 {
+        children.into_iter().map(|&child| self[child]).sum()
+    }
+
     /// Split the given width based on the share of the children.
     pub fn split(&self, children: &[TileId], available_width: f32) -> Vec<f32> {
-        let mut num_shares = 0.0;
-        for &child in children {
-            num_shares += self[child];
-        }
+        let mut num_shares = self.sum_for(children);
         if num_shares == 0.0 {
             num_shares = 1.0;
         }
@@ -76,6 +98,9 @@ impl std::ops::Index<TileId> for Shares {
 
     #[inline]
     fn index(&self, id: TileId) -> &Self::Output {
+        if self.shares.is_empty() {
+            return &1.0; // skip hashing the id
+        }
         self.shares.get(&id).unwrap_or(&1.0)
     }
 }
@@ -105,6 +130,33 @@ pub struct Linear {
     pub children: Vec<TileId>,
     pub dir: LinearDir,
     pub shares: Shares,
+
+    /// The children that absorb changes to the size of the container.
+    ///
+    /// If this is empty (the default), all children grow and shrink in proportion to their shares.
+    /// Otherwise the other children keep their size in points when the container is resized
+    /// (e.g. when the window is resized), and only the flexible children grow or shrink.
+    ///
+    /// This is useful for a fixed-size sidebar next to a flexible editor.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flexible: ahash::HashSet<TileId>,
+}
+
+/// The layout of a [`Linear`] with [`Linear::flexible`] children, remembered between frames.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct FixedSizes {
+    /// The size of the container along its direction during the last layout.
+    size: f32,
+
+    /// The shares of the visible children after the last layout,
+    /// to tell if they have been changed since (e.g. by dragging a splitter).
+    shares: Vec<(TileId, f32)>,
+
+    /// The size in points that each child that isn't flexible should keep.
+    ///
+    /// This is kept even when the container is too small for them,
+    /// so that they grow back when the container grows again.
+    fixed: ahash::HashMap<TileId, f32>,
 }
 
 impl Linear {
@@ -124,6 +176,50 @@ impl Linear {
             .collect()
     }
 
+    /// The fraction of the space of the container that the given child gets, e.g. `0.42` for 42%.
+    ///
+    /// Only visible children take up space.
+    /// Returns `None` if the child is not a visible child of this container.
+    pub fn share_fraction<Pane>(&self, tiles: &Tiles<Pane>, child: TileId) -> Option<f32> {
+        let children = self.visible_children(tiles);
+        if !children.contains(&child) {
+            return None;
+        }
+        let total = self.shares.sum_for(&children);
+        Some(if total == 0.0 {
+            1.0 / children.len() as f32
+        } else {
+            self.shares[child] / total
+        })
+    }
+
+    /// Give the child the given fraction of the container, e.g. `0.2` for 20%,
+    /// by changing its share relative to the current shares of the other children.
+    ///
+    /// The other children keep their proportions to each other.
+    /// Like in [`Self::share_fraction`], only visible children take up space.
+    /// Returns `false` if the child isn't a visible child of this container,
+    /// or if the fraction is not finite.
+    pub fn set_fraction<Pane>(
+        &mut self,
+        tiles: &Tiles<Pane>,
+        child: TileId,
+        fraction: f32,
+    ) -> bool {
+        let children = self.visible_children(tiles);
+        if !children.contains(&child) {
+            return false;
+        }
+        let others = self
+            .shares
+            .sum_for(children.iter().filter(|&&other| other != child));
+        let Some(share) = share_for_fraction(fraction, others) else {
+            return false;
+        };
+        self.shares[child] = share;
+        true
+    }
+
     /// Create a binary split with the given split ratio in the 0.0 - 1.0 range.
     ///
     /// The `fraction` is the fraction of the total width that the first child should get.
@@ -148,16 +244,47 @@ impl Linear {
         self.children.push(child);
     }
 
+    /// Insert a child at the given index, clamped to the number of children.
+    ///
+    /// If `share_from` is one of the other children, the new child gets half of its share,
+    /// as if that child was split in two, and the rest of the children keep their size.
+    /// Otherwise the new child gets the default share of `1.0`.
+    pub fn insert_child_at(&mut self, index: usize, child: TileId, share_from: Option<TileId>) {
+        let index = index.min(self.children.len());
+        self.children.insert(index, child);
+
+        let share_from =
+            share_from.filter(|&other| other != child && self.children.contains(&other));
+        if let Some(share_from) = share_from {
+            let half = 0.5 * self.shares[share_from];
+            self.shares[share_from] = half;
+            self.shares[child] = half;
+        }
+    }
+
     pub fn layout<Pane>(
         &mut self,
         tiles: &mut Tiles<Pane>,
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
+        tile_id: TileId,
     ) {
         // GC:
-        let child_set: ahash::HashSet<TileId> = self.children.iter().copied().collect();
-        self.shares.retain(|id| child_set.contains(&id));
+        if self.has_removed_children() {
+            let child_set: ahash::HashSet<TileId> = self.children.iter().copied().collect();
+            self.shares.retain(|id| child_set.contains(&id));
+            self.flexible.retain(|id| child_set.contains(id));
+        }
+
+        let size = match self.dir {
+            LinearDir::Horizontal => rect.width(),
+            LinearDir::Vertical => rect.height(),
+        };
+        if !self.flexible.is_empty() {
+            let gap = behavior.gap_width(style);
+            self.keep_fixed_sizes(tiles, tile_id, size, gap);
+        }
 
         match self.dir {
             LinearDir::Horizontal => {
@@ -167,6 +294,181 @@ impl Linear {
         }
     }
 
+    /// Are there shares or [`Self::flexible`] flags left over from children that have been removed?
+    fn has_removed_children(&self) -> bool {
+        let num_shares = self
+            .children
+            .iter()
+            .filter(|&&child| self.shares.get(child).is_some())
+            .count();
+        let num_flexible = if self.flexible.is_empty() {
+            0
+        } else {
+            self.children
+                .iter()
+                .filter(|&child| self.flexible.contains(child))
+                .count()
+        };
+        num_shares != self.shares.shares.len() || num_flexible != self.flexible.len()
+    }
+
+    /// Recompute the shares so that only the [`Self::flexible`] children change size
+    /// when the container changes size.
+    ///
+    /// The sizes in points of the other children are remembered in [`Tiles::fixed_sizes`].
+    fn keep_fixed_sizes<Pane>(
+        &mut self,
+        tiles: &mut Tiles<Pane>,
+        tile_id: TileId,
+        size: f32,
+        gap: f32,
+    ) {
+        let children = self.visible_children(tiles);
+        let previous = tiles.fixed_sizes.remove(&tile_id);
+        if !children.iter().any(|child| self.flexible.contains(child)) {
+            return;
+        }
+
+        let shares: Vec<(TileId, f32)> = self.shares.iter_children(&children).collect();
+        let mut state = match previous {
+            Some(previous) if previous.shares == shares => previous,
+            previous => {
+                // The first layout, or the shares were changed since the last one:
+                // the other children keep the size the shares gave them.
+                let last_size = previous.map_or(size, |previous| previous.size);
+                let (sizes, _) = self.child_sizes(tiles, &children, last_size, gap);
+                let fixed = children
+                    .iter()
+                    .zip(sizes)
+                    .filter(|(child, _)| !self.flexible.contains(child))
+                    .map(|(&child, size)| (child, size))
+                    .collect();
+                FixedSizes {
+                    size: last_size,
+                    shares,
+                    fixed,
+                }
+            }
+        };
+
+        if state.size != size {
+            let (_, gaps) = self.child_sizes(tiles, &children, size, gap);
+            let available = (size - gaps.iter().sum::<f32>()).at_least(0.0);
+            if let Some(shares) = self.shares_for_size(&children, &state.fixed, available) {
+                for &(child, share) in &shares {
+                    self.shares.set_share(child, share);
+                }
+                state.shares = shares;
+            }
+            state.size = size;
+        }
+
+        tiles.fixed_sizes.insert(tile_id, state);
+    }
+
+    /// The shares that give the children that aren't [`Self::flexible`] their `fixed` size,
+    /// and the flexible children the rest of the `available` space.
+    ///
+    /// If the fixed sizes don't fit, they are all scaled down.
+    /// The shares are normalized to sum up to the number of children.
+    fn shares_for_size(
+        &self,
+        children: &[TileId],
+        fixed: &ahash::HashMap<TileId, f32>,
+        available: f32,
+    ) -> Option<Vec<(TileId, f32)>> {
+        let fixed_size = |child: &TileId| fixed.get(child).copied().unwrap_or_default();
+        let total_fixed: f32 = children
+            .iter()
+            .filter(|child| !self.flexible.contains(child))
+            .map(fixed_size)
+            .sum();
+        let scale = if available < total_fixed {
+            available / total_fixed
+        } else {
+            1.0
+        };
+        let flexible_size = (available - scale * total_fixed).at_least(0.0);
+
+        let flexible: Vec<TileId> = children
+            .iter()
+            .copied()
+            .filter(|child| self.flexible.contains(child))
+            .collect();
+        let flexible_shares = self.shares.sum_for(&flexible);
+
+        let sizes: Vec<f32> = children
+            .iter()
+            .map(|child| {
+                if !self.flexible.contains(child) {
+                    scale * fixed_size(child)
+                } else if 0.0 < flexible_shares {
+                    flexible_size * self.shares[*child] / flexible_shares
+                } else {
+                    flexible_size / flexible.len() as f32
+                }
+            })
+            .collect();
+
+        let total: f32 = sizes.iter().sum();
+        if total <= 0.0 {
+            return None; // keep the shares of a collapsed container
+        }
+        let num_children = children.len() as f32;
+        Some(
+            children
+                .iter()
+                .zip(sizes)
+                .map(|(&child, size)| (child, num_children * size / total))
+                .collect(),
+        )
+    }
+
+    /// The children to lay out: the visible ones, and the ones animating out of view.
+    fn laid_out_children<Pane>(&self, tiles: &Tiles<Pane>) -> Vec<TileId> {
+        self.children
+            .iter()
+            .copied()
+            .filter(|&child_id| 0.0 < tiles.visibility_factor(child_id))
+            .collect()
+    }
+
+    /// Returns the size of each child along the layout direction, and the gap after each child.
+    ///
+    /// Children that are animating their visibility get a partial share and gap.
+    fn child_sizes<Pane>(
+        &self,
+        tiles: &Tiles<Pane>,
+        children: &[TileId],
+        total_size: f32,
+        gap: f32,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let factors: Vec<f32> = children
+            .iter()
+            .map(|&child| tiles.visibility_factor(child))
+            .collect();
+
+        // There is a gap before each child except the first:
+        let gaps: Vec<f32> = factors.iter().skip(1).map(|&f| gap * f).collect();
+        let available = (total_size - gaps.iter().sum::<f32>()).at_least(0.0);
+
+        let shares: Vec<f32> = children
+            .iter()
+            .zip(&factors)
+            .map(|(&child, &factor)| self.shares[child] * factor)
+            .collect();
+        let mut num_shares: f32 = shares.iter().sum();
+        if num_shares == 0.0 {
+            num_shares = 1.0;
+        }
+        let sizes = shares
+            .iter()
+            .map(|&share| available * share / num_shares)
+            .collect();
+
+        (sizes, gaps)
+    }
+
     fn layout_horizontal<Pane>(
         &self,
         tiles: &mut Tiles<Pane>,
@@ -174,20 +476,18 @@ impl Linear {
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
     ) {
-        let visible_children = self.visible_children(tiles);
-
-        let num_gaps = visible_children.len().saturating_sub(1);
+        let children = self.laid_out_children(tiles);
         let gap_width = behavior.gap_width(style);
-        let total_gap_width = gap_width * num_gaps as f32;
-        let available_width = (rect.width() - total_gap_width).at_least(0.0);
-
-        let widths = self.shares.split(&visible_children, available_width);
+        let (widths, gaps) = self.child_sizes(tiles, &children, rect.width(), gap_width);
 
         let mut x = rect.min.x;
-        for (child, width) in visible_children.iter().zip(widths) {
-            let child_rect = Rect::from_min_size(pos2(x, rect.min.y), vec2(width, rect.height()));
+        for (i, (child, width)) in children.iter().zip(widths).enumerate() {
+            let child_rect = Rect::from_x_y_ranges(
+                tiles.round_to_pixel(x)..=tiles.round_to_pixel(x + width),
+                rect.y_range(),
+            );
             tiles.layout_tile(style, behavior, child_rect, *child);
-            x += width + gap_width;
+            x += width + gaps.get(i).copied().unwrap_or_default();
         }
     }
 
@@ -198,20 +498,18 @@ impl Linear {
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
     ) {
-        let visible_children = self.visible_children(tiles);
-
-        let num_gaps = visible_children.len().saturating_sub(1);
+        let children = self.laid_out_children(tiles);
         let gap_height = behavior.gap_width(style);
-        let total_gap_height = gap_height * num_gaps as f32;
-        let available_height = (rect.height() - total_gap_height).at_least(0.0);
-
-        let heights = self.shares.split(&visible_children, available_height);
+        let (heights, gaps) = self.child_sizes(tiles, &children, rect.height(), gap_height);
 
         let mut y = rect.min.y;
-        for (child, height) in visible_children.iter().zip(heights) {
-            let child_rect = Rect::from_min_size(pos2(rect.min.x, y), vec2(rect.width(), height));
+        for (i, (child, height)) in children.iter().zip(heights).enumerate() {
+            let child_rect = Rect::from_x_y_ranges(
+                rect.x_range(),
+                tiles.round_to_pixel(y)..=tiles.round_to_pixel(y + height),
+            );
             tiles.layout_tile(style, behavior, child_rect, *child);
-            y += height + gap_height;
+            y += height + gaps.get(i).copied().unwrap_or_default();
         }
     }
 
@@ -237,30 +535,45 @@ impl Linear {
         ui: &egui::Ui,
         parent_id: TileId,
     ) {
-        let visible_children = self.visible_children(&tree.tiles);
-
-        for &child in &visible_children {
+        // Includes children that are animating out, so they don't leave a blank gap:
+        for child in self.laid_out_children(&tree.tiles) {
             tree.tile_ui(behavior, drop_context, ui, child);
             crate::cover_tile_if_dragged(tree, behavior, ui, child);
         }
 
-        linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
-            drop_context.suggest_rect(
-                InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(i)),
-                rect,
-            );
-        });
+        let visible_children = self.visible_children(&tree.tiles);
+
+        let preview_thickness = behavior.linear_drop_zone_thickness();
+        linear_drop_zones(
+            ui.ctx(),
+            tree,
+            &self.children,
+            self.dir,
+            preview_thickness,
+            |rect, i| {
+                drop_context.suggest_rect(
+                    InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(i)),
+                    rect,
+                );
+            },
+        );
 
         // ------------------------
         // resizing:
 
         let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let resizable = !tree.read_only
+            && !crate::is_degenerate(parent_rect)
+            && behavior.is_resizable(&tree.tiles, parent_id);
         for (i, (left, right)) in visible_children.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize", i));
 
             let left_rect = tree.tiles.rect_or_die(left);
             let right_rect = tree.tiles.rect_or_die(right);
-            let x = egui::lerp(left_rect.right()..=right_rect.left(), 0.5);
+            let mut x = egui::lerp(left_rect.right()..=right_rect.left(), 0.5);
+            if behavior.pixel_snap() {
+                x = ui.painter().round_to_pixel_center(x);
+            }
 
             let mut resize_state = ResizeState::Idle;
             let line_rect = Rect::from_center_size(
@@ -270,27 +583,33 @@ impl Linear {
                     parent_rect.height(),
                 ),
             );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
-                    behavior,
-                    &mut self.shares,
-                    &visible_children,
-                    &response,
-                    [left, right],
-                    ui.painter().round_to_pixel(pointer.x) - x,
-                    i,
-                    |tile_id: TileId| tree.tiles.rect_or_die(tile_id).width(),
-                );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeHorizontal);
+            if resizable {
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    let double_click =
+                        behavior.splitter_double_click_action(&tree.tiles, parent_id, i);
+                    resize_state = resize_interaction(
+                        behavior,
+                        &mut self.shares,
+                        &visible_children,
+                        &response,
+                        double_click,
+                        [left, right],
+                        ui.painter().round_to_pixel(pointer.x) - x,
+                        i,
+                        |tile_id: TileId| tree.tiles.rect_or_die(tile_id).width(),
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx()
+                            .set_cursor_icon(behavior.resize_cursor(LinearDir::Horizontal));
+                    }
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
+            let stroke = behavior.resize_stroke(ui.style(), resize_state, parent_id, i);
             ui.painter().vline(x, parent_rect.y_range(), stroke);
         }
     }
@@ -303,30 +622,45 @@ impl Linear {
         ui: &egui::Ui,
         parent_id: TileId,
     ) {
-        let visible_children = self.visible_children(&tree.tiles);
-
-        for &child in &visible_children {
+        // Includes children that are animating out, so they don't leave a blank gap:
+        for child in self.laid_out_children(&tree.tiles) {
             tree.tile_ui(behavior, drop_context, ui, child);
             crate::cover_tile_if_dragged(tree, behavior, ui, child);
         }
 
-        linear_drop_zones(ui.ctx(), tree, &self.children, self.dir, |rect, i| {
-            drop_context.suggest_rect(
-                InsertionPoint::new(parent_id, ContainerInsertion::Vertical(i)),
-                rect,
-            );
-        });
+        let visible_children = self.visible_children(&tree.tiles);
+
+        let preview_thickness = behavior.linear_drop_zone_thickness();
+        linear_drop_zones(
+            ui.ctx(),
+            tree,
+            &self.children,
+            self.dir,
+            preview_thickness,
+            |rect, i| {
+                drop_context.suggest_rect(
+                    InsertionPoint::new(parent_id, ContainerInsertion::Vertical(i)),
+                    rect,
+                );
+            },
+        );
 
         // ------------------------
         // resizing:
 
         let parent_rect = tree.tiles.rect_or_die(parent_id);
+        let resizable = !tree.read_only
+            && !crate::is_degenerate(parent_rect)
+            && behavior.is_resizable(&tree.tiles, parent_id);
         for (i, (top, bottom)) in visible_children.iter().copied().tuple_windows().enumerate() {
             let resize_id = ui.id().with((parent_id, "resize", i));
 
             let top_rect = tree.tiles.rect_or_die(top);
             let bottom_rect = tree.tiles.rect_or_die(bottom);
-            let y = egui::lerp(top_rect.bottom()..=bottom_rect.top(), 0.5);
+            let mut y = egui::lerp(top_rect.bottom()..=bottom_rect.top(), 0.5);
+            if behavior.pixel_snap() {
+                y = ui.painter().round_to_pixel_center(y);
+            }
 
             let mut resize_state = ResizeState::Idle;
             let line_rect = Rect::from_center_size(
@@ -336,27 +670,33 @@ impl Linear {
                     2.0 * ui.style().interaction.resize_grab_radius_side,
                 ),
             );
-            let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
-            // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
-            // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
-            if let Some(pointer) = ui.ctx().pointer_interact_pos() {
-                resize_state = resize_interaction(
-                    behavior,
-                    &mut self.shares,
-                    &visible_children,
-                    &response,
-                    [top, bottom],
-                    ui.painter().round_to_pixel(pointer.y) - y,
-                    i,
-                    |tile_id: TileId| tree.tiles.rect_or_die(tile_id).height(),
-                );
-
-                if resize_state != ResizeState::Idle {
-                    ui.ctx().set_cursor_icon(egui::CursorIcon::ResizeVertical);
+            if resizable {
+                let response = ui.interact(line_rect, resize_id, egui::Sense::click_and_drag());
+                // NOTE: Check for interaction with line_rect BEFORE entering the 'IF block' below,
+                // otherwise we miss the start of a drag event in certain cases (e.g. touchscreens).
+                if let Some(pointer) = ui.ctx().pointer_interact_pos() {
+                    let double_click =
+                        behavior.splitter_double_click_action(&tree.tiles, parent_id, i);
+                    resize_state = resize_interaction(
+                        behavior,
+                        &mut self.shares,
+                        &visible_children,
+                        &response,
+                        double_click,
+                        [top, bottom],
+                        ui.painter().round_to_pixel(pointer.y) - y,
+                        i,
+                        |tile_id: TileId| tree.tiles.rect_or_die(tile_id).height(),
+                    );
+
+                    if resize_state != ResizeState::Idle {
+                        ui.ctx()
+                            .set_cursor_icon(behavior.resize_cursor(LinearDir::Vertical));
+                    }
                 }
             }
 
-            let stroke = behavior.resize_stroke(ui.style(), resize_state);
+            let stroke = behavior.resize_stroke(ui.style(), resize_state, parent_id, i);
             ui.painter().hline(parent_rect.x_range(), y, stroke);
         }
     }
@@ -367,6 +707,9 @@ impl Linear {
             SimplifyAction::Keep => true,
             SimplifyAction::Replace(new) => {
                 self.shares.replace_with(*child, new);
+                if self.flexible.remove(child) {
+                    self.flexible.insert(new);
+                }
                 *child = new;
                 true
             }
@@ -387,22 +730,54 @@ fn resize_interaction<Pane>(
     shares: &mut Shares,
     children: &[TileId],
     splitter_response: &egui::Response,
+    double_click: SplitterDoubleClick,
     [left, right]: [TileId; 2],
     dx: f32,
     i: usize,
     tile_width: impl Fn(TileId) -> f32,
 ) -> ResizeState {
-    if splitter_response.double_clicked() {
-        behavior.on_edit(EditAction::TileResized);
+    let shares_before_drag_id = splitter_response.id.with("shares_before_drag");
 
-        // double-click to center the split between left and right:
-        let mean = 0.5 * (shares[left] + shares[right]);
-        shares[left] = mean;
-        shares[right] = mean;
+    if splitter_response.double_clicked() {
+        match double_click {
+            SplitterDoubleClick::None => {}
+            SplitterDoubleClick::Equalize => {
+                // double-click to center the split between left and right:
+                let mean = 0.5 * (shares[left] + shares[right]);
+                shares[left] = mean;
+                shares[right] = mean;
+            }
+            SplitterDoubleClick::Reset => {
+                for &child in children {
+                    shares[child] = 1.0;
+                }
+            }
+            SplitterDoubleClick::RestoreLast => {
+                let shares_before_drag = splitter_response
+                    .ctx
+                    .data(|data| data.get_temp::<Shares>(shares_before_drag_id));
+                if let Some(shares_before_drag) = shares_before_drag {
+                    for &child in children {
+                        shares[child] = shares_before_drag[child];
+                    }
+                }
+            }
+        }
+        if double_click != SplitterDoubleClick::None {
+            behavior.on_edit(EditAction::TileResized);
+        }
         ResizeState::Hovering
     } else if splitter_response.dragged() {
         behavior.on_edit(EditAction::TileResized);
 
+        if splitter_response.drag_started() {
+            // Remember, for `SplitterDoubleClick::RestoreLast`:
+            let shares_before_drag = shares.clone();
+            splitter_response
+                .ctx
+                .data_mut(|data| data.insert_temp(shares_before_drag_id, shares_before_drag));
+        }
+
         if dx < 0.0 {
             // Expand right, shrink stuff to the left:
             shares[right] += shrink_shares(
@@ -465,17 +840,32 @@ fn shrink_shares<Pane>(
     total_shares_lost
 }
 
+/// The share that gets the given fraction of the total, when the other shares sum to `others`.
+///
+/// Returns `None` if the fraction is not finite.
+pub(super) fn share_for_fraction(fraction: f32, others: f32) -> Option<f32> {
+    if !fraction.is_finite() {
+        return None;
+    }
+    if others <= 0.0 {
+        return Some(1.0); // Alone, so any share gets everything
+    }
+    // Keep a sliver for the others, so that they can be found and resized again:
+    let fraction = fraction.clamp(0.0, 0.99);
+    Some(fraction * others / (1.0 - fraction))
+}
+
 fn linear_drop_zones<Pane>(
     egui_ctx: &egui::Context,
     tree: &Tree<Pane>,
     children: &[TileId],
     dir: LinearDir,
+    preview_thickness: f32,
     add_drop_drect: impl FnMut(Rect, usize),
 ) {
-    let preview_thickness = 12.0;
     let dragged_index = children
         .iter()
-        .position(|&child| is_being_dragged(egui_ctx, tree.id, child));
+        .position(|&child| is_being_dragged(egui_ctx, tree, child));
 
     let after_rect = |rect: Rect| match dir {
         LinearDir::Horizontal => Rect::from_min_max(
@@ -563,3 +953,82 @@ pub(super) fn drop_zones(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Tile, UiResponse};
+
+    use super::*;
+
+    struct TestBehavior;
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+    }
+
+    #[test]
+    fn test_fixed_sizes_grow_back() {
+        let mut tiles = Tiles::default();
+        let sidebar = tiles.insert_pane(0);
+        let center = tiles.insert_pane(1);
+        let mut linear = Linear::new(LinearDir::Horizontal, vec![sidebar, center]);
+        linear.shares[center] = 3.0;
+        linear.flexible.insert(center);
+        let root = tiles.insert_container(linear);
+
+        let style = egui::Style::default();
+        let gap = TestBehavior.gap_width(&style);
+        let layout = |tiles: &mut Tiles<u32>, width: f32| {
+            let rect = Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(width + gap, 100.0));
+            tiles.layout_root(&style, &mut TestBehavior, rect, root);
+            let widths = [sidebar, center].map(|child| tiles.rect(child).unwrap().width());
+            let Some(Tile::Container(crate::Container::Linear(linear))) = tiles.get(root) else {
+                panic!("Expected a linear container");
+            };
+            let total_shares = linear.shares.sum_for(&linear.children);
+            assert!(
+                total_shares <= 4.0,
+                "Shares should not be sizes in points, got {total_shares}"
+            );
+            widths
+        };
+
+        assert_eq!(layout(&mut tiles, 1000.0), [250.0, 750.0]);
+        assert_eq!(
+            layout(&mut tiles, 600.0),
+            [250.0, 350.0],
+            "Only the center shrinks"
+        );
+        assert_eq!(
+            layout(&mut tiles, 200.0),
+            [200.0, 0.0],
+            "The sidebar doesn't fit"
+        );
+        assert_eq!(
+            layout(&mut tiles, 1000.0),
+            [250.0, 750.0],
+            "The sidebar grows back"
+        );
+    }
+
+    #[test]
+    fn test_set_fraction_ignores_hidden_children() {
+        let mut tiles: Tiles<u32> = Tiles::default();
+        let panes: Vec<TileId> = (0..3).map(|i| tiles.insert_pane(i)).collect();
+        let mut linear = Linear::new(LinearDir::Horizontal, panes.clone());
+        tiles.set_visible(panes[2], false);
+
+        assert!(linear.set_fraction(&tiles, panes[0], 0.25));
+        let fraction = linear.share_fraction(&tiles, panes[0]).unwrap();
+        assert!((fraction - 0.25).abs() < 1e-6, "Got {fraction}");
+
+        assert!(!linear.set_fraction(&tiles, panes[2], 0.25), "Hidden");
+        assert!(!linear.set_fraction(&tiles, panes[0], f32::INFINITY));
+    }
+}
@@ -8,7 +8,8 @@ mod grid;
 mod linear;
 mod tabs;
 
-pub use grid::{Grid, GridLayout};
+pub use grid::{Grid, GridHoleCollapse, GridLayout};
+pub(crate) use linear::FixedSizes;
 pub use linear::{Linear, LinearDir, Shares};
 pub use tabs::Tabs;
 
@@ -169,7 +170,7 @@ impl Container {
     /// Iterate through all children in order, and keep only those for which the closure returns `true`.
     pub fn retain(&mut self, mut retain: impl FnMut(TileId) -> bool) {
         match self {
-            Self::Tabs(tabs) => tabs.children.retain(|tile_id: &TileId| retain(*tile_id)),
+            Self::Tabs(tabs) => tabs.retain(retain),
             Self::Linear(linear) => linear.children.retain(|tile_id: &TileId| retain(*tile_id)),
             Self::Grid(grid) => grid.retain(retain),
         }
@@ -195,6 +196,10 @@ impl Container {
         }
     }
 
+    /// Convert the container to a different kind, keeping the children in order.
+    ///
+    /// All shares are reset.
+    /// See [`crate::Tree::set_container_kind`] for a conversion that keeps the proportions.
     pub fn set_kind(&mut self, kind: ContainerKind) {
         if kind == self.kind() {
             return;
@@ -212,6 +217,70 @@ impl Container {
         };
     }
 
+    /// Like [`Self::set_kind`], but carries over the proportions of the children where possible,
+    /// and picks an active tab when converting to tabs.
+    pub(crate) fn convert_kind<Pane>(&mut self, tiles: &Tiles<Pane>, kind: ContainerKind) {
+        if kind == self.kind() {
+            return;
+        }
+
+        let children = self.children_vec();
+
+        // The proportions of the children, in the direction of the new kind:
+        let child_shares: Vec<(TileId, f32)> = match (&*self, kind) {
+            (Self::Linear(linear), _) => children
+                .iter()
+                .map(|&child| (child, linear.shares[child]))
+                .collect(),
+            (Self::Grid(grid), ContainerKind::Horizontal) => {
+                grid.child_shares(LinearDir::Horizontal)
+            }
+            (Self::Grid(grid), ContainerKind::Vertical) => grid.child_shares(LinearDir::Vertical),
+            _ => vec![],
+        };
+
+        *self = match kind {
+            ContainerKind::Tabs => {
+                let mut tabs = Tabs::new(children);
+                tabs.ensure_active(tiles);
+                Self::Tabs(tabs)
+            }
+            ContainerKind::Horizontal | ContainerKind::Vertical => {
+                let dir = if kind == ContainerKind::Horizontal {
+                    LinearDir::Horizontal
+                } else {
+                    LinearDir::Vertical
+                };
+                let mut linear = Linear::new(dir, children);
+                for (child, share) in child_shares {
+                    linear.shares.set_share(child, share);
+                }
+                if let Self::Linear(previous) = self {
+                    linear.flexible = std::mem::take(&mut previous.flexible);
+                }
+                Self::Linear(linear)
+            }
+            ContainerKind::Grid => {
+                let num_children = children.len();
+                let mut grid = Grid::new(children);
+                if let Self::Linear(previous) = self {
+                    let shares = child_shares.iter().map(|&(_, share)| share).collect();
+                    match previous.dir {
+                        LinearDir::Horizontal => {
+                            grid.layout = GridLayout::Columns(num_children.max(1));
+                            grid.col_shares = shares;
+                        }
+                        LinearDir::Vertical => {
+                            grid.layout = GridLayout::Columns(1);
+                            grid.row_shares = shares;
+                        }
+                    }
+                }
+                Self::Grid(grid)
+            }
+        };
+    }
+
     pub(super) fn simplify_children(&mut self, simplify: impl FnMut(TileId) -> SimplifyAction) {
         match self {
             Self::Tabs(tabs) => tabs.simplify_children(simplify),
@@ -226,17 +295,18 @@ impl Container {
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
+        tile_id: TileId,
     ) {
         if self.is_empty() {
             return;
         }
 
         match self {
-            Self::Tabs(tabs) => tabs.layout(tiles, style, behavior, rect),
+            Self::Tabs(tabs) => tabs.layout(tiles, style, behavior, rect, tile_id),
             Self::Linear(linear) => {
-                linear.layout(tiles, style, behavior, rect);
+                linear.layout(tiles, style, behavior, rect, tile_id);
             }
-            Self::Grid(grid) => grid.layout(tiles, style, behavior, rect),
+            Self::Grid(grid) => grid.layout(tiles, style, behavior, rect, tile_id),
         }
     }
 
@@ -1,23 +1,60 @@
+use std::collections::BTreeSet;
+
 use egui::{scroll_area::ScrollBarVisibility, vec2, NumExt, Rect, Vec2};
 
-use crate::behavior::{EditAction, TabState};
+use crate::behavior::{
+    DragOrigin, EditAction, GrabOffset, TabAlignment, TabBarWheel, TabScrollArrow, TabState,
+};
 use crate::{
-    is_being_dragged, Behavior, ContainerInsertion, DropContext, InsertionPoint, SimplifyAction,
-    TileId, Tiles, Tree,
+    is_being_dragged, Behavior, ContainerInsertion, DropContext, InsertionPoint, LayoutEvent,
+    SimplifyAction, TileId, Tiles, Tree,
 };
 
-/// Fixed size icons for `⏴` and `⏵`
-const SCROLL_ARROW_SIZE: Vec2 = Vec2::splat(20.0);
-
 /// A container with tabs. Only one tab is open (active) at a time.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Tabs {
     /// The tabs, in order.
+    ///
+    /// Prefer [`Self::insert_child`], [`Self::move_child`] and [`Self::close`]
+    /// over editing this directly, since they keep [`Self::active`] valid.
     pub children: Vec<TileId>,
 
     /// The currently open tab.
+    ///
+    /// If this is not one of [`Self::children`], the first visible child is activated
+    /// at the next layout pass.
     pub active: Option<TileId>,
+
+    /// Pinned tabs are shown first, and never scroll out of view.
+    ///
+    /// Prefer [`Self::pin`] and [`Self::unpin`] over editing this directly,
+    /// since they keep the pinned tabs first in [`Self::children`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub pinned: BTreeSet<TileId>,
+
+    /// Is this the main document area of the tree, like the editor area of an IDE?
+    ///
+    /// A document area is never removed or collapsed by [`crate::Tree::simplify`],
+    /// not even when its last tab is closed (see [`Behavior::tabs_empty_content_ui`]),
+    /// and it is where [`crate::Tree::open_pane`] opens new panes.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub document_area: bool,
+}
+
+/// Remembered while something is dragged over a [`Tabs`] container.
+///
+/// See [`Behavior::drag_tab_activate_delay`].
+#[derive(Clone, Copy, Debug, Default)]
+struct DragHoverState {
+    /// Has the drag activated any tabs?
+    activated: bool,
+
+    /// The active tab before the drag activated any tabs.
+    original_active: Option<TileId>,
+
+    /// The inactive tab currently hovered, and since when.
+    hovered: Option<(TileId, f64)>,
 }
 
 /// The current tab scrolling state
@@ -48,37 +85,64 @@ struct ScrollState {
     /// Did we show the left scroll-arrow last frame?
     pub showed_left_arrow_prev: bool,
 
-    /// Did we show the right scroll-arrow last frame?
-    pub showed_right_arrow_prev: bool,
+    /// The width of the pinned tabs last frame.
+    pub pinned_width: f32,
+
+    /// The width of the [`Behavior::top_bar_left_ui`] this frame, including spacing.
+    pub left_ui_width: f32,
+
+    /// Is the tab strip being scrolled by dragging it with the middle mouse button?
+    pub middle_dragging: bool,
+
+    /// Wheel scrolling not yet turned into a tab switch, see [`TabBarWheel::SwitchTab`].
+    pub wheel_delta: f32,
+
+    /// The width the scrolled tabs needed last frame,
+    /// without the space added by [`Behavior::tab_alignment`].
+    pub tabs_width: f32,
 }
 
 impl ScrollState {
     /// Returns the space left for the tabs after the scroll arrows.
-    pub fn update(&mut self, ui: &egui::Ui) -> f32 {
-        let mut scroll_area_width = ui.available_width();
+    ///
+    /// See [`Behavior::show_disabled_scroll_arrows`] for `show_disabled_arrows`.
+    pub fn update(&mut self, ui: &egui::Ui, arrow_width: f32, show_disabled_arrows: bool) -> f32 {
+        let mut scroll_area_width =
+            (ui.available_width() - self.left_ui_width - self.pinned_width).at_least(0.0);
 
-        let button_and_spacing_width = SCROLL_ARROW_SIZE.x + ui.spacing().item_spacing.x;
+        let button_and_spacing_width = arrow_width + ui.spacing().item_spacing.x;
 
         let margin = 0.1;
 
-        self.show_left_arrow = SCROLL_ARROW_SIZE.x < self.offset;
+        if show_disabled_arrows {
+            // Show both arrows as long as the tabs don't fit, so that they don't jump around:
+            let overflows = 0.0 < arrow_width && scroll_area_width + margin < self.content_size.x;
+            self.show_left_arrow = overflows;
+            self.show_right_arrow = overflows;
+            if overflows {
+                scroll_area_width -= 2.0 * button_and_spacing_width;
+            }
+        } else {
+            self.show_left_arrow = 0.0 < arrow_width && arrow_width < self.offset;
+            if self.show_left_arrow {
+                scroll_area_width -= button_and_spacing_width;
+            }
 
-        if self.show_left_arrow {
-            scroll_area_width -= button_and_spacing_width;
+            self.show_right_arrow =
+                0.0 < arrow_width && self.offset + scroll_area_width + margin < self.content_size.x;
+            if self.show_right_arrow {
+                scroll_area_width -= button_and_spacing_width;
+            }
         }
 
-        self.show_right_arrow = self.offset + scroll_area_width + margin < self.content_size.x;
-
-        // Compensate for showing/hiding of arrow:
-        self.offset += button_and_spacing_width
-            * ((self.show_left_arrow as i32 as f32) - (self.showed_left_arrow_prev as i32 as f32));
-
-        if self.show_right_arrow {
-            scroll_area_width -= button_and_spacing_width;
+        // Compensate for showing/hiding of the left arrow, unless the tabs are at the start:
+        if 0.0 < self.offset {
+            self.offset += button_and_spacing_width
+                * ((self.show_left_arrow as i32 as f32)
+                    - (self.showed_left_arrow_prev as i32 as f32));
         }
 
         self.showed_left_arrow_prev = self.show_left_arrow;
-        self.showed_right_arrow_prev = self.show_right_arrow;
 
         if self.offset_debt != 0.0 {
             const SPEED: f32 = 500.0;
@@ -103,26 +167,33 @@ impl ScrollState {
         (self.available.x / 3.0).at_least(20.0)
     }
 
-    pub fn left_arrow(&mut self, ui: &mut egui::Ui) {
+    /// Can the tabs be scrolled further to the right?
+    fn can_scroll_right(&self) -> bool {
+        self.offset + self.available.x + 0.5 < self.content_size.x
+    }
+
+    pub fn left_arrow<Pane>(&mut self, ui: &mut egui::Ui, behavior: &mut dyn Behavior<Pane>) {
         if !self.show_left_arrow {
             return;
         }
 
-        if ui
-            .add_sized(SCROLL_ARROW_SIZE, egui::Button::new("⏴"))
+        let enabled = 0.0 < self.offset;
+        if behavior
+            .scroll_arrow_ui(ui, TabScrollArrow::Left, enabled)
             .clicked()
         {
             self.offset_debt -= self.scroll_increment();
         }
     }
 
-    pub fn right_arrow(&mut self, ui: &mut egui::Ui) {
+    pub fn right_arrow<Pane>(&mut self, ui: &mut egui::Ui, behavior: &mut dyn Behavior<Pane>) {
         if !self.show_right_arrow {
             return;
         }
 
-        if ui
-            .add_sized(SCROLL_ARROW_SIZE, egui::Button::new("⏵"))
+        let enabled = self.can_scroll_right();
+        if behavior
+            .scroll_arrow_ui(ui, TabScrollArrow::Right, enabled)
             .clicked()
         {
             self.offset_debt += self.scroll_increment();
@@ -133,13 +204,158 @@ impl ScrollState {
 impl Tabs {
     pub fn new(children: Vec<TileId>) -> Self {
         let active = children.first().copied();
-        Self { children, active }
+        Self {
+            children,
+            active,
+            pinned: Default::default(),
+            document_area: false,
+        }
+    }
+
+    /// A [`Self::document_area`] with the given tabs.
+    pub fn new_document_area(children: Vec<TileId>) -> Self {
+        Self {
+            document_area: true,
+            ..Self::new(children)
+        }
     }
 
     pub fn add_child(&mut self, child: TileId) {
         self.children.push(child);
     }
 
+    /// Insert a tab at the given index.
+    ///
+    /// The index is clamped so that the new tab ends up after the pinned tabs
+    /// (or among them, if it is pinned).
+    /// If there is no active tab, the new tab becomes active.
+    ///
+    /// Calls [`Behavior::on_edit`] with [`EditAction::TabInserted`],
+    /// and with [`EditAction::TabSelected`] if the new tab became active.
+    pub fn insert_child<Pane>(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        index: usize,
+        child: TileId,
+    ) {
+        let active = self.active;
+        self.insert_child_impl(index, child);
+        behavior.on_edit(EditAction::TabInserted);
+        if self.active != active {
+            behavior.on_edit(EditAction::TabSelected);
+        }
+    }
+
+    /// Like [`Self::insert_child`], but without calling [`Behavior::on_edit`].
+    pub(crate) fn insert_child_impl(&mut self, index: usize, child: TileId) {
+        let index = self.clamp_index(child, index);
+        self.children.insert(index, child);
+        if self.active_child().is_none() {
+            self.active = Some(child);
+        }
+    }
+
+    /// Move the tab at index `from` so that it ends up at index `to`.
+    ///
+    /// Pinned tabs stay among the pinned tabs, and unpinned tabs among the unpinned ones.
+    /// Calls [`Behavior::on_edit`] with [`EditAction::TabMoved`].
+    ///
+    /// Returns `false` if `from` is out of bounds.
+    pub fn move_child<Pane>(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        if self.children.len() <= from {
+            return false;
+        }
+        let child = self.children.remove(from);
+        let to = self.clamp_index(child, to);
+        self.children.insert(to, child);
+        behavior.on_edit(EditAction::TabMoved);
+        true
+    }
+
+    /// Clamp an insertion index for the given child, so that pinned tabs stay first.
+    fn clamp_index(&self, child: TileId, index: usize) -> usize {
+        let num_pinned = self.num_pinned();
+        if self.is_pinned(child) {
+            index.min(num_pinned)
+        } else {
+            index.clamp(num_pinned, self.children.len())
+        }
+    }
+
+    /// Remove the tab at the given index, returning its id.
+    ///
+    /// If it was the active tab, the tab after it becomes active,
+    /// or the one before it if it was the last tab.
+    ///
+    /// This only removes the tab from this container.
+    /// Use [`crate::Tree::remove_recursively`] to also remove the tile itself.
+    ///
+    /// Calls [`Behavior::on_edit`] with [`EditAction::TabClosed`],
+    /// and with [`EditAction::TabSelected`] if another tab became active.
+    pub fn close<Pane>(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        index: usize,
+    ) -> Option<TileId> {
+        let active = self.active;
+        let child = self.remove_at(index)?;
+        self.pinned.remove(&child);
+        behavior.on_edit(EditAction::TabClosed);
+        if self.active.is_some() && self.active != active {
+            behavior.on_edit(EditAction::TabSelected);
+        }
+        Some(child)
+    }
+
+    /// Like [`Self::close`], but a pinned tab stays pinned if it is inserted again.
+    fn remove_at(&mut self, index: usize) -> Option<TileId> {
+        if self.children.len() <= index {
+            return None;
+        }
+        let child = self.children.remove(index);
+        if self.active == Some(child) {
+            self.active = self
+                .children
+                .get(index)
+                .or_else(|| self.children.last())
+                .copied();
+        }
+        Some(child)
+    }
+
+    /// Keep only the tabs for which the closure returns `true`.
+    ///
+    /// If the active tab is removed, its closest remaining neighbor becomes active.
+    pub fn retain(&mut self, mut keep: impl FnMut(TileId) -> bool) {
+        let previous_children = self.children.clone();
+        self.children.retain(|&child| keep(child));
+        self.activate_neighbor(&previous_children);
+    }
+
+    /// If the active tab was removed, activate its closest remaining neighbor,
+    /// preferring the one after it.
+    fn activate_neighbor(&mut self, previous_children: &[TileId]) {
+        let Some(active) = self.active else {
+            return;
+        };
+        if self.children.contains(&active) {
+            return;
+        }
+        let Some(index) = previous_children.iter().position(|&child| child == active) else {
+            return;
+        };
+        self.active = previous_children[index + 1..]
+            .iter()
+            .chain(previous_children[..index].iter().rev())
+            .copied()
+            .find(|child| self.children.contains(child));
+    }
+
     pub fn set_active(&mut self, child: TileId) {
         self.active = Some(child);
     }
@@ -148,13 +364,66 @@ impl Tabs {
         Some(child) == self.active
     }
 
+    /// The active tab, if it is one of our children.
+    ///
+    /// See also [`crate::Tree::active_leaf_of`].
+    pub fn active_child(&self) -> Option<TileId> {
+        self.active.filter(|active| self.children.contains(active))
+    }
+
+    /// Pin the given tab.
+    ///
+    /// Pinned tabs are shown before all other tabs, and never scroll out of view.
+    /// They can only be reordered among the other pinned tabs.
+    pub fn pin(&mut self, child: TileId) {
+        if self.children.contains(&child) && self.pinned.insert(child) {
+            self.sort_pinned_first();
+        }
+    }
+
+    /// Unpin the given tab, moving it to the start of the unpinned tabs.
+    pub fn unpin(&mut self, child: TileId) {
+        if self.pinned.remove(&child) {
+            if let Some(index) = self.children.iter().position(|&c| c == child) {
+                self.children.remove(index);
+                self.children.insert(self.num_pinned(), child);
+            }
+        }
+    }
+
+    pub fn is_pinned(&self, child: TileId) -> bool {
+        self.pinned.contains(&child)
+    }
+
+    /// The number of pinned tabs. These are always the first children.
+    pub fn num_pinned(&self) -> usize {
+        self.children
+            .iter()
+            .filter(|child| self.pinned.contains(child))
+            .count()
+    }
+
+    /// Move all pinned tabs to the front, keeping the order otherwise the same.
+    fn sort_pinned_first(&mut self) {
+        let pinned = &self.pinned;
+        self.children.sort_by_key(|child| !pinned.contains(child));
+    }
+
     pub(super) fn layout<Pane>(
         &mut self,
         tiles: &mut Tiles<Pane>,
         style: &egui::Style,
         behavior: &mut dyn Behavior<Pane>,
         rect: Rect,
+        tile_id: TileId,
     ) {
+        // GC:
+        if !self.pinned.is_empty() {
+            let children = &self.children;
+            self.pinned.retain(|id| children.contains(id));
+            self.sort_pinned_first();
+        }
+
         let prev_active = self.active;
         self.ensure_active(tiles);
         if prev_active != self.active {
@@ -162,7 +431,10 @@ impl Tabs {
         }
 
         let mut active_rect = rect;
-        active_rect.min.y += behavior.tab_bar_height(style);
+        if !behavior.tab_bar_overlay(tiles, tile_id) {
+            active_rect.min.y =
+                tiles.round_to_pixel(active_rect.min.y + behavior.tab_bar_height(style));
+        }
 
         if let Some(active) = self.active {
             // Only lay out the active tab (saves CPU):
@@ -197,18 +469,89 @@ impl Tabs {
         rect: Rect,
         tile_id: TileId,
     ) {
-        let next_active = self.tab_bar_ui(tree, behavior, ui, rect, drop_context, tile_id);
+        // The active tab may have changed since the last frame, e.g. programmatically:
+        self.notify_tab_selected(tree, behavior, ui.ctx(), tile_id);
 
-        if let Some(active) = self.active {
-            tree.tile_ui(behavior, drop_context, ui, active);
-            crate::cover_tile_if_dragged(tree, behavior, ui, active);
+        let overlay = behavior.tab_bar_overlay(&tree.tiles, tile_id);
+        if overlay {
+            // Paint the tab bar on top of the active tab:
+            self.active_tab_ui(tree, behavior, drop_context, ui);
+        }
+
+        if self.active.is_none() {
+            // No visible tabs:
+            let mut content_rect = rect;
+            if !overlay {
+                content_rect.min.y += behavior.tab_bar_height(ui.style());
+            }
+            let mut content_ui = ui.new_child(
+                egui::UiBuilder::new()
+                    .id_salt(("tabs_empty_content", tile_id))
+                    .max_rect(content_rect),
+            );
+            behavior.tabs_empty_content_ui(&mut content_ui, tile_id);
+        }
+
+        let (next_active, toggle_pin) =
+            self.tab_bar_ui(tree, behavior, ui, rect, drop_context, tile_id);
+
+        if let Some(child_id) = toggle_pin {
+            if self.is_pinned(child_id) {
+                self.unpin(child_id);
+            } else {
+                self.pin(child_id);
+            }
+        }
+
+        if !overlay {
+            self.active_tab_ui(tree, behavior, drop_context, ui);
         }
 
         // We have only laid out the active tab, so we need to switch active tab _after_ the ui pass above:
         self.active = next_active;
+        self.notify_tab_selected(tree, behavior, ui.ctx(), tile_id);
+    }
+
+    /// Call [`Behavior::on_tab_selected`] if the active tab changed since we last checked.
+    fn notify_tab_selected<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ctx: &egui::Context,
+        tile_id: TileId,
+    ) {
+        let id = tree.base_id().with((tile_id, "selected_tab"));
+        let previous = ctx
+            .data(|data| data.get_temp::<Option<TileId>>(id))
+            .flatten();
+        if previous == self.active {
+            return;
+        }
+        ctx.data_mut(|data| data.insert_temp(id, self.active));
+        if let Some(next) = self.active {
+            tree.record(LayoutEvent::TabSelected {
+                tabs_id: tile_id,
+                tab_id: next,
+            });
+            behavior.on_tab_selected(&tree.tiles, tile_id, previous, next);
+        }
+    }
+
+    fn active_tab_ui<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        drop_context: &mut DropContext,
+        ui: &egui::Ui,
+    ) {
+        if let Some(active) = self.active {
+            tree.tile_ui(behavior, drop_context, ui, active);
+            crate::cover_tile_if_dragged(tree, behavior, ui, active);
+        }
     }
 
-    /// Returns the next active tab (e.g. the one clicked, or the current).
+    /// Returns the next active tab (e.g. the one clicked, or the current),
+    /// and the tab whose pin state the user wants to toggle, if any.
     #[allow(clippy::too_many_lines)]
     fn tab_bar_ui<Pane>(
         &self,
@@ -218,8 +561,10 @@ impl Tabs {
         rect: Rect,
         drop_context: &mut DropContext,
         tile_id: TileId,
-    ) -> Option<TileId> {
+    ) -> (Option<TileId>, Option<TileId>) {
         let mut next_active = self.active;
+        let mut toggle_pin = None;
+        let num_pinned = self.num_pinned();
 
         let tab_bar_height = behavior.tab_bar_height(ui.style());
         let tab_bar_rect = rect.split_top_bottom_at_y(rect.top() + tab_bar_height).0;
@@ -227,6 +572,7 @@ impl Tabs {
 
         let mut button_rects = ahash::HashMap::default();
         let mut dragged_index = None;
+        let mut ghost_rect = None;
 
         ui.painter()
             .rect_filled(ui.max_rect(), 0.0, behavior.tab_bar_color(ui.visuals()));
@@ -239,20 +585,90 @@ impl Tabs {
                     .unwrap_or_default()
             });
 
+            self.wheel_ui(
+                tree,
+                behavior,
+                ui,
+                tab_bar_rect,
+                &mut scroll_state,
+                &mut next_active,
+            );
+
             // Allow user to add buttons such as "add new tab".
             // They can also read and modify the scroll state if they want.
             behavior.top_bar_right_ui(&tree.tiles, ui, tile_id, self, &mut scroll_state.offset);
 
-            let scroll_area_width = scroll_state.update(ui);
+            // The left-side UI goes in whatever space the right-side UI left over:
+            let mut left_ui = ui.new_child(
+                egui::UiBuilder::new()
+                    .max_rect(ui.available_rect_before_wrap())
+                    .layout(egui::Layout::left_to_right(egui::Align::Center)),
+            );
+            behavior.top_bar_left_ui(
+                &tree.tiles,
+                &mut left_ui,
+                tile_id,
+                self,
+                &mut scroll_state.offset,
+            );
+            let left_ui_width = left_ui.min_rect().width();
+            scroll_state.left_ui_width = if 0.0 < left_ui_width {
+                left_ui_width + ui.spacing().item_spacing.x
+            } else {
+                0.0
+            };
+
+            let arrow_width = behavior.scroll_arrow_width(ui.style());
+            let scroll_area_width =
+                scroll_state.update(ui, arrow_width, behavior.show_disabled_scroll_arrows());
 
             // We're in a right-to-left layout, so start with the right scroll-arrow:
-            scroll_state.right_arrow(ui);
+            scroll_state.right_arrow(ui, behavior);
 
             ui.allocate_ui_with_layout(
                 ui.available_size(),
                 egui::Layout::left_to_right(egui::Align::Center),
                 |ui| {
-                    scroll_state.left_arrow(ui);
+                    ui.add_space(scroll_state.left_ui_width);
+
+                    // Pinned tabs are never scrolled:
+                    if 0 < num_pinned {
+                        let pinned_rect = ui
+                            .scope(|ui| {
+                                ui.spacing_mut().item_spacing.x = 0.0; // Tabs have spacing built-in
+
+                                for (i, &child_id) in
+                                    self.children.iter().enumerate().take(num_pinned)
+                                {
+                                    if !tree.is_visible(child_id) {
+                                        continue;
+                                    }
+                                    let response = self.tab_button_ui(
+                                        tree,
+                                        behavior,
+                                        ui,
+                                        tile_id,
+                                        child_id,
+                                        0.0,
+                                        None,
+                                        &mut next_active,
+                                        &mut toggle_pin,
+                                    );
+                                    button_rects.insert(child_id, response.rect);
+                                    if is_being_dragged(ui.ctx(), tree, child_id) {
+                                        dragged_index = Some(i);
+                                    }
+                                }
+                            })
+                            .response
+                            .rect;
+                        scroll_state.pinned_width =
+                            pinned_rect.width() + ui.spacing().item_spacing.x;
+                    } else {
+                        scroll_state.pinned_width = 0.0;
+                    }
+
+                    scroll_state.left_arrow(ui, behavior);
 
                     // Prepare to show the scroll area with the tabs:
 
@@ -265,67 +681,189 @@ impl Tabs {
                         .scroll_bar_visibility(ScrollBarVisibility::AlwaysHidden)
                         .max_width(scroll_area_width)
                         .auto_shrink([false; 2])
+                        .enable_scrolling(false) // We handle the wheel and drags ourselves
                         .horizontal_scroll_offset(scroll_state.offset);
 
-                    let output = scroll_area.show(ui, |ui| {
-                        if !tree.is_root(tile_id) {
-                            // Make the background behind the buttons draggable (to drag the parent container tile).
-                            // We also sense clicks to avoid eager-dragging on mouse-down.
-                            let sense = egui::Sense::click_and_drag();
-                            if ui
-                                .interact(ui.max_rect(), ui.id().with("background"), sense)
-                                .on_hover_cursor(egui::CursorIcon::Grab)
-                                .drag_started()
+                    // Place the tabs according to the alignment, using their width last frame:
+                    let num_scrolled = self
+                        .children
+                        .iter()
+                        .skip(num_pinned)
+                        .filter(|&&child_id| tree.is_visible(child_id))
+                        .count();
+                    let free_width = (scroll_area_width.min(ui.available_width())
+                        - scroll_state.tabs_width)
+                        .at_least(0.0);
+                    let mut fixed_width = None;
+                    let (leading_space, extra_width) = match behavior.tab_alignment(tile_id) {
+                        TabAlignment::Left => (0.0, 0.0),
+                        TabAlignment::Center => (0.5 * free_width, 0.0),
+                        TabAlignment::Right => (free_width, 0.0),
+                        TabAlignment::Justified => (0.0, free_width / num_scrolled.max(1) as f32),
+                        TabAlignment::EqualWidth => {
+                            let width = scroll_area_width.min(ui.available_width())
+                                / num_scrolled.max(1) as f32;
+                            fixed_width = Some(width.at_least(tab_bar_height));
+                            (0.0, 0.0)
+                        }
+                    };
+                    let mut tabs_width = 0.0;
+
+                    // Is a tile being dragged over this tab bar, so that it may be dropped here?
+                    // (`dragged_tile_id` is `None` for read-only trees)
+                    let dragging_over_bar = ui.rect_contains_pointer(tab_bar_rect)
+                        && drop_context
+                            .dragged_tile_id
+                            .is_some_and(|dragged| dragged != tile_id);
+
+                    // Show a tile dragged here from elsewhere as a ghost tab button
+                    // where it was about to be dropped last frame:
+                    let ghost = drop_context
+                        .dragged_tile_id
+                        .zip(drop_context.previous_insertion)
+                        .and_then(|(dragged, insertion)| match insertion.insertion {
+                            ContainerInsertion::Tabs(index)
+                                if dragging_over_bar
+                                    && behavior.ghost_tab_drop_preview()
+                                    && insertion.parent_id == tile_id
+                                    && (num_pinned..=self.children.len()).contains(&index)
+                                    && !self.children.contains(&dragged) =>
                             {
+                                Some((index, dragged))
+                            }
+                            _ => None,
+                        });
+                    let ghost_state = TabState {
+                        active: true,
+                        extra_width,
+                        fixed_width,
+                        ..Default::default()
+                    };
+
+                    let mut drag_scroll = 0.0;
+                    let output = scroll_area.show(ui, |ui| {
+                        // Make the background behind the buttons draggable (to drag the parent container tile).
+                        // We also sense clicks to avoid eager-dragging on mouse-down.
+                        let sense = egui::Sense::click_and_drag();
+                        let background =
+                            ui.interact(ui.max_rect(), ui.id().with("background"), sense);
+                        if !tree.is_root(tile_id) && !tree.read_only {
+                            let background = background.on_hover_cursor(egui::CursorIcon::Grab);
+                            if background.drag_started_by(egui::PointerButton::Primary) {
                                 behavior.on_edit(EditAction::TileDragged);
-                                ui.ctx().set_dragged_id(tile_id.egui_id(tree.id));
+                                tree.start_drag_from(ui.ctx(), tile_id, DragOrigin::TabBar);
                             }
+                        } else if background.dragged_by(egui::PointerButton::Primary) {
+                            // Nothing to drag, so scroll the tabs instead:
+                            drag_scroll += background.drag_delta().x;
                         }
 
                         ui.spacing_mut().item_spacing.x = 0.0; // Tabs have spacing built-in
-
-                        for (i, &child_id) in self.children.iter().enumerate() {
+                        ui.add_space(leading_space);
+
+                        for (i, &child_id) in self.children.iter().enumerate().skip(num_pinned) {
+                            if let Some((_, dragged)) = ghost.filter(|&(index, _)| index == i) {
+                                ghost_rect = Some(behavior.ghost_tab_ui(
+                                    &tree.tiles,
+                                    ui,
+                                    dragged,
+                                    &ghost_state,
+                                ));
+                            }
                             if !tree.is_visible(child_id) {
                                 continue;
                             }
-
-                            let is_being_dragged = is_being_dragged(ui.ctx(), tree.id, child_id);
-
-                            let selected = self.is_active(child_id);
-                            let id = child_id.egui_id(tree.id);
-                            let tab_state = TabState {
-                                active: selected,
-                                is_being_dragged,
-                                closable: behavior.is_tab_closable(&tree.tiles, child_id),
-                            };
-
-                            let response =
-                                behavior.tab_ui(&mut tree.tiles, ui, id, child_id, &tab_state);
-
-                            if response.clicked() {
-                                behavior.on_edit(EditAction::TabSelected);
-                                next_active = Some(child_id);
-                            }
-
-                            if let Some(mouse_pos) = drop_context.mouse_pos {
-                                if drop_context.dragged_tile_id.is_some()
-                                    && response.rect.contains(mouse_pos)
-                                {
-                                    // Expand this tab - maybe the user wants to drop something into it!
-                                    behavior.on_edit(EditAction::TabSelected);
-                                    next_active = Some(child_id);
-                                }
-                            }
-
+                            let response = self.tab_button_ui(
+                                tree,
+                                behavior,
+                                ui,
+                                tile_id,
+                                child_id,
+                                extra_width,
+                                fixed_width,
+                                &mut next_active,
+                                &mut toggle_pin,
+                            );
+                            tabs_width += response.rect.width() - extra_width;
                             button_rects.insert(child_id, response.rect);
-                            if is_being_dragged {
+                            if is_being_dragged(ui.ctx(), tree, child_id) {
                                 dragged_index = Some(i);
                             }
                         }
+
+                        if let Some((_, dragged)) =
+                            ghost.filter(|&(index, _)| index == self.children.len())
+                        {
+                            ghost_rect =
+                                Some(behavior.ghost_tab_ui(&tree.tiles, ui, dragged, &ghost_state));
+                        }
+
+                        if dragging_over_bar {
+                            // Leave room for dropping the tile after the last tab:
+                            let end_gap = dragged_index
+                                .and_then(|i| button_rects.get(&self.children[i]))
+                                .map_or(3.0 * tab_bar_height, |rect| rect.width());
+                            ui.add_space(end_gap);
+                        }
                     });
 
-                    scroll_state.offset = output.state.offset.x;
+                    if arrow_width <= 0.0 {
+                        // No arrows, so show that there are more tabs by fading out the edges:
+                        paint_fade_edges(
+                            ui.painter(),
+                            output.inner_rect,
+                            behavior.tab_bar_color(ui.visuals()),
+                            output.state.offset.x,
+                            output.content_size.x - output.inner_rect.width(),
+                        );
+                    }
+
+                    // Scroll when a dragged tile is held near either end of the strip,
+                    // so that every drop position can be reached:
+                    if dragging_over_bar {
+                        if let Some(pointer) = ui.ctx().pointer_hover_pos() {
+                            const SPEED: f32 = 500.0;
+                            let dt = ui.input(|i| i.stable_dt).at_most(0.1);
+                            let edge = tab_bar_height;
+                            let offset = output.state.offset.x;
+                            let max_offset = output.content_size.x - output.inner_rect.width();
+                            if output.inner_rect.right() - edge < pointer.x && offset < max_offset {
+                                drag_scroll -= SPEED * dt;
+                            } else if pointer.x < output.inner_rect.left() + edge && 0.0 < offset {
+                                drag_scroll += SPEED * dt;
+                            }
+                        }
+                    }
+
+                    // Dragging with the middle mouse button scrolls the tabs, also when over a tab:
+                    if ui.input(|i| i.pointer.button_pressed(egui::PointerButton::Middle))
+                        && ui.is_enabled()
+                        && ui.rect_contains_pointer(output.inner_rect)
+                    {
+                        scroll_state.middle_dragging = true;
+                    }
+                    if !crate::is_middle_button_drag(ui.ctx()) {
+                        scroll_state.middle_dragging = false;
+                    }
+                    if scroll_state.middle_dragging {
+                        drag_scroll += ui.input(|i| i.pointer.delta().x);
+                    }
+
+                    if 0.5 < (tabs_width - scroll_state.tabs_width).abs() {
+                        // Tabs were added, removed or renamed, so the alignment needs another frame:
+                        scroll_state.tabs_width = tabs_width;
+                        ui.ctx().request_repaint();
+                    }
+
+                    scroll_state.offset = output.state.offset.x - drag_scroll;
+                    if drag_scroll != 0.0 {
+                        scroll_state.offset_debt = 0.0;
+                        ui.ctx().request_repaint();
+                    }
+                    // Decide on the scroll arrows based on what the tabs need, not on how they are aligned:
                     scroll_state.content_size = output.content_size;
+                    scroll_state.content_size.x -=
+                        leading_space + num_scrolled as f32 * extra_width;
                     scroll_state.available = output.inner_rect.size();
                 },
             );
@@ -334,10 +872,31 @@ impl Tabs {
                 .data_mut(|data| data.insert_temp(scroll_state_id, scroll_state));
         });
 
+        self.active_tab_indicator_ui(behavior, &ui, tile_id, &button_rects);
+
+        self.activate_on_drag_hover(
+            tree,
+            behavior,
+            &ui,
+            rect,
+            drop_context,
+            tile_id,
+            &button_rects,
+            &mut next_active,
+        );
+
         // -----------
         // Drop zones:
 
-        let preview_thickness = 6.0;
+        // The ghost tab button covers the drop zone at its index:
+        let ghost_index = ghost_rect.and_then(|ghost_rect| {
+            let insertion = drop_context.previous_insertion?;
+            drop_context.suggest_rect(insertion, ghost_rect);
+            drop_context.ghost_tab = Some(insertion);
+            Some(insertion.insertion.index())
+        });
+
+        let preview_thickness = behavior.tab_drop_zone_thickness();
         let after_rect = |rect: Rect| {
             let dragged_size = if let Some(dragged_index) = dragged_index {
                 // We actually know the size of this thing
@@ -357,35 +916,500 @@ impl Tabs {
             super::LinearDir::Horizontal,
             |tile_id| button_rects.get(&tile_id).copied(),
             |rect, i| {
-                drop_context.suggest_rect(
-                    InsertionPoint::new(tile_id, ContainerInsertion::Tabs(i)),
-                    rect,
-                );
+                // Tabs can't be dragged across the pin boundary:
+                let is_dragging_pinned = dragged_index.is_some_and(|index| index < num_pinned);
+                let allowed = if is_dragging_pinned {
+                    i <= num_pinned
+                } else {
+                    num_pinned <= i
+                };
+                if allowed && Some(i) != ghost_index {
+                    drop_context.suggest_rect(
+                        InsertionPoint::new(tile_id, ContainerInsertion::Tabs(i)),
+                        rect,
+                    );
+                }
             },
             after_rect,
         );
 
-        next_active
+        (next_active, toggle_pin)
+    }
+
+    /// Paint the line under the active tab, sliding over from the previously active tab.
+    ///
+    /// See [`Behavior::active_tab_indicator_animation_time`].
+    fn active_tab_indicator_ui<Pane>(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        tile_id: TileId,
+        button_rects: &ahash::HashMap<TileId, Rect>,
+    ) {
+        let animation_time = behavior.active_tab_indicator_animation_time();
+        if animation_time <= 0.0 {
+            return;
+        }
+        let shown_tabs: Vec<(TileId, Rect)> = self
+            .children
+            .iter()
+            .filter_map(|&child| Some((child, *button_rects.get(&child)?)))
+            .collect();
+        let Some(active_index) = shown_tabs
+            .iter()
+            .position(|&(child, _)| Some(child) == self.active)
+        else {
+            return;
+        };
+
+        // Animate the index rather than the position, so that the indicator follows the tabs when they scroll:
+        let index = ui.ctx().animate_value_with_time(
+            ui.make_persistent_id((tile_id, "active_tab_indicator")),
+            active_index as f32,
+            animation_time,
+        );
+        let (_, from) = shown_tabs[(index.floor() as usize).min(shown_tabs.len() - 1)];
+        let (_, to) = shown_tabs[(index.ceil() as usize).min(shown_tabs.len() - 1)];
+        let t = index.fract();
+        let left = egui::lerp(from.left()..=to.left(), t);
+        let right = egui::lerp(from.right()..=to.right(), t);
+
+        let stroke = behavior.active_tab_indicator_stroke(ui.visuals());
+        ui.painter()
+            .hline(left..=right, from.bottom() - 0.5 * stroke.width, stroke);
+    }
+
+    /// Handle the mouse wheel over the tab bar, see [`Behavior::tab_bar_wheel_action`].
+    fn wheel_ui<Pane>(
+        &self,
+        tree: &Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        tab_bar_rect: Rect,
+        scroll_state: &mut ScrollState,
+        next_active: &mut Option<TileId>,
+    ) {
+        let action = behavior.tab_bar_wheel_action();
+        if action == TabBarWheel::None
+            || !ui.is_enabled()
+            || !ui.rect_contains_pointer(tab_bar_rect)
+        {
+            scroll_state.wheel_delta = 0.0;
+            return;
+        }
+
+        match action {
+            TabBarWheel::None => {}
+
+            TabBarWheel::ScrollStrip => {
+                if scroll_state.content_size.x <= scroll_state.available.x {
+                    return; // All tabs fit, so leave the wheel to any enclosing scroll area
+                }
+                ui.input_mut(|i| {
+                    // Horizontal scrolling (trackpad, shift+wheel) if there is any, else vertical:
+                    let axis = usize::from(i.smooth_scroll_delta.x == 0.0);
+                    let delta = i.smooth_scroll_delta[axis];
+                    let can_move = if 0.0 < delta {
+                        0.0 < scroll_state.offset
+                    } else {
+                        delta < 0.0 && scroll_state.can_scroll_right()
+                    };
+                    if can_move {
+                        i.smooth_scroll_delta[axis] = 0.0; // Don't scroll anything else
+                        scroll_state.offset -= delta;
+                        scroll_state.offset_debt = 0.0;
+                    }
+                });
+            }
+
+            TabBarWheel::SwitchTab => {
+                /// How far to scroll to switch one tab.
+                ///
+                /// Small enough for a single wheel notch on all platforms,
+                /// and we switch at most one tab per frame so that a notch never skips tabs.
+                const STEP: f32 = 12.0;
+
+                scroll_state.wheel_delta += ui.input_mut(|i| {
+                    i.smooth_scroll_delta = Vec2::ZERO; // Don't scroll anything else
+                    i.raw_scroll_delta.x + i.raw_scroll_delta.y
+                });
+                if scroll_state.wheel_delta.abs() < STEP {
+                    return;
+                }
+                // Scrolling down or right moves to the next tab:
+                let forward = scroll_state.wheel_delta < 0.0;
+                scroll_state.wheel_delta = 0.0;
+
+                let visible: Vec<TileId> = self
+                    .children
+                    .iter()
+                    .copied()
+                    .filter(|&child| tree.is_visible(child))
+                    .collect();
+                let current = next_active
+                    .or(self.active)
+                    .and_then(|active| visible.iter().position(|&child| child == active));
+
+                let index = match current {
+                    Some(index) if forward => (index + 1).min(visible.len().saturating_sub(1)),
+                    Some(index) => index.saturating_sub(1),
+                    None => 0,
+                };
+                if Some(index) != current {
+                    if let Some(&child) = visible.get(index) {
+                        behavior.on_edit(EditAction::TabSelected);
+                        *next_active = Some(child);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Show the button for a single tab, and handle clicks on it.
+    #[allow(clippy::too_many_arguments)]
+    fn tab_button_ui<Pane>(
+        &self,
+        tree: &mut Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut egui::Ui,
+        tile_id: TileId,
+        child_id: TileId,
+        extra_width: f32,
+        fixed_width: Option<f32>,
+        next_active: &mut Option<TileId>,
+        toggle_pin: &mut Option<TileId>,
+    ) -> egui::Response {
+        let is_being_dragged = is_being_dragged(ui.ctx(), tree, child_id);
+
+        let selected = self.is_active(child_id);
+        let id = tree.tile_egui_id(child_id);
+        let tab_state = TabState {
+            active: selected,
+            is_being_dragged,
+            closable: !tree.read_only && behavior.is_tab_closable(&tree.tiles, child_id),
+            read_only: tree.read_only,
+            pinned: self.is_pinned(child_id),
+            extra_width,
+            fixed_width,
+            attention: tree.attention(child_id),
+        };
+
+        let response = behavior.tab_ui(&mut tree.tiles, ui, id, child_id, &tab_state);
+
+        if !tree.read_only && response.drag_started_by(egui::PointerButton::Primary) {
+            tree.set_drag_origin(ui.ctx(), DragOrigin::TabButton);
+            if let Some(press_origin) = ui.input(|i| i.pointer.press_origin()) {
+                tree.set_grab_offset(ui.ctx(), GrabOffset(press_origin - response.rect.min));
+            }
+            if let Some(index) = self.children.iter().position(|&id| id == child_id) {
+                tree.remember_tab_drag_start(ui.ctx(), tile_id, child_id, index, self.active);
+            }
+        }
+
+        if response.clicked() {
+            behavior.on_edit(EditAction::TabSelected);
+            *next_active = Some(child_id);
+        }
+
+        if !tree.read_only && behavior.show_pin_action(&tree.tiles, child_id) {
+            response.context_menu(|ui| {
+                let text = if tab_state.pinned {
+                    "Unpin tab"
+                } else {
+                    "Pin tab"
+                };
+                if ui.button(text).clicked() {
+                    behavior.on_edit(EditAction::TabPinned);
+                    *toggle_pin = Some(child_id);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        response
+    }
+
+    /// Activate a tab when something is dragged over it for long enough
+    /// - maybe the user wants to drop something into it!
+    ///
+    /// If the drag then leaves the container, the original tab is activated again.
+    #[allow(clippy::too_many_arguments)]
+    fn activate_on_drag_hover<Pane>(
+        &self,
+        tree: &Tree<Pane>,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &egui::Ui,
+        rect: Rect,
+        drop_context: &DropContext,
+        tile_id: TileId,
+        button_rects: &ahash::HashMap<TileId, Rect>,
+        next_active: &mut Option<TileId>,
+    ) {
+        let state_id = tree.base_id().with((tile_id, "drag_hover"));
+        let mut state: DragHoverState = ui.data(|data| data.get_temp(state_id)).unwrap_or_default();
+
+        let dragged_mouse_pos = drop_context
+            .mouse_pos
+            .filter(|_| drop_context.dragged_tile_id.is_some());
+
+        match dragged_mouse_pos {
+            Some(mouse_pos) if rect.contains(mouse_pos) => {
+                let hovered_tab = button_rects
+                    .iter()
+                    .find(|(_, button_rect)| button_rect.contains(mouse_pos))
+                    .map(|(&child_id, _)| child_id)
+                    .filter(|&child_id| !self.is_active(child_id));
+
+                if let Some(hovered_tab) = hovered_tab {
+                    let now = ui.input(|i| i.time);
+                    let since = match state.hovered {
+                        Some((child_id, since)) if child_id == hovered_tab => since,
+                        _ => now,
+                    };
+                    let delay = behavior.drag_tab_activate_delay();
+                    let elapsed = (now - since) as f32;
+                    if delay <= elapsed {
+                        behavior.on_edit(EditAction::TabSelected);
+                        if !state.activated {
+                            state.activated = true;
+                            state.original_active = self.active;
+                        }
+                        state.hovered = None;
+                        *next_active = Some(hovered_tab);
+                    } else {
+                        state.hovered = Some((hovered_tab, since));
+                        ui.ctx().request_repaint_after_secs(delay - elapsed);
+                    }
+                } else {
+                    state.hovered = None;
+                }
+            }
+            Some(_) => {
+                // The drag left us without dropping anything - restore the original tab:
+                if std::mem::take(&mut state.activated) {
+                    let original_active = state.original_active.take();
+                    if original_active != self.active
+                        && original_active.map_or(true, |active| self.children.contains(&active))
+                    {
+                        behavior.on_edit(EditAction::TabSelected);
+                        *next_active = original_active;
+                    }
+                }
+                state.hovered = None;
+            }
+            None => {
+                state = DragHoverState::default();
+            }
+        }
+
+        if state.activated || state.hovered.is_some() {
+            ui.data_mut(|data| data.insert_temp(state_id, state));
+        } else {
+            ui.data_mut(|data| data.remove::<DragHoverState>(state_id));
+        }
     }
 
     pub(super) fn simplify_children(&mut self, mut simplify: impl FnMut(TileId) -> SimplifyAction) {
-        self.children.retain_mut(|child| match simplify(*child) {
-            SimplifyAction::Remove => false,
-            SimplifyAction::Keep => true,
-            SimplifyAction::Replace(new) => {
-                if self.active == Some(*child) {
-                    self.active = Some(new);
+        // This runs every frame, so only remember the previous children if something changes:
+        let mut previous_children = None;
+        let mut index = 0;
+        while let Some(&child) = self.children.get(index) {
+            let action = simplify(child);
+            if !matches!(action, SimplifyAction::Keep) && previous_children.is_none() {
+                previous_children = Some(self.children.clone());
+            }
+            match action {
+                SimplifyAction::Remove => {
+                    self.children.remove(index);
+                }
+                SimplifyAction::Keep => index += 1,
+                SimplifyAction::Replace(new) => {
+                    if self.active == Some(child) {
+                        self.active = Some(new);
+                    }
+                    if self.pinned.remove(&child) {
+                        self.pinned.insert(new);
+                    }
+                    self.children[index] = new;
+                    index += 1;
                 }
-                *child = new;
-                true
             }
-        });
+        }
+        if let Some(previous_children) = previous_children {
+            self.activate_neighbor(&previous_children);
+        }
     }
 
     /// Returns child index, if found.
     pub(crate) fn remove_child(&mut self, needle: TileId) -> Option<usize> {
         let index = self.children.iter().position(|&child| child == needle)?;
-        self.children.remove(index);
+        self.remove_at(index);
         Some(index)
     }
 }
+
+/// Fade the tabs into the background at the edges of the tab strip,
+/// where there are more tabs to scroll to.
+fn paint_fade_edges(
+    painter: &egui::Painter,
+    rect: Rect,
+    color: egui::Color32,
+    offset: f32,
+    max_offset: f32,
+) {
+    let width = (0.5 * rect.height()).at_most(0.5 * rect.width());
+    let mut mesh = egui::Mesh::default();
+    let mut add_gradient = |rect: Rect, left_color, right_color| {
+        let index = mesh.vertices.len() as u32;
+        mesh.colored_vertex(rect.left_top(), left_color);
+        mesh.colored_vertex(rect.right_top(), right_color);
+        mesh.colored_vertex(rect.left_bottom(), left_color);
+        mesh.colored_vertex(rect.right_bottom(), right_color);
+        mesh.add_triangle(index, index + 1, index + 2);
+        mesh.add_triangle(index + 2, index + 1, index + 3);
+    };
+    if 0.5 < offset {
+        let left = Rect::from_x_y_ranges(rect.left()..=rect.left() + width, rect.y_range());
+        add_gradient(left, color, egui::Color32::TRANSPARENT);
+    }
+    if offset + 0.5 < max_offset {
+        let right = Rect::from_x_y_ranges(rect.right() - width..=rect.right(), rect.y_range());
+        add_gradient(right, egui::Color32::TRANSPARENT, color);
+    }
+    if !mesh.is_empty() {
+        painter.add(mesh);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Container, SimplificationOptions, Tile, UiResponse};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestBehavior {
+        edits: Vec<EditAction>,
+    }
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+
+        fn on_edit(&mut self, edit_action: EditAction) {
+            self.edits.push(edit_action);
+        }
+    }
+
+    fn tabs(num_tabs: u64, active: u64) -> Tabs {
+        Tabs {
+            active: Some(TileId(active)),
+            ..Tabs::new((0..num_tabs).map(TileId).collect())
+        }
+    }
+
+    #[test]
+    fn test_close_active_tab() {
+        let mut behavior = TestBehavior::default();
+
+        let mut first = tabs(3, 0);
+        assert_eq!(first.close(&mut behavior, 0), Some(TileId(0)));
+        assert_eq!(first.active, Some(TileId(1)), "The next tab becomes active");
+        assert_eq!(
+            behavior.edits,
+            vec![EditAction::TabClosed, EditAction::TabSelected]
+        );
+
+        let mut last = tabs(3, 2);
+        assert_eq!(last.close(&mut behavior, 2), Some(TileId(2)));
+        assert_eq!(
+            last.active,
+            Some(TileId(1)),
+            "The previous tab becomes active"
+        );
+
+        let mut only = tabs(1, 0);
+        behavior.edits.clear();
+        assert_eq!(only.close(&mut behavior, 0), Some(TileId(0)));
+        assert_eq!(only.active, None);
+        assert!(only.children.is_empty());
+        assert_eq!(behavior.edits, vec![EditAction::TabClosed]);
+
+        assert_eq!(only.close(&mut behavior, 0), None);
+    }
+
+    #[test]
+    fn test_move_tab() {
+        let mut behavior = TestBehavior::default();
+
+        let mut tabs = tabs(4, 0);
+        assert!(tabs.move_child(&mut behavior, 0, usize::MAX));
+        assert_eq!(tabs.children, [1, 2, 3, 0].map(TileId));
+        assert_eq!(tabs.active, Some(TileId(0)), "Moving keeps the active tab");
+        assert_eq!(behavior.edits, vec![EditAction::TabMoved]);
+        assert!(!tabs.move_child(&mut behavior, 4, 0));
+
+        // Tabs can't be moved across the boundary of the pinned tabs:
+        tabs.pin(TileId(2));
+        tabs.pin(TileId(3));
+        assert_eq!(tabs.children, [2, 3, 1, 0].map(TileId));
+        assert!(tabs.move_child(&mut behavior, 0, usize::MAX));
+        assert_eq!(tabs.children, [3, 2, 1, 0].map(TileId));
+        assert!(tabs.move_child(&mut behavior, 3, 0));
+        assert_eq!(tabs.children, [3, 2, 0, 1].map(TileId));
+    }
+
+    #[test]
+    fn test_insert_tab() {
+        let mut behavior = TestBehavior::default();
+
+        let mut tabs = Tabs::new(vec![]);
+        tabs.insert_child(&mut behavior, 5, TileId(0));
+        assert_eq!(tabs.active, Some(TileId(0)), "The first tab becomes active");
+        assert_eq!(
+            behavior.edits,
+            vec![EditAction::TabInserted, EditAction::TabSelected]
+        );
+
+        tabs.pin(TileId(0));
+        tabs.insert_child(&mut behavior, 0, TileId(1));
+        assert_eq!(tabs.children, [0, 1].map(TileId), "Pinned tabs stay first");
+        assert_eq!(tabs.active, Some(TileId(0)));
+    }
+
+    #[test]
+    fn test_document_area_is_kept_when_empty() {
+        let mut tiles = Tiles::default();
+        let sidebar = tiles.insert_pane(0);
+        let documents = tiles.insert_container(Tabs::new_document_area(vec![]));
+        let root = tiles.insert_horizontal_tile(vec![sidebar, documents]);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        let options = SimplificationOptions {
+            all_panes_must_have_tabs: false,
+            ..Default::default()
+        };
+        tree.simplify(&options);
+        assert_eq!(tree.tiles.document_area(), Some(documents));
+
+        let first = tree.open_pane(1);
+        let second = tree.open_pane(2);
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get_mut(documents) else {
+            panic!()
+        };
+        assert_eq!(tabs.children, vec![first, second]);
+        assert_eq!(tabs.active, Some(second));
+
+        // Closing all documents leaves the document area, even though it is a single child now:
+        tabs.retain(|_| false);
+        tree.remove_recursively(sidebar);
+        tree.simplify(&options);
+        assert_eq!(tree.root(), Some(documents));
+        assert!(tree.tiles.get_container(documents).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! Tools for inspecting and debugging a [`Tree`].
+//!
+//! Requires the `inspector` feature.
+
+use egui::Ui;
+
+use crate::{Behavior, Container, ContainerKind, Tile, TileId, Tree};
+
+/// Show the structure of the tree as collapsible headers, and let the user edit it.
+///
+/// For each tile this shows its title and [`TileId`], and a checkbox for its visibility.
+/// Containers get a combo box for their kind (see [`Tree::set_container_kind`]),
+/// and the shares of the children of [`crate::Linear`] and [`crate::Grid`] containers can be edited.
+pub fn tree_inspector<Pane>(ui: &mut Ui, tree: &mut Tree<Pane>, behavior: &mut dyn Behavior<Pane>) {
+    let Some(root) = tree.root() else {
+        ui.label("Empty tree");
+        return;
+    };
+
+    let mut kind_changes = vec![];
+    tile_inspector(ui, tree, behavior, root, None, &mut kind_changes);
+
+    // Applied afterwards, so that we don't change the tree while iterating over it:
+    for (tile_id, kind) in kind_changes {
+        tree.set_container_kind(behavior, tile_id, kind);
+    }
+}
+
+fn tile_inspector<Pane>(
+    ui: &mut Ui,
+    tree: &mut Tree<Pane>,
+    behavior: &mut dyn Behavior<Pane>,
+    tile_id: TileId,
+    parent_id: Option<TileId>,
+    kind_changes: &mut Vec<(TileId, ContainerKind)>,
+) {
+    let Some(kind) = tree.tiles.get(tile_id).map(|tile| tile.kind()) else {
+        ui.label(format!("Missing tile {tile_id:?}"));
+        return;
+    };
+    let title = behavior.tab_title_for_tile(&tree.tiles, tile_id);
+
+    let default_open = true;
+    egui::collapsing_header::CollapsingState::load_with_default_open(
+        ui.ctx(),
+        ui.id().with((tree.id(), tile_id, "tile_inspector")),
+        default_open,
+    )
+    .show_header(ui, |ui| {
+        ui.label(format!("{} - {tile_id:?}", title.text()));
+
+        let mut visible = tree.tiles.is_visible(tile_id);
+        if ui.checkbox(&mut visible, "Visible").changed() {
+            tree.tiles.set_visible(tile_id, visible);
+        }
+
+        if let Some(Tile::Container(Container::Linear(linear))) =
+            parent_id.and_then(|parent_id| tree.tiles.get_mut(parent_id))
+        {
+            ui.add(
+                egui::DragValue::new(&mut linear.shares[tile_id])
+                    .speed(0.01)
+                    .range(0.0..=f32::INFINITY)
+                    .prefix("share: "),
+            );
+        }
+    })
+    .body(|ui| {
+        let Some(mut new_kind) = kind else {
+            return; // Panes have nothing more to show
+        };
+
+        // Salted with the tree id, so that several trees can be inspected in the same `Ui`:
+        egui::ComboBox::from_id_salt((tree.id(), tile_id, "kind"))
+            .selected_text(format!("{new_kind:?}"))
+            .show_ui(ui, |ui| {
+                for kind in ContainerKind::ALL {
+                    ui.selectable_value(&mut new_kind, kind, format!("{kind:?}"));
+                }
+            });
+        if Some(new_kind) != kind {
+            kind_changes.push((tile_id, new_kind));
+        }
+
+        if let Some(Tile::Container(Container::Grid(grid))) = tree.tiles.get_mut(tile_id) {
+            shares_ui(ui, "Column shares:", &mut grid.col_shares);
+            shares_ui(ui, "Row shares:", &mut grid.row_shares);
+        }
+
+        let children = tree
+            .tiles
+            .get_container(tile_id)
+            .map(|container| container.children_vec())
+            .unwrap_or_default();
+        for child_id in children {
+            tile_inspector(ui, tree, behavior, child_id, Some(tile_id), kind_changes);
+        }
+    });
+}
+
+fn shares_ui(ui: &mut Ui, label: &str, shares: &mut [f32]) {
+    ui.horizontal_wrapped(|ui| {
+        ui.label(label);
+        for share in shares {
+            ui.add(
+                egui::DragValue::new(share)
+                    .speed(0.01)
+                    .range(0.0..=f32::INFINITY),
+            );
+        }
+    });
+}
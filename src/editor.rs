@@ -0,0 +1,231 @@
+use crate::behavior::EditAction;
+use crate::{Behavior, Container, ContainerKind, Tile, TileId, Tiles, Tree};
+
+/// Changes a [`Tree`] in several steps, see [`Tree::edit`].
+///
+/// The tree is not simplified between the steps,
+/// so it is fine to e.g. empty a container before filling it again.
+pub struct TreeEditor<'a, Pane> {
+    tree: &'a mut Tree<Pane>,
+    edited: bool,
+}
+
+impl<Pane> TreeEditor<'_, Pane> {
+    /// The tiles, as edited so far.
+    pub fn tiles(&self) -> &Tiles<Pane> {
+        &self.tree.tiles
+    }
+
+    /// Edit the tiles directly, for anything not covered by the other methods.
+    pub fn tiles_mut(&mut self) -> &mut Tiles<Pane> {
+        self.edited = true;
+        &mut self.tree.tiles
+    }
+
+    /// The root of the tree, as edited so far.
+    pub fn root(&self) -> Option<TileId> {
+        self.tree.root
+    }
+
+    /// Make the given tile the root of the tree.
+    ///
+    /// The old root is not removed: move it into the new root,
+    /// or it is dropped as unreachable by the next [`Tree::gc`].
+    pub fn set_root(&mut self, root: TileId) {
+        self.edited = true;
+        self.tree.root = Some(root);
+    }
+
+    /// Add a pane that is not yet part of any container.
+    ///
+    /// Use [`Self::move_tile`] to put it somewhere in the tree.
+    pub fn insert_pane(&mut self, pane: Pane) -> TileId {
+        self.edited = true;
+        self.tree.tiles.insert_pane(pane)
+    }
+
+    /// Add a container that is not yet part of any other container.
+    ///
+    /// Use [`Self::move_tile`] to put it somewhere in the tree.
+    pub fn insert_container(&mut self, container: impl Into<Container>) -> TileId {
+        self.edited = true;
+        self.tree.tiles.insert_container(container)
+    }
+
+    /// Move a tile to the given index of a container, see [`Tree::move_tile_to_container`].
+    ///
+    /// The tile may also be one that was just inserted.
+    pub fn move_tile(&mut self, tile_id: TileId, container_id: TileId, index: usize) {
+        self.edited = true;
+        self.tree
+            .move_tile_to_container(tile_id, container_id, index, false);
+    }
+
+    /// Remove a tile and all its children, see [`Tree::remove_recursively`].
+    pub fn remove(&mut self, tile_id: TileId) -> Vec<Tile<Pane>> {
+        self.edited = true;
+        self.tree.remove_recursively(tile_id)
+    }
+
+    /// Put a tile in a new container, see [`Tree::wrap_in_container`].
+    pub fn wrap_in_container(&mut self, tile_id: TileId, kind: ContainerKind) -> TileId {
+        self.edited = true;
+        self.tree.wrap_in_container_impl(tile_id, kind)
+    }
+
+    /// Set the share of a tile in its parent [`crate::Linear`] container.
+    ///
+    /// Returns `false` if the parent is not a [`crate::Linear`] container.
+    pub fn set_share(&mut self, tile_id: TileId, share: f32) -> bool {
+        let Some(parent_id) = self.tree.tiles.parent_of(tile_id) else {
+            return false;
+        };
+        let Some(Tile::Container(Container::Linear(linear))) = self.tree.tiles.get_mut(parent_id)
+        else {
+            return false;
+        };
+        linear.shares.set_share(tile_id, share);
+        self.edited = true;
+        true
+    }
+
+    /// Make a tile the active tab of its parent [`crate::Tabs`] container.
+    ///
+    /// Returns `false` if the parent is not a [`crate::Tabs`] container.
+    pub fn set_active_tab(&mut self, tile_id: TileId) -> bool {
+        let Some(parent_id) = self.tree.tiles.parent_of(tile_id) else {
+            return false;
+        };
+        let Some(Tile::Container(Container::Tabs(tabs))) = self.tree.tiles.get_mut(parent_id)
+        else {
+            return false;
+        };
+        tabs.set_active(tile_id);
+        self.edited = true;
+        true
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Make several changes to the tree at once.
+    ///
+    /// The tree is simplified and garbage-collected (unless [`Behavior::auto_simplify`] is `false`),
+    /// and every [`crate::Tabs`] container gets a valid active tab,
+    /// only once, after `edit` returns.
+    /// If anything was changed, [`Behavior::on_edit`] is then called with [`EditAction::TreeEdited`].
+    ///
+    /// ```
+    /// # use egui_tiles::*;
+    /// # struct MyBehavior;
+    /// # impl Behavior<String> for MyBehavior {
+    /// #     fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut String) -> UiResponse {
+    /// #         UiResponse::None
+    /// #     }
+    /// #     fn tab_title_for_pane(&mut self, pane: &String) -> egui::WidgetText {
+    /// #         pane.clone().into()
+    /// #     }
+    /// # }
+    /// let mut tree = Tree::new_tabs("tree", vec!["a".to_owned()]);
+    /// let root = tree.root().unwrap();
+    ///
+    /// tree.edit(&mut MyBehavior, |editor| {
+    ///     let b = editor.insert_pane("b".to_owned());
+    ///     let c = editor.insert_pane("c".to_owned());
+    ///     editor.move_tile(b, root, 1);
+    ///     editor.move_tile(c, root, 2);
+    ///     editor.set_active_tab(c);
+    /// });
+    /// ```
+    pub fn edit<R>(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        edit: impl FnOnce(&mut TreeEditor<'_, Pane>) -> R,
+    ) -> R {
+        let mut editor = TreeEditor {
+            tree: self,
+            edited: false,
+        };
+        let result = edit(&mut editor);
+
+        if editor.edited {
+            if behavior.auto_simplify() {
+                self.simplify(&behavior.simplification_options());
+                self.gc(behavior);
+            }
+            self.ensure_active_tabs();
+            behavior.on_edit(EditAction::TreeEdited);
+        }
+
+        result
+    }
+
+    /// Make sure every [`crate::Tabs`] container has an active tab, if it has any visible tabs.
+    fn ensure_active_tabs(&mut self) {
+        let tabs_ids: Vec<TileId> = self
+            .tiles
+            .iter()
+            .filter(|(_, tile)| matches!(tile, Tile::Container(Container::Tabs(_))))
+            .map(|(&tile_id, _)| tile_id)
+            .collect();
+        for tabs_id in tabs_ids {
+            // Take the tabs out while fixing them, since that needs the other tiles:
+            let Some(mut tile) = self.tiles.remove(tabs_id) else {
+                continue;
+            };
+            if let Tile::Container(Container::Tabs(tabs)) = &mut tile {
+                tabs.ensure_active(&self.tiles);
+            }
+            self.tiles.insert(tabs_id, tile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UiResponse;
+
+    use super::*;
+
+    struct TestBehavior {
+        num_edits: usize,
+    }
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+
+        fn on_edit(&mut self, _edit_action: EditAction) {
+            self.num_edits += 1;
+        }
+    }
+
+    #[test]
+    fn test_edit_simplifies_once() {
+        let mut tree = Tree::new_horizontal("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let panes: Vec<TileId> = tree.tiles.get_container(root).unwrap().children_vec();
+        let mut behavior = TestBehavior { num_edits: 0 };
+
+        // Move both panes into a new tab container, emptying the root for a while:
+        tree.edit(&mut behavior, |editor| {
+            let tabs = editor.insert_container(crate::Tabs::new(vec![]));
+            for (i, &pane) in panes.iter().enumerate() {
+                editor.move_tile(pane, tabs, i);
+            }
+            editor.move_tile(tabs, root, 0);
+            editor.set_active_tab(panes[0]);
+        });
+
+        assert_eq!(behavior.num_edits, 1);
+        let Some(Container::Tabs(tabs)) = tree.tiles.get_container(tree.root().unwrap()) else {
+            panic!("The single-child root should have been simplified away");
+        };
+        assert_eq!(tabs.children, panes);
+        assert_eq!(tabs.active, Some(panes[0]));
+    }
+}
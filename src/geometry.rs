@@ -0,0 +1,252 @@
+use egui::{Pos2, Rect};
+
+use crate::{ContainerKind, Tile, TileId, Tree};
+
+/// Where a single tile was laid out, as part of a [`TreeGeometry`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileGeometry {
+    pub tile_id: TileId,
+
+    /// The kind of container, or `None` for a pane.
+    pub kind: Option<ContainerKind>,
+
+    /// `None` for the root.
+    pub parent: Option<TileId>,
+
+    /// The full rect of the tile, including any tab bar.
+    pub rect: Rect,
+
+    /// Is this tile shown, i.e. visible and not in an inactive tab?
+    pub active: bool,
+}
+
+/// An owned copy of where all tiles of a [`Tree`] were laid out.
+///
+/// This borrows nothing from the tree, and is [`Send`] and [`Sync`],
+/// so it can be handed to background threads, e.g. for overlays or analytics.
+///
+/// See [`Tree::geometry_snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeGeometry {
+    pub root: Option<TileId>,
+
+    /// All tiles that were laid out, parents before their children.
+    pub tiles: Vec<TileGeometry>,
+}
+
+impl TreeGeometry {
+    /// The geometry of the given tile, if it was laid out.
+    pub fn get(&self, tile_id: TileId) -> Option<&TileGeometry> {
+        self.tiles.iter().find(|tile| tile.tile_id == tile_id)
+    }
+
+    /// The children of the given tile, in order.
+    pub fn children_of(&self, tile_id: TileId) -> impl Iterator<Item = &TileGeometry> + '_ {
+        self.tiles
+            .iter()
+            .filter(move |tile| tile.parent == Some(tile_id))
+    }
+
+    /// The active pane at the given position, if any.
+    pub fn pane_at(&self, pos: Pos2) -> Option<&TileGeometry> {
+        self.tiles
+            .iter()
+            .find(|tile| tile.kind.is_none() && tile.active && tile.rect.contains(pos))
+    }
+}
+
+#[cfg(feature = "svg")]
+impl TreeGeometry {
+    /// Draw the layout as an SVG image, e.g. for documentation or bug reports.
+    ///
+    /// Draws the frames of all active containers and panes, the tab bars, and a label for each pane.
+    /// The gaps between tiles are left empty.
+    pub fn to_svg(&self, labels: impl Fn(TileId) -> String) -> String {
+        use std::fmt::Write as _;
+
+        let Some(bounds) = self
+            .root
+            .and_then(|root| self.get(root))
+            .map(|root| root.rect)
+        else {
+            return r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0"></svg>"#
+                .to_owned();
+        };
+
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+            bounds.min.x,
+            bounds.min.y,
+            bounds.width(),
+            bounds.height(),
+            bounds.width(),
+            bounds.height(),
+        );
+        svg.push('\n');
+
+        for tile in self.tiles.iter().filter(|tile| tile.active) {
+            let rect = tile.rect;
+            match tile.kind {
+                None => {
+                    writeln!(
+                        svg,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#f4f4f4" stroke="#888"/>"##,
+                        rect.min.x,
+                        rect.min.y,
+                        rect.width(),
+                        rect.height(),
+                    )
+                    .ok();
+                    let center = rect.center();
+                    writeln!(
+                        svg,
+                        r##"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="middle" font-family="sans-serif" font-size="12" fill="#222">{}</text>"##,
+                        center.x,
+                        center.y,
+                        escape_xml(&labels(tile.tile_id)),
+                    )
+                    .ok();
+                }
+                Some(kind) => {
+                    if kind == ContainerKind::Tabs {
+                        // The tab bar is whatever is above the active tab:
+                        let content_top = self
+                            .children_of(tile.tile_id)
+                            .find(|child| child.active)
+                            .map_or(rect.max.y, |child| child.rect.min.y);
+                        let tab_bar_height = content_top - rect.min.y;
+                        if 0.0 < tab_bar_height {
+                            writeln!(
+                                svg,
+                                r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#d0d0d0"/>"##,
+                                rect.min.x,
+                                rect.min.y,
+                                rect.width(),
+                                tab_bar_height,
+                            )
+                            .ok();
+                        }
+                    }
+                    writeln!(
+                        svg,
+                        r##"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="#444" stroke-dasharray="4 2"/>"##,
+                        rect.min.x,
+                        rect.min.y,
+                        rect.width(),
+                        rect.height(),
+                    )
+                    .ok();
+                }
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+#[cfg(feature = "svg")]
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl<Pane> Tree<Pane> {
+    /// Copy out where all tiles were laid out during the last call to [`Self::ui`].
+    pub fn geometry_snapshot(&self) -> TreeGeometry {
+        let mut geometry = TreeGeometry {
+            root: self.root,
+            tiles: vec![],
+        };
+        if let Some(root) = self.root {
+            let active = self.is_visible(root);
+            self.collect_geometry(root, None, active, &mut geometry.tiles);
+        }
+        geometry
+    }
+
+    fn collect_geometry(
+        &self,
+        tile_id: TileId,
+        parent: Option<TileId>,
+        active: bool,
+        out: &mut Vec<TileGeometry>,
+    ) {
+        let (Some(tile), Some(rect)) = (self.tiles.get(tile_id), self.tiles.rect(tile_id)) else {
+            return;
+        };
+
+        out.push(TileGeometry {
+            tile_id,
+            kind: tile.kind(),
+            parent,
+            rect,
+            active,
+        });
+
+        if let Tile::Container(container) = tile {
+            let active_children: ahash::HashSet<TileId> =
+                container.active_children().copied().collect();
+            for &child_id in container.children() {
+                let child_active =
+                    active && self.is_visible(child_id) && active_children.contains(&child_id);
+                self.collect_geometry(child_id, Some(tile_id), child_active, out);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tree_geometry_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TreeGeometry>();
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn test_to_svg() {
+    let tabs = TileId::from_u64(1);
+    let [first, second] = [2, 3].map(TileId::from_u64);
+    let pane = |tile_id, rect, active| TileGeometry {
+        tile_id,
+        kind: None,
+        parent: Some(tabs),
+        rect,
+        active,
+    };
+    let content = Rect::from_min_max(egui::pos2(0.0, 20.0), egui::pos2(200.0, 100.0));
+    let geometry = TreeGeometry {
+        root: Some(tabs),
+        tiles: vec![
+            TileGeometry {
+                tile_id: tabs,
+                kind: Some(ContainerKind::Tabs),
+                parent: None,
+                rect: Rect::from_min_max(Pos2::ZERO, egui::pos2(200.0, 100.0)),
+                active: true,
+            },
+            pane(first, content, true),
+            pane(second, content, false),
+        ],
+    };
+
+    // The inactive tab is left out, and labels are escaped:
+    let svg = geometry.to_svg(|tile_id| format!("<{tile_id}>"));
+    assert_eq!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 100" width="200" height="100">
+<rect x="0" y="0" width="200" height="20" fill="#d0d0d0"/>
+<rect x="0" y="0" width="200" height="100" fill="none" stroke="#444" stroke-dasharray="4 2"/>
+<rect x="0" y="20" width="200" height="80" fill="#f4f4f4" stroke="#888"/>
+<text x="100" y="60" text-anchor="middle" dominant-baseline="middle" font-family="sans-serif" font-size="12" fill="#222">&lt;#2&gt;</text>
+</svg>
+"##
+    );
+
+    assert_eq!(
+        TreeGeometry::default().to_svg(|_| String::new()),
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0"></svg>"#
+    );
+}
@@ -0,0 +1,149 @@
+use std::marker::PhantomData;
+
+use crate::{ContainerKind, Tile, TileId, Tree};
+
+/// A long-lived reference to a tile in a specific [`Tree`], created with [`Tree::handle`].
+///
+/// Unlike a bare [`TileId`], a handle never resolves to a tile of a tree with another [`Tree::id`],
+/// and stops resolving once the tile has been removed.
+/// Since a removed [`TileId`] is never reused, the handle won't resolve to a newer tile either.
+///
+/// The handle also stops resolving when another kind of tile takes over the id,
+/// e.g. when a new container takes the place of the tile in a drop
+/// (see [`crate::DropResult::wrapped_tile`]), or when [`Tree::set_container_kind`] converts it.
+///
+/// A handle stays valid when the tree is cloned or serialized and deserialized.
+/// Re-inserting a removed tile under the same [`TileId`] (e.g. with [`crate::Tiles::insert`])
+/// makes the handle resolve again.
+pub struct TileHandle<Pane> {
+    tree_id: egui::Id,
+    tile_id: TileId,
+
+    /// The kind of the tile when the handle was created, `None` for panes and placeholders.
+    kind: Option<ContainerKind>,
+
+    _pane: PhantomData<fn() -> Pane>,
+}
+
+impl<Pane> Clone for TileHandle<Pane> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Pane> Copy for TileHandle<Pane> {}
+
+impl<Pane> PartialEq for TileHandle<Pane> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tree_id == other.tree_id && self.tile_id == other.tile_id && self.kind == other.kind
+    }
+}
+
+impl<Pane> Eq for TileHandle<Pane> {}
+
+impl<Pane> std::hash::Hash for TileHandle<Pane> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.tree_id.hash(state);
+        self.tile_id.hash(state);
+    }
+}
+
+impl<Pane> std::fmt::Debug for TileHandle<Pane> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TileHandle")
+            .field("tree_id", &self.tree_id)
+            .field("tile_id", &self.tile_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Pane> TileHandle<Pane> {
+    /// The id of the tile, without any guarantee that it is still in the tree.
+    pub fn tile_id(&self) -> TileId {
+        self.tile_id
+    }
+
+    /// Is this the tile the handle was created for, in the tree it was created from?
+    fn refers_to(&self, tree: &Tree<Pane>, tile: &Tile<Pane>) -> bool {
+        self.tree_id == tree.id() && self.kind == tile.kind()
+    }
+
+    /// The tile, if it is still in the tree the handle was created from.
+    pub fn resolve<'t>(&self, tree: &'t Tree<Pane>) -> Option<&'t Tile<Pane>> {
+        let tile = tree.tiles.get(self.tile_id)?;
+        self.refers_to(tree, tile).then_some(tile)
+    }
+
+    /// The tile, if it is still in the tree the handle was created from.
+    pub fn resolve_mut<'t>(&self, tree: &'t mut Tree<Pane>) -> Option<&'t mut Tile<Pane>> {
+        let tile = tree.tiles.get(self.tile_id)?;
+        if self.refers_to(tree, tile) {
+            tree.tiles.get_mut(self.tile_id)
+        } else {
+            None
+        }
+    }
+
+    /// Is the tile still in the tree the handle was created from?
+    pub fn is_valid(&self, tree: &Tree<Pane>) -> bool {
+        self.resolve(tree).is_some()
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// A [`TileHandle`] to the given tile, or `None` if it is not in the tree.
+    ///
+    /// Prefer a handle over a bare [`TileId`] when the reference outlives the current frame,
+    /// e.g. when it is stored in application state.
+    pub fn handle(&self, tile_id: TileId) -> Option<TileHandle<Pane>> {
+        let tile = self.tiles.get(tile_id)?;
+        Some(TileHandle {
+            tree_id: self.id(),
+            tile_id,
+            kind: tile.kind(),
+            _pane: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ContainerInsertion, InsertionPoint};
+
+    use super::*;
+
+    #[test]
+    fn test_handle_is_invalidated() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2]);
+        let other_tree = Tree::new_tabs("other_tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let first = tree.tiles.get_container(root).unwrap().children_vec()[0];
+
+        let handle = tree.handle(first).unwrap();
+        assert!(matches!(handle.resolve(&tree), Some(Tile::Pane(1))));
+        assert!(handle.is_valid(&tree.clone()));
+        assert!(!handle.is_valid(&other_tree));
+
+        tree.remove_recursively(first);
+        assert!(handle.resolve(&tree).is_none());
+    }
+
+    #[test]
+    fn test_handle_is_invalidated_by_wrapping() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let [first, second] = tree.tiles.get_container(root).unwrap().children_vec()[..] else {
+            panic!("Expected two tabs");
+        };
+        let handle = tree.handle(first).unwrap();
+
+        // Split the first pane by dropping the second one below it:
+        let insertion = InsertionPoint::new(first, ContainerInsertion::Vertical(1));
+        tree.move_tile(second, insertion, false);
+        assert!(tree.tiles.get(first).unwrap().is_container());
+        assert!(
+            handle.resolve(&tree).is_none(),
+            "The handle must not resolve to the new container"
+        );
+    }
+}
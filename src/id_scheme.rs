@@ -0,0 +1,32 @@
+/// How a [`crate::Tree`] derives the [`egui::Id`]s of its widgets and of its per-frame state,
+/// like tab buttons, drags and drop previews.
+///
+/// Set with [`crate::Tree::set_id_scheme`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum IdScheme {
+    /// Derive the ids from [`crate::Tree::id`] and the [`crate::TileId`] (or tile name) only.
+    ///
+    /// The ids are stable no matter where the tree is shown,
+    /// but showing the same tree twice in one frame makes the ids clash.
+    #[default]
+    Tree,
+
+    /// Also derive the ids from the [`egui::Ui`] the tree is shown in.
+    ///
+    /// Use this to show the same tree in several places, e.g. in two windows.
+    /// Each place then gets its own drag state, so a tile dragged in one place
+    /// can only be dropped in that same place.
+    Ui,
+}
+
+impl IdScheme {
+    /// The id that all widget ids of a tree with the given id are derived from,
+    /// when shown in a [`egui::Ui`] with the id `ui_id`.
+    pub fn base_id(self, tree_id: egui::Id, ui_id: egui::Id) -> egui::Id {
+        match self {
+            Self::Tree => tree_id,
+            Self::Ui => tree_id.with(("ui", ui_id)),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use egui::{Key, KeyboardShortcut, Modifiers};
+
+/// Keyboard shortcuts handled by [`crate::Tree::ui`].
+///
+/// Return this from [`crate::Behavior::keybindings`] to enable them.
+///
+/// The shortcuts act on the _focused_ pane (the pane the user last clicked in, see [`crate::Tree::focused_pane`]),
+/// and the closest [`crate::Tabs`] container above it.
+///
+/// The [`Default`] keymap has no shortcuts. Use [`Self::standard`] for a common set.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TreeKeymap {
+    /// The n:th shortcut activates the n:th tab.
+    pub activate_tab: Vec<KeyboardShortcut>,
+
+    /// Activate the next tab, wrapping around.
+    pub next_tab: Option<KeyboardShortcut>,
+
+    /// Activate the previous tab, wrapping around.
+    pub previous_tab: Option<KeyboardShortcut>,
+
+    /// Close the active tab.
+    ///
+    /// This respects [`crate::Behavior::is_tab_closable`] and [`crate::Behavior::on_tab_close`].
+    pub close_tab: Option<KeyboardShortcut>,
+
+    /// Move the active tab into a new split to the right of its tab container.
+    pub split_right: Option<KeyboardShortcut>,
+
+    /// Move the active tab into a new split below its tab container.
+    pub split_down: Option<KeyboardShortcut>,
+
+    /// Move the focus to the next visible pane, wrapping around.
+    pub focus_next_pane: Option<KeyboardShortcut>,
+}
+
+impl TreeKeymap {
+    /// `Cmd/Ctrl+1` … `Cmd/Ctrl+9` to activate tabs,
+    /// `Ctrl+Tab` and `Ctrl+Shift+Tab` to cycle tabs,
+    /// `Cmd/Ctrl+W` to close a tab,
+    /// `Cmd/Ctrl+\` and `Cmd/Ctrl+Shift+\` to split,
+    /// and `F6` to move the focus to the next pane.
+    pub fn standard() -> Self {
+        let number_keys = [
+            Key::Num1,
+            Key::Num2,
+            Key::Num3,
+            Key::Num4,
+            Key::Num5,
+            Key::Num6,
+            Key::Num7,
+            Key::Num8,
+            Key::Num9,
+        ];
+
+        Self {
+            activate_tab: number_keys
+                .into_iter()
+                .map(|key| KeyboardShortcut::new(Modifiers::COMMAND, key))
+                .collect(),
+            next_tab: Some(KeyboardShortcut::new(Modifiers::CTRL, Key::Tab)),
+            previous_tab: Some(KeyboardShortcut::new(
+                Modifiers::CTRL | Modifiers::SHIFT,
+                Key::Tab,
+            )),
+            close_tab: Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::W)),
+            split_right: Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Backslash)),
+            split_down: Some(KeyboardShortcut::new(
+                Modifiers::COMMAND | Modifiers::SHIFT,
+                Key::Backslash,
+            )),
+            focus_next_pane: Some(KeyboardShortcut::new(Modifiers::NONE, Key::F6)),
+        }
+    }
+}
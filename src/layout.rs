@@ -0,0 +1,93 @@
+use crate::{Container, ContainerKind, TileId, Tiles};
+
+/// A plain-data description of a layout, used with [`crate::Tree::from_layout_fn`].
+///
+/// ```
+/// use egui_tiles::{Layout, Tree};
+///
+/// let layout = Layout::Horizontal(vec![
+///     Layout::Pane("sidebar"),
+///     Layout::Tabs(vec![Layout::Pane("viewport"), Layout::Pane("settings")]).with_share(3.0),
+/// ]);
+///
+/// let (tree, tile_ids) = Tree::from_layout_fn("my_tree", layout, |pane| *pane);
+/// assert!(tree.tiles.get_pane(&tile_ids["viewport"]).is_some());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum Layout<Pane> {
+    /// A leaf.
+    Pane(Pane),
+
+    /// A [`crate::Tabs`] container.
+    Tabs(Vec<Self>),
+
+    /// A horizontal [`crate::Linear`] container.
+    Horizontal(Vec<Self>),
+
+    /// A vertical [`crate::Linear`] container.
+    Vertical(Vec<Self>),
+
+    /// A [`crate::Grid`] container.
+    Grid(Vec<Self>),
+
+    /// Give the inner layout this share of its parent linear container.
+    ///
+    /// The default share is `1`. This is ignored if the parent is not a linear container.
+    /// See also [`Self::with_share`].
+    Share(f32, Box<Self>),
+}
+
+impl<Pane> Layout<Pane> {
+    /// Give this layout the given share of its parent linear container.
+    #[inline]
+    pub fn with_share(self, share: f32) -> Self {
+        Self::Share(share, Box::new(self))
+    }
+
+    /// Insert the tiles of this layout, returning the id of the top tile.
+    ///
+    /// `on_pane` is called for each inserted pane.
+    pub(crate) fn insert_into(
+        self,
+        tiles: &mut Tiles<Pane>,
+        on_pane: &mut dyn FnMut(&Pane, TileId),
+    ) -> TileId {
+        let (kind, children) = match self {
+            Self::Pane(pane) => {
+                let tile_id = tiles.insert_pane(pane);
+                if let Some(pane) = tiles.get_pane(&tile_id) {
+                    on_pane(pane, tile_id);
+                }
+                return tile_id;
+            }
+            Self::Share(_, layout) => return layout.insert_into(tiles, on_pane),
+            Self::Tabs(children) => (ContainerKind::Tabs, children),
+            Self::Horizontal(children) => (ContainerKind::Horizontal, children),
+            Self::Vertical(children) => (ContainerKind::Vertical, children),
+            Self::Grid(children) => (ContainerKind::Grid, children),
+        };
+
+        let mut child_ids = Vec::with_capacity(children.len());
+        let mut shares = vec![];
+        for child in children {
+            let share = if let Self::Share(share, _) = &child {
+                Some(*share)
+            } else {
+                None
+            };
+            let child_id = child.insert_into(tiles, on_pane);
+            child_ids.push(child_id);
+            if let Some(share) = share {
+                shares.push((child_id, share));
+            }
+        }
+
+        let mut container = Container::new(kind, child_ids);
+        if let Container::Linear(linear) = &mut container {
+            for (child_id, share) in shares {
+                linear.shares.set_share(child_id, share);
+            }
+        }
+        tiles.insert_container(container)
+    }
+}
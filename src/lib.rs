@@ -87,6 +87,16 @@
 //! the total shares are always approximately the same as the number of rows/columns.
 //! This makes it easy to add new rows/columns.
 //!
+//! ## Layout presets
+//! A [`LayoutPreset`] describes a layout in a human-editable text format,
+//! referring to panes by string keys instead of [`TileId`]s.
+//! See the [`preset`] module for details.
+//!
+//! ## Thread safety
+//! [`Tree`] and [`Tiles`] are [`Send`] if the pane type is, and [`Sync`] if the pane type is both [`Send`] and [`Sync`],
+//! so a layout can be built or changed on a worker thread and then handed to the ui thread.
+//! To change a tree that is being shown from another thread, use a [`TreeCommandSender`].
+//!
 //! ## Shortcomings
 //! The implementation is recursive, so if your trees get too deep you will get a stack overflow.
 //!
@@ -117,16 +127,51 @@
 use egui::{Pos2, Rect};
 
 mod behavior;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod command;
 mod container;
+#[cfg(feature = "inspector")]
+pub mod debug;
+mod editor;
+mod geometry;
+mod handle;
+mod id_scheme;
+mod keymap;
+mod layout;
+pub mod preset;
+mod recording;
+mod registry;
+mod sidebar;
+mod stats;
 mod tile;
+mod tile_store;
 mod tiles;
 mod tree;
 
-pub use behavior::{Behavior, EditAction, TabState};
-pub use container::{Container, ContainerKind, Grid, GridLayout, Linear, LinearDir, Shares, Tabs};
-pub use tile::{Tile, TileId};
+pub use behavior::{
+    AttentionLevel, Behavior, CloseButtonVisibility, CloseResponse, DragEnd, DragOrigin,
+    DropOutsideAction, DropResult, EditAction, GrabOffset, GridDrop, GridDropPreview, OrphanPolicy,
+    OverflowPolicy, SplitterDoubleClick, TabAlignment, TabBarWheel, TabScrollArrow, TabState,
+};
+pub use command::{TreeCommand, TreeCommandSender};
+pub use container::{
+    Container, ContainerKind, Grid, GridHoleCollapse, GridLayout, Linear, LinearDir, Shares, Tabs,
+};
+pub use editor::TreeEditor;
+pub use geometry::{TileGeometry, TreeGeometry};
+pub use handle::TileHandle;
+pub use id_scheme::IdScheme;
+pub use keymap::TreeKeymap;
+pub use layout::Layout;
+pub use preset::LayoutPreset;
+pub use recording::{LayoutEvent, LayoutScript};
+pub use registry::{KeyedPane, PaneRegistry, RegisteredPane};
+pub use sidebar::Side;
+pub use stats::TreeStats;
+pub use tile::{PaneDrop, Tile, TileId, TileUiTag};
 pub use tiles::Tiles;
-pub use tree::Tree;
+pub use tree::{Tree, TreeResponse};
 
 // ----------------------------------------------------------------------------
 
@@ -146,7 +191,7 @@ pub enum UiResponse {
 /// Drag-dropping tiles can often leave containers empty, or with only a single child.
 /// The [`SimplificationOptions`] specifies what simplifications are allowed.
 ///
-/// The [`Tree`] will run a simplification pass each frame.
+/// The [`Tree`] will run a simplification pass each frame, unless [`Behavior::auto_simplify`] is `false`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct SimplificationOptions {
     /// Remove empty [`Tabs`] containers?
@@ -171,6 +216,17 @@ pub struct SimplificationOptions {
     /// If a horizontal container contain another horizontal container, join them?
     /// Same for vertical containers. Does NOT apply to grid container or tab containers.
     pub join_nested_linear_containers: bool,
+
+    /// Convert a [`Grid`] whose children are all in a single row (or column)
+    /// into a horizontal (or vertical) [`Linear`] container, keeping the shares?
+    ///
+    /// Together with [`Self::join_nested_linear_containers`], such a grid is then joined
+    /// with a parent of the same direction.
+    ///
+    /// Only applies to grids with a fixed number of columns ([`GridLayout::Columns`]),
+    /// since the shape of a [`GridLayout::Auto`] grid depends on the available space,
+    /// and not to subgrids or grids with frozen rows or columns.
+    pub join_single_line_grids: bool,
 }
 
 impl SimplificationOptions {
@@ -192,6 +248,7 @@ impl SimplificationOptions {
         prune_single_child_containers: false,
         all_panes_must_have_tabs: false,
         join_nested_linear_containers: false,
+        join_single_line_grids: false,
     };
 }
 
@@ -204,6 +261,7 @@ impl Default for SimplificationOptions {
             prune_single_child_containers: true,
             all_panes_must_have_tabs: false,
             join_nested_linear_containers: true,
+            join_single_line_grids: false,
         }
     }
 }
@@ -222,6 +280,25 @@ pub enum ResizeState {
 
 // ----------------------------------------------------------------------------
 
+// Keep the thread safety documented above:
+const _: () = {
+    const fn assert_send<T: Send>() {}
+    const fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Tree<String>>();
+    assert_send_sync::<Tiles<String>>();
+    assert_send_sync::<TileHandle<String>>();
+    assert_send_sync::<TreeCommandSender<String>>();
+    assert_send_sync::<LayoutScript>();
+
+    // `Send`, but not `Sync`, panes:
+    assert_send::<Tree<std::cell::Cell<u32>>>();
+    assert_send::<Tiles<std::cell::Cell<u32>>>();
+    assert_send::<TreeCommandSender<std::cell::Cell<u32>>>();
+};
+
+// ----------------------------------------------------------------------------
+
 /// An insertion point in a specific container.
 ///
 /// Specifies the expected container layout type, and where to insert.
@@ -234,6 +311,15 @@ enum ContainerInsertion {
 }
 
 impl ContainerInsertion {
+    fn new(kind: ContainerKind, index: usize) -> Self {
+        match kind {
+            ContainerKind::Tabs => Self::Tabs(index),
+            ContainerKind::Horizontal => Self::Horizontal(index),
+            ContainerKind::Vertical => Self::Vertical(index),
+            ContainerKind::Grid => Self::Grid(index),
+        }
+    }
+
     /// Where in the parent (in what order among its children).
     fn index(self) -> usize {
         match self {
@@ -255,7 +341,7 @@ impl ContainerInsertion {
 }
 
 /// Where in the tree to insert a tile.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct InsertionPoint {
     pub parent_id: TileId,
 
@@ -285,9 +371,51 @@ enum SimplifyAction {
     Replace(TileId),
 }
 
-pub(crate) fn is_being_dragged(ctx: &egui::Context, tree_id: egui::Id, tile_id: TileId) -> bool {
+pub(crate) fn is_being_dragged<Pane>(
+    ctx: &egui::Context,
+    tree: &Tree<Pane>,
+    tile_id: TileId,
+) -> bool {
+    if is_middle_button_drag(ctx) {
+        return false; // This scrolls the tab bar instead
+    }
     let dragged_id = ctx.dragged_id().or(ctx.drag_stopped_id());
-    dragged_id == Some(tile_id.egui_id(tree_id))
+    dragged_id == Some(tree.tile_egui_id(tile_id))
+}
+
+/// Is this position covered by a layer other than the one of the `Ui`,
+/// e.g. a window floating above the tree?
+pub(crate) fn is_covered_by_other_layer(ui: &egui::Ui, pos: Pos2) -> bool {
+    ui.ctx()
+        .layer_id_at(pos)
+        .is_some_and(|layer_id| layer_id != ui.layer_id())
+}
+
+/// Is the middle mouse button held down (or just released) on its own?
+///
+/// Dragging with the middle mouse button scrolls the tab bar, and never drags tiles.
+pub(crate) fn is_middle_button_drag(ctx: &egui::Context) -> bool {
+    ctx.input(|i| {
+        let middle =
+            i.pointer.middle_down() || i.pointer.button_released(egui::PointerButton::Middle);
+        middle && !i.pointer.primary_down()
+    })
+}
+
+/// Make sure a layout rect has a finite position and a non-negative size.
+fn sanitize_rect(rect: Rect) -> Rect {
+    if !rect.min.is_finite() {
+        return Rect::from_min_max(Pos2::ZERO, Pos2::ZERO);
+    }
+    // NOTE: `f32::max` ignores NaN.
+    Rect::from_min_size(rect.min, rect.size().max(egui::Vec2::ZERO))
+}
+
+/// Is this rect too small to interact with?
+///
+/// This happens when a tree is squeezed into a tiny space, e.g. a collapsing panel.
+fn is_degenerate(rect: Rect) -> bool {
+    !rect.is_finite() || rect.width() < 1.0 || rect.height() < 1.0
 }
 
 /// If this tile is currently being dragged, cover it with a semi-transparent overlay ([`Behavior::dragged_overlay_color`]).
@@ -297,7 +425,7 @@ fn cover_tile_if_dragged<Pane>(
     ui: &egui::Ui,
     tile_id: TileId,
 ) {
-    if is_being_dragged(ui.ctx(), tree.id, tile_id) {
+    if is_being_dragged(ui.ctx(), tree, tile_id) {
         if let Some(child_rect) = tree.tiles.rect(tile_id) {
             let overlay_color = behavior.dragged_overlay_color(ui.visuals());
             ui.painter().rect_filled(child_rect, 0.0, overlay_color);
@@ -317,8 +445,34 @@ struct DropContext {
     mouse_pos: Option<Pos2>,
 
     best_insertion: Option<InsertionPoint>,
-    best_dist_sq: f32,
+    best_score: f32,
     preview_rect: Option<Rect>,
+
+    /// The insertion point chosen last frame, which is favored to avoid flickering.
+    previous_insertion: Option<InsertionPoint>,
+
+    /// Where the preview of the dragged tile was shown last frame, if anywhere.
+    drag_preview_rect: Option<Rect>,
+
+    /// The pane that the dragged tile is over, if that pane accepts the drop itself.
+    ///
+    /// See [`Behavior::pane_accepts_drop`].
+    pane_target: Option<TileId>,
+
+    /// The insertion point shown as a ghost tab button in a tab bar this frame, if any.
+    ///
+    /// See [`Behavior::ghost_tab_drop_preview`].
+    ghost_tab: Option<InsertionPoint>,
+
+    /// The kind of root container to create if the dragged tile is dropped on a tree without a root.
+    ///
+    /// See [`Behavior::empty_tree_root_kind`].
+    empty_tree_root: Option<ContainerKind>,
+
+    /// The kinds of containers the dragged tile may be dropped into.
+    ///
+    /// See [`Behavior::is_drop_allowed`].
+    allowed_kinds: Vec<ContainerKind>,
 }
 
 impl DropContext {
@@ -334,47 +488,89 @@ impl DropContext {
             return;
         }
 
+        let fraction = behavior.split_drop_fraction().clamp(0.0, 1.0);
+
         if tile.kind() != Some(ContainerKind::Horizontal) {
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(0)),
-                rect.split_left_right_at_fraction(0.5).0,
+                rect.split_left_right_at_fraction(fraction).0,
             );
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Horizontal(usize::MAX)),
-                rect.split_left_right_at_fraction(0.5).1,
+                rect.split_left_right_at_fraction(1.0 - fraction).1,
             );
         }
 
         if tile.kind() != Some(ContainerKind::Vertical) {
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Vertical(0)),
-                rect.split_top_bottom_at_fraction(0.5).0,
+                rect.split_top_bottom_at_fraction(fraction).0,
             );
             self.suggest_rect(
                 InsertionPoint::new(parent_id, ContainerInsertion::Vertical(usize::MAX)),
-                rect.split_top_bottom_at_fraction(0.5).1,
+                rect.split_top_bottom_at_fraction(1.0 - fraction).1,
             );
         }
 
         self.suggest_rect(
             InsertionPoint::new(parent_id, ContainerInsertion::Tabs(usize::MAX)),
-            rect.split_top_bottom_at_y(rect.top() + behavior.tab_bar_height(style))
+            rect.split_top_bottom_at_y(rect.top() + behavior.tab_bar_drop_band_thickness(style))
                 .1,
         );
     }
 
+    /// The tree has no root, so the whole tree is a drop target, creating a root of the given kind.
+    fn on_empty_tree(&mut self, kind: ContainerKind, rect: Rect) {
+        if self.enabled
+            && self.mouse_pos.is_some()
+            && !is_degenerate(rect)
+            && self.allowed_kinds.contains(&kind)
+        {
+            self.empty_tree_root = Some(kind);
+            self.preview_rect = Some(rect);
+        }
+    }
+
     fn suggest_rect(&mut self, insertion: InsertionPoint, preview_rect: Rect) {
-        if !self.enabled {
+        if !self.enabled
+            || is_degenerate(preview_rect)
+            || !self.allowed_kinds.contains(&insertion.insertion.kind())
+        {
             return;
         }
-        let target_point = preview_rect.center();
         if let Some(mouse_pos) = self.mouse_pos {
-            let dist_sq = mouse_pos.distance_sq(target_point);
-            if dist_sq < self.best_dist_sq {
-                self.best_dist_sq = dist_sq;
+            let score = self.score(mouse_pos, insertion, preview_rect);
+            if score < self.best_score {
+                self.best_score = score;
                 self.best_insertion = Some(insertion);
                 self.preview_rect = Some(preview_rect);
             }
         }
     }
+
+    /// Lower is better.
+    fn score(&self, mouse_pos: Pos2, insertion: InsertionPoint, preview_rect: Rect) -> f32 {
+        /// How much a full overlap with the drag preview counts.
+        const OVERLAP_WEIGHT: f32 = 1.0;
+
+        /// Applied to the insertion point chosen last frame,
+        /// so that a candidate needs to be clearly better to replace it.
+        const HYSTERESIS: f32 = 0.7;
+
+        let mut score = mouse_pos.distance_sq(preview_rect.center());
+
+        if let Some(drag_preview_rect) = self.drag_preview_rect {
+            let overlap = drag_preview_rect.intersect(preview_rect);
+            if overlap.is_positive() && drag_preview_rect.area() > 0.0 {
+                let overlap_fraction = overlap.area() / drag_preview_rect.area();
+                score /= 1.0 + OVERLAP_WEIGHT * overlap_fraction;
+            }
+        }
+
+        if self.previous_insertion == Some(insertion) {
+            score *= HYSTERESIS;
+        }
+
+        score
+    }
 }
@@ -0,0 +1,600 @@
+//! A human-editable text format for layout presets.
+//!
+//! A preset describes the shape of a [`Tree`] without any [`TileId`]s.
+//! Panes are instead referred to by string _keys_, which you resolve into actual panes
+//! when calling [`LayoutPreset::instantiate`].
+//!
+//! This makes presets suitable for checking default layouts into a repository,
+//! and tweaking them without recompiling.
+//!
+//! ## Format
+//! ```text
+//! # Comments start with `#` and run to the end of the line.
+//! horizontal {
+//!     pane "sidebar"
+//!     vertical share=3 {
+//!         tabs {
+//!             pane "viewport"
+//!             pane "settings"
+//!         }
+//!         pane "console" share=0.5
+//!     }
+//! }
+//! ```
+//!
+//! * A preset consists of a single root node.
+//! * A node is either `pane "key"`, or a container: `tabs`, `horizontal`, `vertical` or `grid`,
+//!   followed by its children in `{ }`.
+//! * Children of `horizontal` and `vertical` containers can have a `share=<number>` (default `1`).
+//!   Shares must be positive.
+//!   See [the crate-level documentation](crate) for how shares work.
+//! * A `grid` can have `columns=<count>`, which sets [`GridLayout::Columns`].
+//!   Without it, the grid uses [`GridLayout::Auto`].
+
+use crate::{Container, ContainerKind, GridLayout, Tile, TileId, Tiles, Tree};
+
+/// How deeply containers may be nested in a preset.
+///
+/// Guards against running out of stack on a malicious or broken preset.
+const MAX_DEPTH: usize = 64;
+
+/// A layout preset, parsed from the text format described in [the module docs](self).
+///
+/// Each value is one node of the layout, with the root node describing the whole [`Tree`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayoutPreset {
+    /// A pane, to be resolved from its key by [`LayoutPreset::instantiate`].
+    Pane {
+        key: String,
+
+        /// The share of this pane in its parent linear container, if not the default.
+        share: Option<f32>,
+    },
+
+    /// A container of more nodes.
+    Container {
+        kind: ContainerKind,
+
+        /// The share of this container in its parent linear container, if not the default.
+        share: Option<f32>,
+
+        /// The number of columns, if this is a grid with [`GridLayout::Columns`].
+        columns: Option<usize>,
+
+        children: Vec<Self>,
+    },
+}
+
+/// An error from [`LayoutPreset::parse`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PresetError {
+    /// The line the error was found on, starting at 1.
+    pub line: usize,
+
+    pub message: String,
+}
+
+impl std::fmt::Display for PresetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for PresetError {}
+
+impl LayoutPreset {
+    /// Parse a preset from the text format described in [the module docs](self).
+    ///
+    /// # Errors
+    /// Returns an error with a line number if the text is not a valid preset.
+    pub fn parse(text: &str) -> Result<Self, PresetError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+            depth: 0,
+        };
+        let root = parser.node()?;
+        if let Some((line, token)) = parser.tokens.get(parser.pos) {
+            return Err(PresetError {
+                line: *line,
+                message: format!("Expected end of preset, found {token}"),
+            });
+        }
+        Ok(root)
+    }
+
+    /// Describe the layout of an existing tree as a preset.
+    ///
+    /// `pane_key` is called to get the key of each pane.
+    /// Placeholders (see [`Tile::Placeholder`]) are left out, since they have no pane yet.
+    /// Returns `None` for an empty tree.
+    ///
+    /// A preset only describes the shape of the layout, so some state is not kept:
+    /// the active tab of each [`crate::Tabs`], the column and row shares of each [`crate::Grid`]
+    /// (and whether it is a subgrid),
+    /// and which tiles are hidden.
+    /// Instantiating the preset gives the defaults for those instead.
+    pub fn from_tree<Pane>(
+        tree: &Tree<Pane>,
+        mut pane_key: impl FnMut(&Pane) -> String,
+    ) -> Option<Self> {
+        from_tile(&tree.tiles, tree.root?, None, &mut pane_key)
+    }
+
+    /// Create a [`Tree`] with this layout.
+    ///
+    /// `resolve` is called with each pane key, and should return the pane for it.
+    /// Panes whose key is not resolved are left out.
+    ///
+    /// The `id` must be _globally_ unique (!).
+    pub fn instantiate<Pane>(
+        &self,
+        id: impl Into<egui::Id>,
+        mut resolve: impl FnMut(&str) -> Option<Pane>,
+    ) -> Tree<Pane> {
+        let mut tiles = Tiles::default();
+        if let Some(root) = self.insert_into(&mut tiles, &mut resolve) {
+            Tree::new(id, root, tiles)
+        } else {
+            Tree::empty(id)
+        }
+    }
+
+    /// The share of this node in its parent linear container, if not the default.
+    pub fn share(&self) -> Option<f32> {
+        match self {
+            Self::Pane { share, .. } | Self::Container { share, .. } => *share,
+        }
+    }
+
+    fn insert_into<Pane>(
+        &self,
+        tiles: &mut Tiles<Pane>,
+        resolve: &mut dyn FnMut(&str) -> Option<Pane>,
+    ) -> Option<TileId> {
+        match self {
+            Self::Pane { key, .. } => {
+                if let Some(pane) = resolve(key) {
+                    Some(tiles.insert_pane(pane))
+                } else {
+                    log::warn!("Layout preset: failed to resolve pane {key:?}");
+                    None
+                }
+            }
+            Self::Container {
+                kind,
+                columns,
+                children,
+                ..
+            } => {
+                let mut child_ids = vec![];
+                let mut shares = vec![];
+                for child in children {
+                    if let Some(child_id) = child.insert_into(tiles, resolve) {
+                        child_ids.push(child_id);
+                        shares.push((child_id, child.share()));
+                    }
+                }
+
+                let mut container = Container::new(*kind, child_ids);
+                match &mut container {
+                    Container::Tabs(_) => {}
+                    Container::Linear(linear) => {
+                        for (child_id, share) in shares {
+                            if let Some(share) = share {
+                                linear.shares.set_share(child_id, share);
+                            }
+                        }
+                    }
+                    Container::Grid(grid) => {
+                        if let Some(columns) = *columns {
+                            grid.layout = GridLayout::Columns(columns);
+                        }
+                    }
+                }
+                Some(tiles.insert_container(container))
+            }
+        }
+    }
+
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, indent: usize) -> std::fmt::Result {
+        write!(f, "{}", "    ".repeat(indent))?;
+        match self {
+            Self::Pane { key, share } => {
+                write!(f, "pane {}", quoted(key))?;
+                if let Some(share) = share {
+                    write!(f, " share={share}")?;
+                }
+                writeln!(f)
+            }
+            Self::Container {
+                kind,
+                share,
+                columns,
+                children,
+            } => {
+                write!(f, "{}", kind_keyword(*kind))?;
+                if let Some(share) = share {
+                    write!(f, " share={share}")?;
+                }
+                if let Some(columns) = columns {
+                    write!(f, " columns={columns}")?;
+                }
+                writeln!(f, " {{")?;
+                for child in children {
+                    child.write(f, indent + 1)?;
+                }
+                writeln!(f, "{}}}", "    ".repeat(indent))
+            }
+        }
+    }
+}
+
+/// Writes the preset in the text format described in [the module docs](self).
+impl std::fmt::Display for LayoutPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+fn from_tile<Pane>(
+    tiles: &Tiles<Pane>,
+    tile_id: TileId,
+    share: Option<f32>,
+    pane_key: &mut dyn FnMut(&Pane) -> String,
+) -> Option<LayoutPreset> {
+    match tiles.get(tile_id)? {
+        Tile::Pane(pane) => Some(LayoutPreset::Pane {
+            key: pane_key(pane),
+            share,
+        }),
+        Tile::Placeholder => None,
+        Tile::Container(container) => {
+            let children = container
+                .children()
+                .filter_map(|&child_id| {
+                    let share = if let Container::Linear(linear) = container {
+                        Some(linear.shares[child_id]).filter(|&share| share != 1.0)
+                    } else {
+                        None
+                    };
+                    from_tile(tiles, child_id, share, pane_key)
+                })
+                .collect();
+            let columns = if let Container::Grid(grid) = container {
+                match grid.layout {
+                    GridLayout::Auto => None,
+                    GridLayout::Columns(columns) => Some(columns),
+                }
+            } else {
+                None
+            };
+            Some(LayoutPreset::Container {
+                kind: container.kind(),
+                share,
+                columns,
+                children,
+            })
+        }
+    }
+}
+
+/// Quote a pane key, escaping it the way the tokenizer expects.
+fn quoted(key: &str) -> String {
+    let escaped = key
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn kind_keyword(kind: ContainerKind) -> &'static str {
+    match kind {
+        ContainerKind::Tabs => "tabs",
+        ContainerKind::Horizontal => "horizontal",
+        ContainerKind::Vertical => "vertical",
+        ContainerKind::Grid => "grid",
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    String(String),
+    Number(f32),
+    Equals,
+    OpenBrace,
+    CloseBrace,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ident(ident) => write!(f, "`{ident}`"),
+            Self::String(string) => write!(f, "{string:?}"),
+            Self::Number(number) => write!(f, "`{number}`"),
+            Self::Equals => write!(f, "`=`"),
+            Self::OpenBrace => write!(f, "`{{`"),
+            Self::CloseBrace => write!(f, "`}}`"),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, PresetError> {
+    let mut tokens = vec![];
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_nr = line_index + 1;
+        let error = |message: String| PresetError {
+            line: line_nr,
+            message,
+        };
+
+        let mut chars = line.chars().peekable();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '#' {
+                break; // comment
+            } else if c == '=' {
+                chars.next();
+                tokens.push((line_nr, Token::Equals));
+            } else if c == '{' {
+                chars.next();
+                tokens.push((line_nr, Token::OpenBrace));
+            } else if c == '}' {
+                chars.next();
+                tokens.push((line_nr, Token::CloseBrace));
+            } else if c == '"' {
+                chars.next();
+                let mut string = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped @ ('"' | '\\')) => string.push(escaped),
+                            Some('n') => string.push('\n'),
+                            other => {
+                                return Err(error(format!("Unknown escape sequence: {other:?}")));
+                            }
+                        },
+                        Some(c) => string.push(c),
+                        None => return Err(error("Unterminated string".to_owned())),
+                    }
+                }
+                tokens.push((line_nr, Token::String(string)));
+            } else if c.is_ascii_alphabetic() || c == '_' {
+                let mut ident = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        ident.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push((line_nr, Token::Ident(ident)));
+            } else if c.is_ascii_digit() || c == '.' || c == '-' {
+                let mut number = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '.' || c == '-' || c == 'e' {
+                        number.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                let number = number
+                    .parse()
+                    .map_err(|err| error(format!("Invalid number {number:?}: {err}")))?;
+                tokens.push((line_nr, Token::Number(number)));
+            } else {
+                return Err(error(format!("Unexpected character {c:?}")));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+
+    /// How many containers we are currently inside.
+    depth: usize,
+}
+
+impl Parser {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(line, _)| *line)
+    }
+
+    fn error(&self, message: String) -> PresetError {
+        PresetError {
+            line: self.line(),
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Result<Token, PresetError> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| self.error("Unexpected end of preset".to_owned()))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: &Token) -> Result<(), PresetError> {
+        let token = self.next()?;
+        if &token == expected {
+            Ok(())
+        } else {
+            self.pos -= 1;
+            Err(self.error(format!("Expected {expected}, found {token}")))
+        }
+    }
+
+    fn node(&mut self) -> Result<LayoutPreset, PresetError> {
+        let kind = match self.next()? {
+            Token::Ident(ident) => match ident.as_str() {
+                "pane" => None,
+                "tabs" => Some(ContainerKind::Tabs),
+                "horizontal" => Some(ContainerKind::Horizontal),
+                "vertical" => Some(ContainerKind::Vertical),
+                "grid" => Some(ContainerKind::Grid),
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error(format!("Unknown node type `{ident}`")));
+                }
+            },
+            token => {
+                self.pos -= 1;
+                return Err(self.error(format!("Expected a node, found {token}")));
+            }
+        };
+
+        let key = if kind.is_none() {
+            match self.next()? {
+                Token::String(key) => Some(key),
+                token => {
+                    self.pos -= 1;
+                    return Err(self.error(format!("Expected a pane key string, found {token}")));
+                }
+            }
+        } else {
+            None
+        };
+
+        // Attributes:
+        let mut share = None;
+        let mut columns = None;
+        while let Some(Token::Ident(name)) = self.peek().cloned() {
+            if !matches!(self.tokens.get(self.pos + 1), Some((_, Token::Equals))) {
+                break; // the next sibling
+            }
+            self.pos += 2;
+            let value = match self.next()? {
+                Token::Number(value) => value,
+                token => {
+                    self.pos -= 1;
+                    return Err(self.error(format!("Expected a number, found {token}")));
+                }
+            };
+            match name.as_str() {
+                "share" => {
+                    if !value.is_finite() || value <= 0.0 {
+                        return Err(self.error(format!("Invalid share: {value}")));
+                    }
+                    share = Some(value);
+                }
+                "columns" if kind == Some(ContainerKind::Grid) => {
+                    if value < 1.0 || value.fract() != 0.0 {
+                        return Err(self.error(format!("Invalid column count: {value}")));
+                    }
+                    columns = Some(value as usize);
+                }
+                _ => return Err(self.error(format!("Unknown attribute `{name}`"))),
+            }
+        }
+
+        if let Some(kind) = kind {
+            self.expect(&Token::OpenBrace)?;
+            if MAX_DEPTH <= self.depth {
+                return Err(self.error(format!(
+                    "Containers are nested more than {MAX_DEPTH} levels deep"
+                )));
+            }
+            self.depth += 1;
+            let mut children = vec![];
+            while self.peek() != Some(&Token::CloseBrace) {
+                children.push(self.node()?);
+            }
+            self.expect(&Token::CloseBrace)?;
+            self.depth -= 1;
+            Ok(LayoutPreset::Container {
+                kind,
+                share,
+                columns,
+                children,
+            })
+        } else {
+            Ok(LayoutPreset::Pane {
+                key: key.unwrap_or_default(),
+                share,
+            })
+        }
+    }
+}
+
+#[test]
+fn test_preset_round_trip() {
+    let text = r#"
+        # The main layout
+        horizontal {
+            pane "side\\bar \"1\""
+            vertical share=3 {
+                tabs {
+                    pane "viewport"
+                    pane "settings"
+                }
+                pane "console" share=0.5
+            }
+            grid columns=2 {
+                pane "a"
+                pane "b"
+            }
+        }
+    "#;
+
+    let preset = LayoutPreset::parse(text).unwrap();
+    let tree = preset.instantiate("preset_tree", |key| Some(key.to_owned()));
+    assert_eq!(
+        tree.tiles.len(),
+        10,
+        "Expected six panes and four containers"
+    );
+
+    let written = LayoutPreset::from_tree(&tree, Clone::clone).unwrap();
+    assert_eq!(
+        written, preset,
+        "Preset did not survive a round-trip through a tree"
+    );
+    assert_eq!(
+        LayoutPreset::parse(&written.to_string()).unwrap(),
+        preset,
+        "Preset did not survive a round-trip through text"
+    );
+
+    let error = LayoutPreset::parse("horizontal {\n pane \"a\"\n stack { }\n}").unwrap_err();
+    assert_eq!(error.line, 3, "Wrong line for {error}");
+}
+
+#[test]
+fn test_preset_rejects_bad_input() {
+    for share in ["0", "-1", "1e39", "-0"] {
+        let text = format!("horizontal {{ pane \"a\" share={share} }}");
+        assert!(
+            LayoutPreset::parse(&text).is_err(),
+            "share={share} should be rejected"
+        );
+    }
+
+    let deep = format!("{}{}", "tabs { ".repeat(10_000), "} ".repeat(10_000));
+    assert!(
+        LayoutPreset::parse(&deep).is_err(),
+        "Deep nesting should be rejected"
+    );
+    let shallow = format!("{}{}", "tabs { ".repeat(MAX_DEPTH), "} ".repeat(MAX_DEPTH));
+    assert!(LayoutPreset::parse(&shallow).is_ok());
+}
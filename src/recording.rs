@@ -0,0 +1,303 @@
+use crate::behavior::DropOutsideAction;
+use crate::{
+    Behavior, Container, ContainerInsertion, ContainerKind, InsertionPoint, Tile, TileId, Tree,
+};
+
+/// A user interaction that changed the layout of a [`Tree`], see [`LayoutScript`].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum LayoutEvent {
+    /// A dragged tile was dropped into a container.
+    Dropped {
+        tile_id: TileId,
+        parent_id: TileId,
+
+        /// The kind of container the tile was dropped into,
+        /// which is created in place of the parent if it is of a different kind.
+        kind: ContainerKind,
+
+        /// Where among the children of the parent.
+        index: usize,
+
+        /// Was the tile copied instead of moved (see [`Behavior::clone_pane`])?
+        copy: bool,
+    },
+
+    /// An empty root container was created for a tile dropped into an empty tree,
+    /// see [`Behavior::empty_tree_root_kind`].
+    ///
+    /// Followed by the [`Self::Dropped`] into the new root.
+    RootCreated { kind: ContainerKind },
+
+    /// A dragged tile was dropped outside the tree, see [`Behavior::on_drop_outside`].
+    DroppedOutside {
+        tile_id: TileId,
+        action: DropOutsideAction,
+    },
+
+    /// The shares of a [`crate::Linear`] container were changed, e.g. by dragging a resize handle.
+    LinearResized {
+        container_id: TileId,
+
+        /// The new share of each child that has one.
+        shares: Vec<(TileId, f32)>,
+    },
+
+    /// The shares of a [`crate::Grid`] were changed, e.g. by dragging a resize handle.
+    GridResized {
+        grid_id: TileId,
+        col_shares: Vec<f32>,
+        row_shares: Vec<f32>,
+    },
+
+    /// A tab became the active tab of its [`crate::Tabs`] container.
+    TabSelected { tabs_id: TileId, tab_id: TileId },
+
+    /// The tree was simplified and garbage-collected at the start of a frame,
+    /// see [`Behavior::auto_simplify`].
+    Simplified,
+}
+
+impl LayoutEvent {
+    /// The current shares of the container, if it can be resized.
+    pub(crate) fn resized(container_id: TileId, container: &Container) -> Option<Self> {
+        match container {
+            Container::Tabs(_) => None,
+            Container::Linear(linear) => Some(Self::LinearResized {
+                container_id,
+                shares: linear
+                    .children
+                    .iter()
+                    .filter_map(|&child| Some((child, linear.shares.get(child)?)))
+                    .collect(),
+            }),
+            Container::Grid(grid) => Some(Self::GridResized {
+                grid_id: container_id,
+                col_shares: grid.col_shares.clone(),
+                row_shares: grid.row_shares.clone(),
+            }),
+        }
+    }
+
+    /// Is this the same kind of resize of the same container?
+    fn replaces(&self, previous: &Self) -> bool {
+        match (self, previous) {
+            (
+                Self::LinearResized { container_id, .. },
+                Self::LinearResized {
+                    container_id: previous_id,
+                    ..
+                },
+            ) => container_id == previous_id,
+            (
+                Self::GridResized { grid_id, .. },
+                Self::GridResized {
+                    grid_id: previous_id,
+                    ..
+                },
+            ) => grid_id == previous_id,
+            _ => false,
+        }
+    }
+}
+
+/// The layout changes made by the user, recorded with [`Tree::start_recording`].
+///
+/// Replaying the script with [`Tree::replay`] on a copy of the tree as it was when the recording started
+/// makes the same changes, with the same tile ids.
+/// This is useful for reproducing bug reports and for deterministic UI tests.
+///
+/// Only drags, resizes and tab selections are recorded.
+/// Changes made in other ways (e.g. with keyboard shortcuts or from code) are not.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct LayoutScript {
+    pub events: Vec<LayoutEvent>,
+}
+
+impl LayoutScript {
+    fn push(&mut self, event: LayoutEvent) {
+        match self.events.last_mut() {
+            // A resize drag changes the shares each frame; only the end result matters.
+            Some(last) if event.replaces(last) => *last = event,
+            Some(LayoutEvent::Simplified) if event == LayoutEvent::Simplified => {}
+            _ => self.events.push(event),
+        }
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Start recording the layout changes made by the user, see [`LayoutScript`].
+    ///
+    /// Any previous recording is discarded.
+    pub fn start_recording(&mut self) {
+        self.recording = Some(LayoutScript::default());
+    }
+
+    /// Stop recording, and return what was recorded since [`Self::start_recording`].
+    pub fn stop_recording(&mut self) -> Option<LayoutScript> {
+        self.recording.take()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub(crate) fn record(&mut self, event: LayoutEvent) {
+        if let Some(recording) = &mut self.recording {
+            recording.push(event);
+        }
+    }
+
+    /// Make the changes of a [`LayoutScript`], in order.
+    ///
+    /// The behavior is notified like when the user made the changes.
+    /// Events referring to tiles that no longer exist are skipped.
+    pub fn replay(&mut self, behavior: &mut dyn Behavior<Pane>, script: &LayoutScript) {
+        for event in &script.events {
+            self.replay_event(behavior, event);
+        }
+    }
+
+    fn replay_event(&mut self, behavior: &mut dyn Behavior<Pane>, event: &LayoutEvent) {
+        match event {
+            &LayoutEvent::Dropped {
+                tile_id,
+                parent_id,
+                kind,
+                index,
+                copy,
+            } => {
+                if self.tiles.get(tile_id).is_none() || self.tiles.get(parent_id).is_none() {
+                    log::debug!("Skipping the replay of a drop of {tile_id:?} into {parent_id:?}");
+                    return;
+                }
+                let insertion_point = InsertionPoint {
+                    parent_id,
+                    insertion: ContainerInsertion::new(kind, index),
+                };
+                self.drop_tile(behavior, tile_id, insertion_point, copy);
+            }
+            &LayoutEvent::RootCreated { kind } => {
+                if self.root.is_some() {
+                    log::debug!("Skipping the replay of a root creation in a non-empty tree");
+                    return;
+                }
+                self.create_empty_root(kind);
+            }
+            &LayoutEvent::DroppedOutside { tile_id, action } => {
+                if self.tiles.get(tile_id).is_some() {
+                    self.apply_drop_outside(behavior, tile_id, action);
+                }
+            }
+            LayoutEvent::LinearResized {
+                container_id,
+                shares,
+            } => {
+                if let Some(Tile::Container(Container::Linear(linear))) =
+                    self.tiles.get_mut(*container_id)
+                {
+                    for &(child, share) in shares {
+                        linear.shares.set_share(child, share);
+                    }
+                }
+                self.record(event.clone());
+            }
+            LayoutEvent::GridResized {
+                grid_id,
+                col_shares,
+                row_shares,
+            } => {
+                if let Some(Tile::Container(Container::Grid(grid))) = self.tiles.get_mut(*grid_id) {
+                    grid.col_shares.clone_from(col_shares);
+                    grid.row_shares.clone_from(row_shares);
+                }
+                self.record(event.clone());
+            }
+            &LayoutEvent::TabSelected { tabs_id, tab_id } => {
+                if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(tabs_id) {
+                    tabs.set_active(tab_id);
+                }
+                self.record(event.clone());
+            }
+            LayoutEvent::Simplified => {
+                self.record(LayoutEvent::Simplified);
+                self.simplify(&behavior.simplification_options());
+                self.gc(behavior);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::UiResponse;
+
+    use super::*;
+
+    struct TestBehavior;
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2, 3]);
+        let root = tree.root().unwrap();
+        let panes = tree.tiles.get_container(root).unwrap().children_vec();
+        let original = tree.clone();
+
+        // Drag the last tab to the right of the tabs, then resize the new split:
+        tree.start_recording();
+        let insertion_point = InsertionPoint {
+            parent_id: root,
+            insertion: ContainerInsertion::Horizontal(1),
+        };
+        tree.drop_tile(&mut TestBehavior, panes[2], insertion_point, false);
+        tree.record(LayoutEvent::Simplified);
+        tree.simplify(&TestBehavior.simplification_options());
+        tree.gc(&mut TestBehavior);
+        let split = tree.root().unwrap();
+        let Some(Tile::Container(container)) = tree.tiles.get_mut(split) else {
+            panic!()
+        };
+        let Container::Linear(linear) = &mut *container else {
+            panic!("Expected a horizontal split, got {container:?}")
+        };
+        linear.shares.set_share(panes[2], 3.0);
+        let resize = LayoutEvent::resized(split, container).unwrap();
+        tree.record(resize);
+        let script = tree.stop_recording().unwrap();
+        assert_eq!(script.events.len(), 3);
+
+        let mut replayed = original;
+        replayed.replay(&mut TestBehavior, &script);
+        assert_eq!(replayed, tree);
+    }
+
+    #[test]
+    fn test_replay_drop_into_empty_tree() {
+        let mut tree = Tree::empty("tree");
+        let pane = tree.tiles.insert_pane(1);
+        let original = tree.clone();
+
+        tree.start_recording();
+        let root = tree.create_empty_root(ContainerKind::Tabs);
+        let insertion_point = InsertionPoint::new(root, ContainerInsertion::Tabs(0));
+        tree.drop_tile(&mut TestBehavior, pane, insertion_point, false);
+        let script = tree.stop_recording().unwrap();
+        assert_eq!(script.events.len(), 2);
+
+        let mut replayed = original;
+        replayed.replay(&mut TestBehavior, &script);
+        assert_eq!(replayed.root(), Some(root));
+        assert_eq!(replayed, tree);
+    }
+}
@@ -0,0 +1,402 @@
+use egui::{Ui, WidgetText};
+
+use crate::{Behavior, CloseResponse, OrphanPolicy, Tile, TileId, Tiles, Tree, UiResponse};
+
+/// A pane that can be created by a [`PaneRegistry`], e.g. one provided by a plugin.
+pub trait RegisteredPane {
+    /// Show the pane, like [`Behavior::pane_ui`].
+    fn ui(&mut self, ui: &mut Ui, tile_id: TileId) -> UiResponse;
+
+    /// The title of the tab of the pane, like [`Behavior::tab_title_for_pane`].
+    fn title(&self) -> WidgetText;
+
+    /// See [`Behavior::save_pane_state`].
+    #[cfg(feature = "serde")]
+    fn save_state(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// See [`Behavior::restore_pane_state`].
+    #[cfg(feature = "serde")]
+    fn restore_state(&mut self, _state: serde_json::Value) {}
+}
+
+type PaneFactory = Box<dyn Fn() -> Box<dyn RegisteredPane>>;
+
+/// Creates panes by a string key, for plugin architectures where the host
+/// doesn't know the concrete pane types.
+///
+/// Use it with a [`Tree<KeyedPane>`]. The registry itself implements [`Behavior<KeyedPane>`],
+/// or you can use [`KeyedPane::pane_mut`] from your own [`Behavior`].
+///
+/// Panes whose key is not registered (e.g. because their plugin is not loaded)
+/// are [`Tile::Placeholder`]s, which the registry creates once the key is registered,
+/// see [`Behavior::instantiate_pane`].
+/// Since the registry remembers the keys of the placeholders by [`TileId`],
+/// use a separate registry for each tree.
+/// If you wrap the registry in your own [`Behavior`], forward [`Behavior::on_tab_close`],
+/// [`Behavior::on_tile_id_changed`] and [`Behavior::on_unreachable_tiles`] to it,
+/// so that it can keep track of the placeholders.
+///
+/// ```
+/// use egui_tiles::{PaneRegistry, RegisteredPane, TileId, Tiles, Tree, UiResponse};
+///
+/// struct LogView;
+///
+/// impl RegisteredPane for LogView {
+///     fn ui(&mut self, ui: &mut egui::Ui, _tile_id: TileId) -> UiResponse {
+///         ui.label("No logs yet");
+///         UiResponse::None
+///     }
+///
+///     fn title(&self) -> egui::WidgetText {
+///         "Log".into()
+///     }
+/// }
+///
+/// let mut registry = PaneRegistry::default();
+/// registry.register("log_view", || Box::new(LogView));
+///
+/// let mut tiles = Tiles::default();
+/// let log_view = tiles.insert_registered_pane(&registry, "log_view").unwrap();
+/// let root = tiles.insert_tab_tile(vec![log_view]);
+/// let tree = Tree::new("my_tree", root, tiles);
+///
+/// // Save the keys, and create the panes again after loading:
+/// let saved: Tree<String> = tree.to_pane_keys(&registry);
+/// let loaded = saved.instantiate_registered_panes(&mut registry);
+/// ```
+#[derive(Default)]
+pub struct PaneRegistry {
+    factories: ahash::HashMap<String, PaneFactory>,
+
+    /// The keys of the placeholders of panes whose key was not registered.
+    missing: ahash::HashMap<TileId, String>,
+}
+
+impl std::fmt::Debug for PaneRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut keys = self.keys().collect::<Vec<_>>();
+        keys.sort_unstable();
+        f.debug_struct("PaneRegistry").field("keys", &keys).finish()
+    }
+}
+
+impl PaneRegistry {
+    /// Register a factory for the panes with the given key.
+    ///
+    /// Replaces any factory already registered for that key.
+    pub fn register(
+        &mut self,
+        key: impl Into<String>,
+        factory: impl Fn() -> Box<dyn RegisteredPane> + 'static,
+    ) -> &mut Self {
+        let key = key.into();
+        if self
+            .factories
+            .insert(key.clone(), Box::new(factory))
+            .is_some()
+        {
+            log::debug!("Replaced the pane factory for {key:?}");
+        }
+        self
+    }
+
+    /// Remove the factory for the given key, e.g. when a plugin is unloaded.
+    ///
+    /// Existing panes are kept.
+    /// Use [`Self::replace_with_placeholder`] to replace them.
+    /// Returns `false` if there was no factory for that key.
+    pub fn unregister(&mut self, key: &str) -> bool {
+        self.factories.remove(key).is_some()
+    }
+
+    pub fn is_registered(&self, key: &str) -> bool {
+        self.factories.contains_key(key)
+    }
+
+    /// All registered keys, in arbitrary order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(|key| key.as_str())
+    }
+
+    /// Create a pane with the factory for the given key.
+    ///
+    /// Returns `None` if no factory is registered for that key.
+    pub fn create(&self, key: &str) -> Option<KeyedPane> {
+        let factory = self.factories.get(key)?;
+        Some(KeyedPane {
+            key: key.to_owned(),
+            pane: factory(),
+        })
+    }
+
+    /// The key of the pane of a [`Tile::Placeholder`], if the key was not registered.
+    pub fn missing_key(&self, tile_id: TileId) -> Option<&str> {
+        self.missing.get(&tile_id).map(String::as_str)
+    }
+
+    /// Turn a pane into a [`Tile::Placeholder`] that remembers its key,
+    /// e.g. because the plugin providing it is about to be unloaded.
+    ///
+    /// The pane is created again once its key is registered.
+    /// Returns the removed pane, or `None` if the tile is not a pane.
+    pub fn replace_with_placeholder(
+        &mut self,
+        tree: &mut Tree<KeyedPane>,
+        tile_id: TileId,
+    ) -> Option<KeyedPane> {
+        let pane = tree.replace_pane_with_placeholder(self, tile_id)?;
+        self.missing.insert(tile_id, pane.key.clone());
+        Some(pane)
+    }
+
+    /// Forget the keys of the placeholders in the subtree of `tile_id`, because it is being removed.
+    fn forget_missing(&mut self, tiles: &Tiles<KeyedPane>, tile_id: TileId) {
+        if self.missing.is_empty() {
+            return;
+        }
+        self.missing.remove(&tile_id);
+        if let Some(Tile::Container(container)) = tiles.get(tile_id) {
+            for &child in container.children() {
+                self.forget_missing(tiles, child);
+            }
+        }
+    }
+}
+
+/// The pane type of a tree whose panes are created by a [`PaneRegistry`].
+///
+/// Remembers the key of the factory that created the pane, so that the tree can be saved by key.
+pub struct KeyedPane {
+    key: String,
+    pane: Box<dyn RegisteredPane>,
+}
+
+impl std::fmt::Debug for KeyedPane {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyedPane").field("key", &self.key).finish()
+    }
+}
+
+impl KeyedPane {
+    /// The key of the factory of this pane.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn pane(&self) -> &dyn RegisteredPane {
+        self.pane.as_ref()
+    }
+
+    pub fn pane_mut(&mut self) -> &mut (dyn RegisteredPane + 'static) {
+        self.pane.as_mut()
+    }
+}
+
+impl Behavior<KeyedPane> for PaneRegistry {
+    fn pane_ui(&mut self, ui: &mut Ui, tile_id: TileId, pane: &mut KeyedPane) -> UiResponse {
+        pane.pane.ui(ui, tile_id)
+    }
+
+    fn tab_title_for_pane(&mut self, pane: &KeyedPane) -> WidgetText {
+        pane.pane.title()
+    }
+
+    fn instantiate_pane(&mut self, tile_id: TileId) -> Option<KeyedPane> {
+        let pane = self.create(self.missing_key(tile_id)?)?;
+        self.missing.remove(&tile_id);
+        Some(pane)
+    }
+
+    fn missing_pane_ui(&mut self, ui: &mut Ui, tile_id: TileId) {
+        if let Some(key) = self.missing_key(tile_id) {
+            ui.centered_and_justified(|ui| {
+                ui.weak(format!("Missing pane: {key}"));
+            });
+        }
+    }
+
+    fn tab_title_for_placeholder(&mut self, tile_id: TileId) -> WidgetText {
+        self.missing_key(tile_id).unwrap_or("…").into()
+    }
+
+    fn on_tab_close(&mut self, tiles: &mut Tiles<KeyedPane>, tile_id: TileId) -> CloseResponse {
+        self.forget_missing(tiles, tile_id);
+        CloseResponse::Close
+    }
+
+    fn on_tile_id_changed(&mut self, old: TileId, new: TileId) {
+        if let Some(key) = self.missing.remove(&old) {
+            self.missing.insert(new, key);
+        }
+    }
+
+    fn on_unreachable_tiles(
+        &mut self,
+        tiles: &Tiles<KeyedPane>,
+        unreachable: Vec<TileId>,
+    ) -> OrphanPolicy {
+        for tile_id in unreachable {
+            self.forget_missing(tiles, tile_id);
+        }
+        OrphanPolicy::Drop
+    }
+
+    #[cfg(feature = "serde")]
+    fn save_pane_state(&mut self, pane: &KeyedPane) -> Option<serde_json::Value> {
+        pane.pane.save_state()
+    }
+
+    #[cfg(feature = "serde")]
+    fn restore_pane_state(&mut self, pane: &mut KeyedPane, state: serde_json::Value) {
+        pane.pane.restore_state(state);
+    }
+}
+
+impl Tiles<KeyedPane> {
+    /// Insert a pane created by the factory registered for the given key.
+    ///
+    /// Returns `None` if no factory is registered for that key.
+    pub fn insert_registered_pane(&mut self, registry: &PaneRegistry, key: &str) -> Option<TileId> {
+        let pane = registry.create(key)?;
+        Some(self.insert_pane(pane))
+    }
+}
+
+impl Tree<KeyedPane> {
+    /// Replace each pane with the key of its factory, e.g. to serialize the tree.
+    ///
+    /// The placeholders of panes whose key is not registered are saved with their key too.
+    /// Use [`Tree::instantiate_registered_panes`] to create the panes again.
+    pub fn to_pane_keys(&self, registry: &PaneRegistry) -> Tree<String> {
+        let mut tree = self.map_panes_ref(|_, pane| pane.key.clone());
+        for (&tile_id, tile) in tree.tiles.iter_mut() {
+            if let (Tile::Placeholder, Some(key)) = (&tile, registry.missing_key(tile_id)) {
+                *tile = Tile::Pane(key.to_owned());
+            }
+        }
+        tree
+    }
+}
+
+impl Tree<String> {
+    /// Create each pane with the factory registered for its key.
+    ///
+    /// Panes whose key is not registered become [`Tile::Placeholder`]s, so that they keep their place in the tree.
+    /// The registry creates them once their key is registered, see [`PaneRegistry::missing_key`].
+    pub fn instantiate_registered_panes(mut self, registry: &mut PaneRegistry) -> Tree<KeyedPane> {
+        let missing: Vec<TileId> = self
+            .tiles
+            .iter()
+            .filter(|(_, tile)| matches!(tile, Tile::Pane(key) if !registry.is_registered(key)))
+            .map(|(&tile_id, _)| tile_id)
+            .collect();
+        for tile_id in missing {
+            if let Some(key) = self.tiles.replace_with_placeholder(tile_id) {
+                registry.missing.insert(tile_id, key);
+            }
+        }
+
+        self.map_panes(|_, key| {
+            registry
+                .create(&key)
+                .expect("Unregistered panes were replaced by placeholders")
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Tile;
+
+    use super::*;
+
+    struct TestPane;
+
+    impl RegisteredPane for TestPane {
+        fn ui(&mut self, _ui: &mut Ui, _tile_id: TileId) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn title(&self) -> WidgetText {
+            "Test".into()
+        }
+    }
+
+    #[test]
+    fn test_missing_panes_keep_their_key() {
+        let mut registry = PaneRegistry::default();
+        registry.register("a", || Box::new(TestPane));
+        registry.register("b", || Box::new(TestPane));
+
+        let mut tiles = Tiles::default();
+        let a = tiles.insert_registered_pane(&registry, "a").unwrap();
+        let b = tiles.insert_registered_pane(&registry, "b").unwrap();
+        assert_eq!(tiles.insert_registered_pane(&registry, "c"), None);
+        let root = tiles.insert_tab_tile(vec![a, b]);
+        let tree = Tree::new("test_tree", root, tiles);
+
+        // The plugin providing "b" is unloaded:
+        let saved = tree.to_pane_keys(&registry);
+        registry.unregister("b");
+        let mut loaded = saved.instantiate_registered_panes(&mut registry);
+        assert_eq!(loaded.tiles.get_pane(&a).unwrap().key(), "a");
+        assert!(matches!(loaded.tiles.get(b), Some(Tile::Placeholder)));
+        assert_eq!(registry.missing_key(b), Some("b"));
+        assert_eq!(
+            loaded.to_pane_keys(&registry).tiles.get_pane(&b),
+            Some(&"b".to_owned()),
+            "Placeholders are saved with their key"
+        );
+
+        // …and loaded again:
+        registry.register("b", || Box::new(TestPane));
+        let pane = registry.instantiate_pane(b).unwrap();
+        assert_eq!(pane.key(), "b");
+        assert_eq!(registry.missing_key(b), None);
+
+        // …and unloaded while running:
+        *loaded.tiles.get_mut(b).unwrap() = Tile::Pane(pane);
+        registry.unregister("b");
+        assert!(registry.replace_with_placeholder(&mut loaded, b).is_some());
+        assert!(matches!(loaded.tiles.get(b), Some(Tile::Placeholder)));
+        assert_eq!(registry.missing_key(b), Some("b"));
+    }
+
+    #[test]
+    fn test_missing_keys_are_forgotten_with_their_placeholder() {
+        let mut registry = PaneRegistry::default();
+        registry.register("a", || Box::new(TestPane));
+
+        let mut tiles = Tiles::default();
+        let a = tiles.insert_registered_pane(&registry, "a").unwrap();
+        let b = tiles.insert_registered_pane(&registry, "a").unwrap();
+        let orphan = tiles.insert_registered_pane(&registry, "a").unwrap();
+        let root = tiles.insert_tab_tile(vec![a, b]);
+        let mut tree = Tree::new("test_tree", root, tiles);
+
+        registry.unregister("a");
+        for tile_id in [a, b, orphan] {
+            registry
+                .replace_with_placeholder(&mut tree, tile_id)
+                .unwrap();
+        }
+
+        // Closing a placeholder forgets its key:
+        tree.queue(crate::TreeCommand::Close(a));
+        tree.apply_queued_commands(&mut registry);
+        assert!(tree.tiles.get(a).is_none());
+        assert_eq!(registry.missing_key(a), None);
+
+        // …and so does dropping it because it is unreachable:
+        tree.gc(&mut registry);
+        assert!(tree.tiles.get(orphan).is_none());
+        assert_eq!(registry.missing_key(orphan), None);
+
+        // A placeholder that gets a new id keeps its key:
+        registry.on_tile_id_changed(b, orphan);
+        assert_eq!(registry.missing_key(b), None);
+        assert_eq!(registry.missing_key(orphan), Some("a"));
+    }
+}
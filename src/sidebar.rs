@@ -0,0 +1,95 @@
+use crate::{Container, Linear, LinearDir, TileId, Tiles, Tree};
+
+/// One of the sidebars of a tree created with [`Tree::new_with_sidebars`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Side {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl<Pane> Tree<Pane> {
+    /// Create the classic IDE layout: a center pane with optional sidebars to the left, right and bottom.
+    ///
+    /// The sidebars keep their size when the tree is resized (see [`Linear::flexible`]),
+    /// and can be hidden and shown with [`Self::toggle_sidebar`].
+    ///
+    /// The `id` must be _globally_ unique (!).
+    /// This is so that the same tree can be added to different [`egui::Ui`]s (if you want).
+    pub fn new_with_sidebars(
+        id: impl Into<egui::Id>,
+        center: Pane,
+        left: Option<Pane>,
+        right: Option<Pane>,
+        bottom: Option<Pane>,
+    ) -> Self {
+        let mut tiles = Tiles::default();
+        let mut sidebars = ahash::HashMap::default();
+
+        let center = tiles.insert_pane(center);
+        let left = left.map(|pane| tiles.insert_pane(pane));
+        let right = right.map(|pane| tiles.insert_pane(pane));
+        let bottom = bottom.map(|pane| tiles.insert_pane(pane));
+
+        let mut root = center;
+
+        if left.is_some() || right.is_some() {
+            let children = left.into_iter().chain([center]).chain(right).collect();
+            let mut linear = Linear::new(LinearDir::Horizontal, children);
+            linear.shares.set_share(center, 4.0);
+            linear.flexible.insert(center);
+            root = tiles.insert_container(Container::Linear(linear));
+        }
+
+        if let Some(bottom) = bottom {
+            let mut linear = Linear::new(LinearDir::Vertical, vec![root, bottom]);
+            linear.shares.set_share(root, 3.0);
+            linear.flexible.insert(root);
+            root = tiles.insert_container(Container::Linear(linear));
+        }
+
+        for (side, tile_id) in [
+            (Side::Left, left),
+            (Side::Right, right),
+            (Side::Bottom, bottom),
+        ] {
+            if let Some(tile_id) = tile_id {
+                sidebars.insert(side, tile_id);
+            }
+        }
+
+        let mut tree = Self::new(id, root, tiles);
+        tree.sidebars = sidebars;
+        tree
+    }
+
+    /// The tile of the given sidebar, if the tree was created with it using [`Self::new_with_sidebars`],
+    /// and it has not been removed since.
+    pub fn sidebar(&self, side: Side) -> Option<TileId> {
+        let tile_id = *self.sidebars.get(&side)?;
+        self.tiles.get(tile_id).is_some().then_some(tile_id)
+    }
+
+    /// Is the given sidebar shown?
+    pub fn is_sidebar_visible(&self, side: Side) -> bool {
+        self.sidebar(side)
+            .is_some_and(|tile_id| self.tiles.is_visible(tile_id))
+    }
+
+    /// Show or hide the given sidebar.
+    ///
+    /// The size of a hidden sidebar is preserved until it is shown again.
+    pub fn set_sidebar_visible(&mut self, side: Side, visible: bool) {
+        if let Some(tile_id) = self.sidebar(side) {
+            self.tiles.set_visible(tile_id, visible);
+        } else {
+            log::debug!("The tree has no {side:?} sidebar");
+        }
+    }
+
+    /// Show the given sidebar if it is hidden, and vice versa.
+    pub fn toggle_sidebar(&mut self, side: Side) {
+        self.set_sidebar_visible(side, !self.is_sidebar_visible(side));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{ContainerKind, Tile, TileId, Tree};
+
+/// Statistics about the structure of a [`Tree`], see [`Tree::stats`].
+///
+/// Only tiles reachable from the root are counted, including hidden ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TreeStats {
+    /// Number of panes.
+    pub num_panes: usize,
+
+    /// Number of placeholders that have not been turned into panes yet, see [`crate::Tile::Placeholder`].
+    pub num_placeholders: usize,
+
+    /// Number of [`crate::Tabs`] containers.
+    pub num_tabs: usize,
+
+    /// Number of horizontal [`crate::Linear`] containers.
+    pub num_horizontal: usize,
+
+    /// Number of vertical [`crate::Linear`] containers.
+    pub num_vertical: usize,
+
+    /// Number of [`crate::Grid`] containers.
+    pub num_grids: usize,
+
+    /// The number of tiles on the longest path from the root down to a leaf.
+    ///
+    /// An empty tree has depth zero, a tree with just a root pane has depth one.
+    pub depth: usize,
+}
+
+impl TreeStats {
+    /// Total number of containers, of all kinds.
+    pub fn num_containers(&self) -> usize {
+        self.num_tabs + self.num_horizontal + self.num_vertical + self.num_grids
+    }
+
+    /// Number of containers of the given kind.
+    pub fn num_containers_of_kind(&self, kind: ContainerKind) -> usize {
+        match kind {
+            ContainerKind::Tabs => self.num_tabs,
+            ContainerKind::Horizontal => self.num_horizontal,
+            ContainerKind::Vertical => self.num_vertical,
+            ContainerKind::Grid => self.num_grids,
+        }
+    }
+}
+
+impl<Pane> Tree<Pane> {
+    /// Count the panes and containers of the tree, and measure its depth.
+    ///
+    /// This is computed with a single traversal of the tree.
+    pub fn stats(&self) -> TreeStats {
+        let mut stats = TreeStats::default();
+        if let Some(root) = self.root {
+            self.collect_stats(root, 1, &mut stats);
+        }
+        stats
+    }
+
+    /// Number of panes in the tree, including hidden ones.
+    pub fn num_panes(&self) -> usize {
+        self.stats().num_panes
+    }
+
+    /// Number of containers in the tree, including hidden ones.
+    pub fn num_containers(&self) -> usize {
+        self.stats().num_containers()
+    }
+
+    /// The number of tiles on the longest path from the root down to a leaf.
+    ///
+    /// See [`TreeStats::depth`].
+    pub fn depth(&self) -> usize {
+        self.stats().depth
+    }
+
+    fn collect_stats(&self, tile_id: TileId, depth: usize, stats: &mut TreeStats) {
+        let Some(tile) = self.tiles.get(tile_id) else {
+            return;
+        };
+        stats.depth = stats.depth.max(depth);
+        match tile {
+            Tile::Pane(_) => {
+                stats.num_panes += 1;
+            }
+            Tile::Placeholder => {
+                stats.num_placeholders += 1;
+            }
+            Tile::Container(container) => {
+                match container.kind() {
+                    ContainerKind::Tabs => stats.num_tabs += 1,
+                    ContainerKind::Horizontal => stats.num_horizontal += 1,
+                    ContainerKind::Vertical => stats.num_vertical += 1,
+                    ContainerKind::Grid => stats.num_grids += 1,
+                }
+                for &child_id in container.children() {
+                    self.collect_stats(child_id, depth + 1, stats);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tree_stats() {
+    let mut tiles = crate::Tiles::default();
+    let a = tiles.insert_pane(());
+    let b = tiles.insert_pane(());
+    let c = tiles.insert_pane(());
+    let tabs = tiles.insert_tab_tile(vec![b, c]);
+    let root = tiles.insert_horizontal_tile(vec![a, tabs]);
+    let tree = Tree::new("stats_tree", root, tiles);
+
+    let stats = tree.stats();
+    assert_eq!(stats.num_panes, 3);
+    assert_eq!(stats.num_tabs, 1);
+    assert_eq!(stats.num_horizontal, 1);
+    assert_eq!(stats.num_containers(), 2);
+    assert_eq!(stats.depth, 3);
+
+    assert_eq!(
+        Tree::<()>::empty("empty_tree").stats(),
+        TreeStats::default()
+    );
+}
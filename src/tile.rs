@@ -3,16 +3,61 @@ use crate::{Container, ContainerKind};
 /// An identifier for a [`Tile`] in the tree, be it a [`Container`] or a pane.
 ///
 /// This id is unique within the tree, but not across trees.
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+///
+/// The id consists of an index and a generation.
+/// When a tile is removed for good (e.g. with [`crate::Tree::remove_recursively`]),
+/// its index may be reused for a new tile, but with a newer generation,
+/// so an old id never refers to the new tile.
+///
+/// The id is serialized as a single number, with the generation in the upper 16 bits.
+/// Ids serialized before generations were introduced are read back as generation zero,
+/// unless they don't fit in 48 bits (e.g. ids made with [`Self::from_u64`] from a hash),
+/// in which case their upper bits are read as the generation.
+#[derive(Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct TileId(pub u64);
 
 impl TileId {
+    /// Number of bits used for the index, the rest is used for the generation.
+    const INDEX_BITS: u32 = 48;
+
+    /// From the raw bits, as returned by [`Self::as_u64`].
     #[inline]
     pub fn from_u64(n: u64) -> Self {
         Self(n)
     }
 
+    /// Panics if the index does not fit in 48 bits, see [`Self::checked_new`].
+    #[inline]
+    pub fn new(index: u64, generation: u16) -> Self {
+        Self::checked_new(index, generation)
+            .unwrap_or_else(|| panic!("Tile index {index} is too large"))
+    }
+
+    /// Returns `None` if the index does not fit in 48 bits.
+    #[inline]
+    pub fn checked_new(index: u64, generation: u16) -> Option<Self> {
+        (index >> Self::INDEX_BITS == 0)
+            .then(|| Self(u64::from(generation) << Self::INDEX_BITS | index))
+    }
+
+    /// The raw bits of the id, including the generation.
+    #[inline]
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    pub fn index(self) -> u64 {
+        self.0 & ((1 << Self::INDEX_BITS) - 1)
+    }
+
+    /// Zero for a freshly used index, and bumped each time the index is reused.
+    #[inline]
+    pub fn generation(self) -> u16 {
+        (self.0 >> Self::INDEX_BITS) as u16
+    }
+
     /// Corresponding [`egui::Id`], used for tracking dragging of tiles.
     pub fn egui_id(&self, tree_id: egui::Id) -> egui::Id {
         tree_id.with(("tile", self))
@@ -21,7 +66,89 @@ impl TileId {
 
 impl std::fmt::Debug for TileId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "#{}", self.0)
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+/// `#index`, followed by `v` and the generation if it is not zero, e.g. `#12` or `#12v3`.
+impl std::fmt::Display for TileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.generation() {
+            0 => write!(f, "#{}", self.index()),
+            generation => write!(f, "#{}v{generation}", self.index()),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Attached to the [`egui::UiStack`] of the [`egui::Ui`] of each tile,
+/// so that any nested widget can find out what tile it is in.
+///
+/// ```
+/// # fn foo(ui: &egui::Ui) {
+/// if let Some(tag) = egui_tiles::TileUiTag::from_ui(ui) {
+///     println!("This widget is in tile {:?}", tag.tile_id);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TileUiTag {
+    /// The [`crate::Tree::id`] of the tree the tile is in.
+    pub tree_id: egui::Id,
+
+    /// The tile (pane or container).
+    pub tile_id: TileId,
+}
+
+impl TileUiTag {
+    /// The key in [`egui::UiTags`] used for the tag.
+    pub const KEY: &'static str = "egui_tiles::TileUiTag";
+
+    /// The innermost tile that the given [`egui::Ui`] is in, if any.
+    pub fn from_ui(ui: &egui::Ui) -> Option<Self> {
+        ui.stack()
+            .iter()
+            .find_map(|stack| stack.tags().get_downcast::<Self>(Self::KEY).copied())
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A tile being dragged over a pane that accepts drops itself (see [`crate::Behavior::pane_accepts_drop`]).
+///
+/// This is attached to the [`egui::UiStack`] of the pane while the pointer is over it,
+/// so that [`crate::Behavior::pane_ui`] can show a drop target and handle the drop:
+///
+/// ```
+/// # fn pane_ui(ui: &mut egui::Ui) {
+/// if let Some(drop) = egui_tiles::PaneDrop::from_ui(ui) {
+///     if drop.released {
+///         println!("Tile {:?} was dropped onto this pane", drop.dragged_tile_id);
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PaneDrop {
+    /// The tile being dragged.
+    pub dragged_tile_id: TileId,
+
+    /// Was the tile released over the pane this frame?
+    ///
+    /// The layout of the tree is then left unchanged, and it is up to the pane to handle the drop.
+    pub released: bool,
+}
+
+impl PaneDrop {
+    /// The key in [`egui::UiTags`] used for the tag.
+    pub const KEY: &'static str = "egui_tiles::PaneDrop";
+
+    /// The tile being dragged over the pane that the given [`egui::Ui`] is in, if any.
+    pub fn from_ui(ui: &egui::Ui) -> Option<Self> {
+        ui.stack()
+            .iter()
+            .find_map(|stack| stack.tags().get_downcast::<Self>(Self::KEY).copied())
     }
 }
 
@@ -36,6 +163,13 @@ pub enum Tile<Pane> {
 
     /// A container of more tiles, e.g. a horizontal layout or a tab layout.
     Container(Container),
+
+    /// A leaf whose pane has not been created yet.
+    ///
+    /// It is replaced by a [`Self::Pane`] from [`crate::Behavior::instantiate_pane`]
+    /// the first time it is shown, e.g. when its tab becomes active.
+    /// See [`crate::Tiles::insert_placeholder`].
+    Placeholder,
 }
 
 impl<T> From<Container> for Tile<T> {
@@ -46,11 +180,11 @@ impl<T> From<Container> for Tile<T> {
 }
 
 impl<Pane> Tile<Pane> {
-    /// Returns `None` if this is a [`Self::Pane`].
+    /// Returns `None` if this is a [`Self::Pane`] or a [`Self::Placeholder`].
     #[inline]
     pub fn kind(&self) -> Option<ContainerKind> {
         match self {
-            Self::Pane(_) => None,
+            Self::Pane(_) | Self::Placeholder => None,
             Self::Container(container) => Some(container.kind()),
         }
     }
@@ -60,6 +194,11 @@ impl<Pane> Tile<Pane> {
         matches!(self, Self::Pane(_))
     }
 
+    #[inline]
+    pub fn is_placeholder(&self) -> bool {
+        matches!(self, Self::Placeholder)
+    }
+
     #[inline]
     pub fn is_container(&self) -> bool {
         matches!(self, Self::Container(_))
@@ -68,7 +207,7 @@ impl<Pane> Tile<Pane> {
     #[inline]
     pub fn container_kind(&self) -> Option<ContainerKind> {
         match self {
-            Self::Pane(_) => None,
+            Self::Pane(_) | Self::Placeholder => None,
             Self::Container(container) => Some(container.kind()),
         }
     }
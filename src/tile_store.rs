@@ -0,0 +1,498 @@
+use std::ops::Range;
+
+use egui::Rect;
+use itertools::Either;
+
+use crate::{Tile, TileId};
+
+/// Tile indices above this are never stored densely, since that would waste too much memory.
+const MAX_DENSE_ID: u64 = 1 << 24;
+
+/// Storage of the tiles in [`crate::Tiles`].
+///
+/// By default this is a hash map, which handles arbitrary [`TileId`]s.
+/// In dense mode the tiles are instead stored in a `Vec` indexed by [`TileId::index`],
+/// which is a lot faster for trees with thousands of tiles,
+/// as long as the ids are small, as they are when allocated by [`crate::Tiles`] itself.
+///
+/// Both modes serialize the same way, as a map.
+#[derive(Clone, Debug)]
+pub(crate) enum TileStore<Pane> {
+    Map(ahash::HashMap<TileId, Tile<Pane>>),
+
+    Dense {
+        /// Indexed by [`TileId::index`].
+        ///
+        /// Only one generation of each index can be stored.
+        slots: Vec<Option<(TileId, Tile<Pane>)>>,
+
+        /// Number of occupied slots.
+        len: usize,
+    },
+}
+
+impl<Pane> Default for TileStore<Pane> {
+    fn default() -> Self {
+        Self::Map(Default::default())
+    }
+}
+
+impl<Pane: PartialEq> PartialEq for TileStore<Pane> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(tile_id, tile)| other.get(tile_id) == Some(tile))
+    }
+}
+
+impl<Pane> FromIterator<(TileId, Tile<Pane>)> for TileStore<Pane> {
+    fn from_iter<T: IntoIterator<Item = (TileId, Tile<Pane>)>>(iter: T) -> Self {
+        Self::Map(iter.into_iter().collect())
+    }
+}
+
+impl<Pane> IntoIterator for TileStore<Pane> {
+    type Item = (TileId, Tile<Pane>);
+    type IntoIter = Either<
+        std::collections::hash_map::IntoIter<TileId, Tile<Pane>>,
+        std::iter::Flatten<std::vec::IntoIter<Option<(TileId, Tile<Pane>)>>>,
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            Self::Map(map) => Either::Left(map.into_iter()),
+            Self::Dense { slots, .. } => Either::Right(slots.into_iter().flatten()),
+        }
+    }
+}
+
+impl<Pane> TileStore<Pane> {
+    pub fn is_dense(&self) -> bool {
+        matches!(self, Self::Dense { .. })
+    }
+
+    /// Switch between hash map and dense storage.
+    ///
+    /// Switching to dense storage is ignored if some tile id is too large,
+    /// and undone if two tiles share an index.
+    pub fn set_dense(&mut self, dense: bool) {
+        if dense == self.is_dense() {
+            return;
+        }
+
+        let tiles = std::mem::take(self);
+        if dense {
+            let too_large = tiles
+                .keys()
+                .copied()
+                .find(|tile_id| dense_index(*tile_id).is_none());
+            if let Some(tile_id) = too_large {
+                log::warn!("Cannot use dense tile storage: {tile_id:?} is too large");
+                *self = tiles;
+                return;
+            }
+            *self = Self::Dense {
+                slots: Vec::new(),
+                len: 0,
+            };
+            for (tile_id, tile) in tiles {
+                self.insert(tile_id, tile);
+            }
+        } else {
+            *self = tiles.into_iter().collect();
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Map(map) => map.len(),
+            Self::Dense { len, .. } => *len,
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, tile_id: &TileId) -> Option<&Tile<Pane>> {
+        match self {
+            Self::Map(map) => map.get(tile_id),
+            Self::Dense { slots, .. } => {
+                let (id, tile) = slots.get(dense_index(*tile_id)?)?.as_ref()?;
+                (id == tile_id).then_some(tile)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, tile_id: &TileId) -> Option<&mut Tile<Pane>> {
+        match self {
+            Self::Map(map) => map.get_mut(tile_id),
+            Self::Dense { slots, .. } => {
+                let (id, tile) = slots.get_mut(dense_index(*tile_id)?)?.as_mut()?;
+                (id == tile_id).then_some(tile)
+            }
+        }
+    }
+
+    #[inline]
+    pub fn contains_key(&self, tile_id: &TileId) -> bool {
+        self.get(tile_id).is_some()
+    }
+
+    /// Returns the previous tile with the same id, if any.
+    pub fn insert(&mut self, tile_id: TileId, tile: Tile<Pane>) -> Option<Tile<Pane>> {
+        match self {
+            Self::Map(map) => map.insert(tile_id, tile),
+            Self::Dense { slots, len } => {
+                let Some(index) = dense_index(tile_id) else {
+                    log::debug!("{tile_id:?} is too large for dense storage; using a hash map");
+                    self.set_dense(false);
+                    return self.insert(tile_id, tile);
+                };
+                if slots.len() <= index {
+                    slots.resize_with(index + 1, || None);
+                }
+                if let Some((other_id, _)) = &slots[index] {
+                    if *other_id != tile_id {
+                        log::debug!(
+                            "{tile_id:?} and {other_id:?} share an index; using a hash map"
+                        );
+                        self.set_dense(false);
+                        return self.insert(tile_id, tile);
+                    }
+                }
+                let previous = slots[index].replace((tile_id, tile));
+                if previous.is_none() {
+                    *len += 1;
+                }
+                previous.map(|(_, tile)| tile)
+            }
+        }
+    }
+
+    pub fn remove(&mut self, tile_id: &TileId) -> Option<Tile<Pane>> {
+        match self {
+            Self::Map(map) => map.remove(tile_id),
+            Self::Dense { slots, len } => {
+                let slot = slots.get_mut(dense_index(*tile_id)?)?;
+                if slot.as_ref()?.0 != *tile_id {
+                    return None;
+                }
+                let (_, tile) = slot.take()?;
+                *len -= 1;
+                Some(tile)
+            }
+        }
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&TileId, &mut Tile<Pane>) -> bool) {
+        match self {
+            Self::Map(map) => map.retain(|tile_id, tile| keep(tile_id, tile)),
+            Self::Dense { slots, len } => {
+                for slot in slots.iter_mut() {
+                    if let Some((tile_id, tile)) = slot {
+                        if !keep(tile_id, tile) {
+                            *slot = None;
+                            *len -= 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// In arbitrary order for the hash map, and in id order for dense storage.
+    pub fn iter(&self) -> impl Iterator<Item = (&TileId, &Tile<Pane>)> + '_ {
+        match self {
+            Self::Map(map) => Either::Left(map.iter()),
+            Self::Dense { slots, .. } => Either::Right(
+                slots
+                    .iter()
+                    .flatten()
+                    .map(|(tile_id, tile)| (tile_id, tile)),
+            ),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&TileId, &mut Tile<Pane>)> + '_ {
+        match self {
+            Self::Map(map) => Either::Left(map.iter_mut()),
+            Self::Dense { slots, .. } => Either::Right(
+                slots
+                    .iter_mut()
+                    .flatten()
+                    .map(|(tile_id, tile)| (&*tile_id, tile)),
+            ),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &TileId> + '_ {
+        self.iter().map(|(tile_id, _)| tile_id)
+    }
+
+    /// Number of slots in dense storage, or zero for the hash map.
+    pub fn num_slots(&self) -> usize {
+        match self {
+            Self::Map(_) => 0,
+            Self::Dense { slots, .. } => slots.len(),
+        }
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Tile<Pane>> + '_ {
+        self.iter().map(|(_, tile)| tile)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Tile<Pane>> + '_ {
+        self.iter_mut().map(|(_, tile)| tile)
+    }
+}
+
+/// The children of all containers in dense storage, as slot indices in a single array.
+///
+/// This lets the layout pass walk the tree by index, without recursing through each container.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ChildArena {
+    /// The children of the tile in each slot, as a range in [`Self::children`].
+    ranges: Vec<Range<u32>>,
+
+    /// The slot of each child, grouped by parent.
+    children: Vec<u32>,
+}
+
+impl ChildArena {
+    /// Collect the children of all the containers in `store`, which should be dense.
+    pub fn rebuild<Pane>(&mut self, store: &TileStore<Pane>) {
+        self.ranges.clear();
+        self.children.clear();
+        let TileStore::Dense { slots, .. } = store else {
+            return;
+        };
+
+        self.ranges.reserve(slots.len());
+        for slot in slots {
+            let start = self.children.len() as u32;
+            if let Some((_, Tile::Container(container))) = slot {
+                self.children
+                    .extend(container.children().filter_map(|&child| slot_of(child)));
+            }
+            self.ranges.push(start..self.children.len() as u32);
+        }
+    }
+
+    /// The slots of the children of the tile in the given slot.
+    #[inline]
+    pub fn children(&self, slot: u32) -> &[u32] {
+        self.ranges.get(slot as usize).map_or(&[], |range| {
+            &self.children[range.start as usize..range.end as usize]
+        })
+    }
+}
+
+/// The rects of the tiles that were laid out this frame.
+///
+/// Like [`TileStore`], this is indexed by [`TileId::index`] in dense mode,
+/// with a hash map for everything else.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TileRects {
+    /// Indexed by [`TileId::index`].
+    slots: Vec<Option<(TileId, Rect)>>,
+
+    /// The rects that don't fit in [`Self::slots`].
+    map: ahash::HashMap<TileId, Rect>,
+}
+
+impl TileRects {
+    pub fn clear(&mut self) {
+        self.slots.fill(None);
+        self.map.clear();
+    }
+
+    /// Store the rects of the tiles with an index below `num_slots` in a `Vec`.
+    pub fn set_num_slots(&mut self, num_slots: usize) {
+        self.clear();
+        self.slots.resize(num_slots, None);
+    }
+
+    #[inline]
+    pub fn get(&self, tile_id: TileId) -> Option<Rect> {
+        let slot = dense_index(tile_id).and_then(|index| self.slots.get(index).copied().flatten());
+        match slot {
+            Some((id, rect)) if id == tile_id => Some(rect),
+            _ => self.map.get(&tile_id).copied(),
+        }
+    }
+
+    /// The tile in the given slot and its rect, if it was laid out.
+    #[inline]
+    pub fn slot(&self, slot: u32) -> Option<(TileId, Rect)> {
+        *self.slots.get(slot as usize)?
+    }
+
+    #[inline]
+    pub fn insert(&mut self, tile_id: TileId, rect: Rect) {
+        if let Some(slot) = dense_index(tile_id).and_then(|index| self.slots.get_mut(index)) {
+            match slot {
+                Some((id, _)) if *id != tile_id => {}
+                slot => {
+                    *slot = Some((tile_id, rect));
+                    return;
+                }
+            }
+        }
+        self.map.insert(tile_id, rect);
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = TileId> + '_ {
+        self.slots
+            .iter()
+            .flatten()
+            .map(|&(tile_id, _)| tile_id)
+            .chain(self.map.keys().copied())
+    }
+}
+
+/// The slot of the tile in dense storage, if it fits.
+#[inline]
+pub(crate) fn slot_of(tile_id: TileId) -> Option<u32> {
+    dense_index(tile_id).map(|index| index as u32)
+}
+
+#[inline]
+fn dense_index(tile_id: TileId) -> Option<usize> {
+    if tile_id.index() <= MAX_DENSE_ID {
+        usize::try_from(tile_id.index()).ok()
+    } else {
+        None
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Pane: serde::Serialize> serde::Serialize for TileStore<Pane> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Pane: serde::Deserialize<'de>> serde::Deserialize<'de> for TileStore<Pane> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ahash::HashMap::deserialize(deserializer).map(Self::Map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestBehavior;
+
+    impl crate::Behavior<u32> for TestBehavior {
+        fn pane_ui(
+            &mut self,
+            _ui: &mut egui::Ui,
+            _tile_id: TileId,
+            _pane: &mut u32,
+        ) -> crate::UiResponse {
+            crate::UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+    }
+
+    #[test]
+    fn test_dense_storage() {
+        let mut store = TileStore::default();
+        for i in [1, 5, 3] {
+            store.insert(TileId(i), Tile::Pane(i));
+        }
+        store.set_dense(true);
+        assert!(store.is_dense(), "All ids are small");
+        assert_eq!(store.len(), 3, "Switching storage keeps all tiles");
+        assert_eq!(store.get(&TileId(5)), Some(&Tile::Pane(5)));
+        assert_eq!(store.get(&TileId(4)), None);
+
+        assert_eq!(store.remove(&TileId(5)), Some(Tile::Pane(5)));
+        store.retain(|tile_id, _| tile_id.0 != 1);
+        assert_eq!(store.keys().copied().collect::<Vec<_>>(), vec![TileId(3)]);
+
+        store.insert(TileId(u64::MAX), Tile::Pane(0));
+        assert!(!store.is_dense(), "Huge ids fall back to a hash map");
+        assert_eq!(store.len(), 2);
+    }
+
+    #[test]
+    fn test_generations() {
+        let mut store = TileStore::default();
+        store.set_dense(true);
+        store.insert(TileId::new(1, 0), Tile::Pane(0));
+        assert_eq!(store.get(&TileId::new(1, 1)), None);
+        assert_eq!(store.remove(&TileId::new(1, 1)), None);
+
+        store.insert(TileId::new(1, 1), Tile::Pane(1));
+        assert!(
+            !store.is_dense(),
+            "Two generations of an index fall back to a hash map"
+        );
+        assert_eq!(store.get(&TileId::new(1, 0)), Some(&Tile::Pane(0)));
+        assert_eq!(store.get(&TileId::new(1, 1)), Some(&Tile::Pane(1)));
+    }
+
+    #[test]
+    fn test_removed_ids_are_not_reused() {
+        let mut tree = crate::Tree::new_tabs("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let first = tree.tiles.get_container(root).unwrap().children_vec()[0];
+        tree.remove_recursively(first);
+
+        let new = tree.tiles.insert_pane(3);
+        assert_eq!(new.index(), first.index(), "The index is reused…");
+        assert_ne!(new, first, "…with a newer generation");
+        assert_eq!(tree.tiles.get(first), None);
+        assert_eq!(format!("{new:?}"), format!("#{}v1", first.index()));
+    }
+
+    #[test]
+    fn test_dense_layout() {
+        let mut tiles = crate::Tiles::default();
+        let tabs = (0..3).map(|i| tiles.insert_pane(i)).collect();
+        let tabs = tiles.insert_tab_tile(tabs);
+        let subgrid = (0..4).map(|i| tiles.insert_pane(i)).collect();
+        let subgrid = tiles.insert_grid_tile(subgrid);
+        let mut grid: Vec<TileId> = (0..3).map(|i| tiles.insert_pane(i)).collect();
+        grid.push(subgrid);
+        let grid = tiles.insert_grid_tile(grid);
+        let row = (0..5).map(|i| tiles.insert_pane(i)).collect();
+        let row = tiles.insert_horizontal_tile(row);
+        let root = tiles.insert_vertical_tile(vec![tabs, grid, row]);
+
+        let style = egui::Style::default();
+        let rect = Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+        let rects = |tiles: &mut crate::Tiles<u32>| {
+            tiles.layout_root(&style, &mut TestBehavior, rect, root);
+            let mut rects: Vec<(TileId, Rect)> = tiles
+                .rects
+                .keys()
+                .filter_map(|tile_id| Some((tile_id, tiles.rects.get(tile_id)?)))
+                .collect();
+            rects.sort_by_key(|(tile_id, _)| tile_id.0);
+            rects
+        };
+
+        let hash_map_rects = rects(&mut tiles);
+        tiles.set_dense_storage(true);
+        let dense_rects = rects(&mut tiles);
+        assert_eq!(
+            hash_map_rects.len(),
+            tiles.len() - 2,
+            "Only the active tab is laid out"
+        );
+        assert_eq!(hash_map_rects, dense_rects);
+    }
+}
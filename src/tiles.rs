@@ -1,9 +1,15 @@
-use egui::{Pos2, Rect};
+use std::collections::BTreeMap;
+
+use egui::{vec2, Pos2, Rect, Vec2};
+use itertools::Itertools as _;
 
 use super::{
-    Behavior, Container, ContainerInsertion, ContainerKind, GcAction, Grid, InsertionPoint, Linear,
-    LinearDir, SimplificationOptions, SimplifyAction, Tabs, Tile, TileId,
+    Behavior, CloseResponse, Container, ContainerInsertion, ContainerKind, GcAction, Grid,
+    InsertionPoint, Linear, LinearDir, OrphanPolicy, SimplificationOptions, SimplifyAction, Tabs,
+    Tile, TileId,
 };
+use crate::container::FixedSizes;
+use crate::tile_store::{ChildArena, TileRects, TileStore};
 
 /// Contains all tile state, but no root.
 ///
@@ -23,25 +29,124 @@ use super::{
 pub struct Tiles<Pane> {
     next_tile_id: u64,
 
-    tiles: ahash::HashMap<TileId, Tile<Pane>>,
+    tiles: TileStore<Pane>,
+
+    /// Indices of tiles that were removed for good, with the generation to reuse them with.
+    ///
+    /// See [`Self::next_free_id`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    released: BTreeMap<u64, u16>,
 
     /// Tiles are visible by default, so we only store the invisible ones.
     invisible: ahash::HashSet<TileId>,
 
+    /// Stable names of tiles, see [`Self::set_name`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "ahash::HashMap::is_empty")
+    )]
+    names: ahash::HashMap<TileId, String>,
+
     /// Filled in by the layout step at the start of each frame.
     #[cfg_attr(feature = "serde", serde(default, skip))]
-    pub(super) rects: ahash::HashMap<TileId, Rect>,
+    pub(super) rects: TileRects,
+
+    /// The children of all containers, while a densely stored tree is being laid out.
+    ///
+    /// See [`Self::layout_root`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    layout_arena: Option<ChildArena>,
+
+    /// Tiles that are currently animating their visibility, with a factor in `0..1`.
+    ///
+    /// Filled in at the start of each frame if [`Behavior::visibility_animation_time`] is positive.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) visibility_factors: ahash::HashMap<TileId, f32>,
+
+    /// Tiles whose visibility changed and that have not finished animating to it yet.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    visibility_animations: ahash::HashMap<TileId, VisibilityAnimation>,
+
+    /// Set at the start of each frame if [`Behavior::pixel_snap`] is `true`.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) pixels_per_point: Option<f32>,
+
+    /// The column shares of the grids being laid out, innermost last, see [`Grid::subgrid`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) grid_col_shares: Vec<Vec<f32>>,
+
+    /// The visible part of the tree, set at the start of each frame.
+    ///
+    /// Smaller than the tree when the tree is in a [`egui::ScrollArea`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) viewport: Option<Rect>,
+
+    /// Extra clipping of some tiles, filled in by the layout step at the start of each frame.
+    ///
+    /// Used to keep the scrolling cells of a grid from being painted over its frozen rows and columns.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) clip_rects: ahash::HashMap<TileId, Rect>,
+
+    /// The sizes of the children of [`Linear`] containers with [`Linear::flexible`] children.
+    ///
+    /// Kept in egui temp data between frames by [`crate::Tree`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(crate) fixed_sizes: ahash::HashMap<TileId, FixedSizes>,
+
+    /// Is a tile being dragged? Set at the start of each frame.
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) is_dragging: bool,
+
+    /// When the last drag ended, in [`egui::InputState::time`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) drag_ended_at: Option<f64>,
+
+    /// A tab waiting for the user to confirm that it should be closed, see [`CloseResponse::Pending`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) pending_close: Option<TileId>,
+
+    /// Tiles that got a new id because a new container took over their old one,
+    /// waiting for [`crate::Tree`] to move its own state along. See [`Self::rehome`].
+    #[cfg_attr(feature = "serde", serde(default, skip))]
+    pub(super) rehomed: Vec<(TileId, TileId)>,
+}
+
+/// A tile animating towards its current visibility.
+#[derive(Clone, Copy, Debug)]
+struct VisibilityAnimation {
+    /// The visibility factor when the visibility changed.
+    from: f32,
+
+    /// Set on the first frame of the animation.
+    start_time: Option<f64>,
 }
 
 impl<Pane: PartialEq> PartialEq for Tiles<Pane> {
     fn eq(&self, other: &Self) -> bool {
         let Self {
             next_tile_id: _, // ignored
+            released: _,     // ignored
             tiles,
             invisible,
-            rects: _, // ignore transient state
+            names,
+            rects: _,                 // ignore transient state
+            layout_arena: _,          // ignore transient state
+            visibility_factors: _,    // ignore transient state
+            visibility_animations: _, // ignore transient state
+            pixels_per_point: _,      // ignore transient state
+            grid_col_shares: _,       // ignore transient state
+            viewport: _,              // ignore transient state
+            clip_rects: _,            // ignore transient state
+            fixed_sizes: _,           // ignore transient state
+            is_dragging: _,           // ignore transient state
+            drag_ended_at: _,         // ignore transient state
+            pending_close: _,         // ignore transient state
+            rehomed: _,               // ignore transient state
         } = self;
-        tiles == &other.tiles && invisible == &other.invisible
+        tiles == &other.tiles && invisible == &other.invisible && names == &other.names
     }
 }
 
@@ -49,9 +154,23 @@ impl<Pane> Default for Tiles<Pane> {
     fn default() -> Self {
         Self {
             next_tile_id: 1,
+            released: Default::default(),
             tiles: Default::default(),
             invisible: Default::default(),
+            names: Default::default(),
             rects: Default::default(),
+            layout_arena: None,
+            visibility_factors: Default::default(),
+            visibility_animations: Default::default(),
+            pixels_per_point: None,
+            grid_col_shares: Default::default(),
+            viewport: None,
+            clip_rects: Default::default(),
+            fixed_sizes: Default::default(),
+            is_dragging: false,
+            drag_ended_at: None,
+            pending_close: None,
+            rehomed: Vec::new(),
         }
     }
 }
@@ -59,6 +178,128 @@ impl<Pane> Default for Tiles<Pane> {
 // ----------------------------------------------------------------------------
 
 impl<Pane> Tiles<Pane> {
+    /// Convert all panes to a different type, keeping all tile ids, containers, shares and visibility.
+    ///
+    /// See [`crate::Tree::map_panes`].
+    pub fn map_panes<NewPane>(self, mut f: impl FnMut(TileId, Pane) -> NewPane) -> Tiles<NewPane> {
+        let Self {
+            next_tile_id,
+            tiles,
+            released,
+            invisible,
+            names,
+            rects,
+            layout_arena,
+            visibility_factors,
+            visibility_animations,
+            pixels_per_point,
+            grid_col_shares,
+            viewport,
+            clip_rects,
+            fixed_sizes,
+            is_dragging,
+            drag_ended_at,
+            pending_close,
+            rehomed,
+        } = self;
+
+        let dense = tiles.is_dense();
+        let mut tiles: TileStore<NewPane> = tiles
+            .into_iter()
+            .map(|(tile_id, tile)| {
+                let tile = match tile {
+                    Tile::Pane(pane) => Tile::Pane(f(tile_id, pane)),
+                    Tile::Container(container) => Tile::Container(container),
+                    Tile::Placeholder => Tile::Placeholder,
+                };
+                (tile_id, tile)
+            })
+            .collect();
+        tiles.set_dense(dense);
+
+        Tiles {
+            next_tile_id,
+            tiles,
+            released,
+            invisible,
+            names,
+            rects,
+            layout_arena,
+            visibility_factors,
+            visibility_animations,
+            pixels_per_point,
+            grid_col_shares,
+            viewport,
+            clip_rects,
+            fixed_sizes,
+            is_dragging,
+            drag_ended_at,
+            pending_close,
+            rehomed,
+        }
+    }
+
+    /// Like [`Self::map_panes`], but leaves `self` untouched.
+    pub fn map_panes_ref<NewPane>(
+        &self,
+        mut f: impl FnMut(TileId, &Pane) -> NewPane,
+    ) -> Tiles<NewPane> {
+        let mut tiles: TileStore<NewPane> = self
+            .tiles
+            .iter()
+            .map(|(&tile_id, tile)| {
+                let tile = match tile {
+                    Tile::Pane(pane) => Tile::Pane(f(tile_id, pane)),
+                    Tile::Container(container) => Tile::Container(container.clone()),
+                    Tile::Placeholder => Tile::Placeholder,
+                };
+                (tile_id, tile)
+            })
+            .collect();
+        tiles.set_dense(self.tiles.is_dense());
+
+        Tiles {
+            next_tile_id: self.next_tile_id,
+            tiles,
+            released: self.released.clone(),
+            invisible: self.invisible.clone(),
+            names: self.names.clone(),
+            rects: self.rects.clone(),
+            layout_arena: self.layout_arena.clone(),
+            visibility_factors: self.visibility_factors.clone(),
+            visibility_animations: self.visibility_animations.clone(),
+            pixels_per_point: self.pixels_per_point,
+            grid_col_shares: self.grid_col_shares.clone(),
+            viewport: self.viewport,
+            clip_rects: self.clip_rects.clone(),
+            fixed_sizes: self.fixed_sizes.clone(),
+            is_dragging: self.is_dragging,
+            drag_ended_at: self.drag_ended_at,
+            pending_close: self.pending_close,
+            rehomed: self.rehomed.clone(),
+        }
+    }
+
+    /// Store the tiles in a `Vec` indexed by [`TileId`] instead of in a hash map.
+    ///
+    /// This makes tile lookups a lot cheaper, which matters for trees with thousands of tiles.
+    /// It requires the tile ids to be reasonably small, which they are
+    /// when allocated by [`Self::insert_new`] and friends.
+    /// The memory use grows with the largest tile id ever used, not the number of tiles.
+    ///
+    /// The layout pass then also walks the tree by index, using the children of all containers
+    /// collected into a single array. See `benches/layout.rs` for how long that takes.
+    ///
+    /// This is not persisted: call it again after deserializing.
+    pub fn set_dense_storage(&mut self, dense: bool) {
+        self.tiles.set_dense(dense);
+    }
+
+    /// Are the tiles stored densely? See [`Self::set_dense_storage`].
+    pub fn is_dense_storage(&self) -> bool {
+        self.tiles.is_dense()
+    }
+
     #[inline]
     pub fn is_empty(&self) -> bool {
         self.tiles.is_empty()
@@ -78,7 +319,7 @@ impl<Pane> Tiles<Pane> {
     pub fn get_pane(&self, tile_id: &TileId) -> Option<&Pane> {
         match self.tiles.get(tile_id)? {
             Tile::Pane(pane) => Some(pane),
-            Tile::Container(_) => None,
+            Tile::Container(_) | Tile::Placeholder => None,
         }
     }
 
@@ -86,7 +327,7 @@ impl<Pane> Tiles<Pane> {
     pub fn get_container(&self, tile_id: TileId) -> Option<&Container> {
         match self.tiles.get(&tile_id)? {
             Tile::Container(container) => Some(container),
-            Tile::Pane(_) => None,
+            Tile::Pane(_) | Tile::Placeholder => None,
         }
     }
 
@@ -101,16 +342,27 @@ impl<Pane> Tiles<Pane> {
     /// If the tile isn't visible, or is in an inactive tab, this return `None`.
     pub fn rect(&self, tile_id: TileId) -> Option<Rect> {
         if self.is_visible(tile_id) {
-            self.rects.get(&tile_id).copied()
+            self.rects.get(tile_id)
         } else {
             None
         }
     }
 
+    /// Like [`Self::rect`], but falls back to an empty rect if the tile has no rect.
     pub(super) fn rect_or_die(&self, tile_id: TileId) -> Rect {
-        let rect = self.rect(tile_id);
-        debug_assert!(rect.is_some(), "Failed to find rect for {tile_id:?}");
-        rect.unwrap_or(egui::Rect::from_min_max(Pos2::ZERO, Pos2::ZERO))
+        self.rect(tile_id).unwrap_or_else(|| {
+            log::debug!("Failed to find rect for {tile_id:?}");
+            Rect::from_min_max(Pos2::ZERO, Pos2::ZERO)
+        })
+    }
+
+    /// Round a layout coordinate to the nearest physical pixel, if [`Behavior::pixel_snap`] is on.
+    pub(super) fn round_to_pixel(&self, point: f32) -> f32 {
+        if let Some(pixels_per_point) = self.pixels_per_point {
+            (point * pixels_per_point).round() / pixels_per_point
+        } else {
+            point
+        }
     }
 
     /// All tiles, in arbitrary order
@@ -142,13 +394,26 @@ impl<Pane> Tiles<Pane> {
     ///
     /// Invisible tiles still retain their place in the tile hierarchy.
     pub fn is_visible(&self, tile_id: TileId) -> bool {
-        !self.invisible.contains(&tile_id)
+        // Skip hashing the id in the common case of all tiles being visible:
+        self.invisible.is_empty() || !self.invisible.contains(&tile_id)
     }
 
     /// Tiles are visible by default.
     ///
     /// Invisible tiles still retain their place in the tile hierarchy.
     pub fn set_visible(&mut self, tile_id: TileId, visible: bool) {
+        if visible == self.is_visible(tile_id) {
+            return;
+        }
+        // Start animating from wherever we are now, in case we were already animating:
+        let from = self.visibility_factor(tile_id);
+        self.visibility_animations.insert(
+            tile_id,
+            VisibilityAnimation {
+                from,
+                start_time: None,
+            },
+        );
         if visible {
             self.invisible.remove(&tile_id);
         } else {
@@ -156,6 +421,153 @@ impl<Pane> Tiles<Pane> {
         }
     }
 
+    /// How visible is this tile, in `0.0..=1.0`?
+    ///
+    /// This is either `0.0` or `1.0`, except while the visibility is animating.
+    /// See [`Behavior::visibility_animation_time`].
+    pub fn visibility_factor(&self, tile_id: TileId) -> f32 {
+        // Skip hashing the id when nothing is animating, which is most of the time:
+        let animated = if self.visibility_factors.is_empty() {
+            None
+        } else {
+            self.visibility_factors.get(&tile_id)
+        };
+        if let Some(&factor) = animated {
+            factor
+        } else if self.is_visible(tile_id) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Advance the animations of tiles whose visibility changed.
+    ///
+    /// Tiles that are not animating are left untouched.
+    pub(super) fn update_visibility_animations(
+        &mut self,
+        ctx: &egui::Context,
+        animation_time: f32,
+    ) {
+        self.visibility_factors.clear();
+        if self.visibility_animations.is_empty() {
+            return;
+        }
+        if animation_time <= 0.0 {
+            self.visibility_animations.clear();
+            return;
+        }
+
+        let now = ctx.input(|i| i.time);
+        let Self {
+            tiles,
+            invisible,
+            visibility_factors,
+            visibility_animations,
+            ..
+        } = self;
+        visibility_animations.retain(|tile_id, animation| {
+            if !tiles.contains_key(tile_id) {
+                return false;
+            }
+            let start_time = *animation.start_time.get_or_insert(now);
+            let t = ((now - start_time) as f32 / animation_time).clamp(0.0, 1.0);
+            if 1.0 <= t {
+                return false;
+            }
+            let target = if invisible.contains(tile_id) {
+                0.0
+            } else {
+                1.0
+            };
+            visibility_factors.insert(*tile_id, egui::lerp(animation.from..=target, t));
+            true
+        });
+
+        if !self.visibility_animations.is_empty() {
+            ctx.request_repaint();
+        }
+    }
+
+    /// All tiles that have been made invisible, in arbitrary order.
+    ///
+    /// This does not include the children of invisible tiles (unless they are invisible themselves).
+    /// Useful for a "Reopen closed panel" menu, see [`Behavior::hide_on_close`].
+    pub fn hidden_tiles(&self) -> impl Iterator<Item = TileId> + '_ {
+        self.invisible
+            .iter()
+            .copied()
+            .filter(|tile_id| self.tiles.contains_key(tile_id))
+    }
+
+    /// Give a tile a stable name, e.g. for finding it in UI tests.
+    ///
+    /// Unlike [`TileId`]s, which depend on the order the tiles were created in,
+    /// names are chosen by you, and so stay the same across sessions.
+    /// Names are serialized, shown in the [`Debug`] output of [`crate::Tree`],
+    /// and used for the [`egui::Id`]s of the tile (see [`Self::egui_id`]).
+    ///
+    /// Names should be unique: if another tile already has this name, it loses it.
+    pub fn set_name(&mut self, tile_id: TileId, name: &str) {
+        if let Some(previous) = self.find_by_name(name) {
+            if previous != tile_id {
+                log::warn!("Moving the tile name {name:?} from {previous:?} to {tile_id:?}");
+                self.names.remove(&previous);
+            }
+        }
+        self.names.insert(tile_id, name.to_owned());
+    }
+
+    /// Remove the name of a tile, see [`Self::set_name`].
+    pub fn remove_name(&mut self, tile_id: TileId) -> Option<String> {
+        self.names.remove(&tile_id)
+    }
+
+    /// The name of a tile, if it has one. See [`Self::set_name`].
+    pub fn name(&self, tile_id: TileId) -> Option<&str> {
+        self.names.get(&tile_id).map(String::as_str)
+    }
+
+    /// Find the tile with the given name, see [`Self::set_name`].
+    pub fn find_by_name(&self, name: &str) -> Option<TileId> {
+        #[allow(clippy::iter_over_hash_type)] // Names are unique
+        self.names
+            .iter()
+            .find(|(_, tile_name)| tile_name.as_str() == name)
+            .map(|(&tile_id, _)| tile_id)
+    }
+
+    /// The [`Tabs::document_area`], if any.
+    ///
+    /// If there are several, the one with the lowest id is returned.
+    pub fn document_area(&self) -> Option<TileId> {
+        self.iter()
+            .filter(|(_, tile)| {
+                matches!(
+                    tile,
+                    Tile::Container(Container::Tabs(Tabs {
+                        document_area: true,
+                        ..
+                    }))
+                )
+            })
+            .map(|(&tile_id, _)| tile_id)
+            .min_by_key(|tile_id| tile_id.as_u64())
+    }
+
+    /// The [`egui::Id`] of a tile in the tree with the given id.
+    ///
+    /// This is derived from the name of the tile if it has one (see [`Self::set_name`]),
+    /// else from its [`TileId`].
+    /// This is e.g. the id of its tab button, and what is dragged when the tile is dragged.
+    pub fn egui_id(&self, tree_id: egui::Id, tile_id: TileId) -> egui::Id {
+        if let Some(name) = self.name(tile_id) {
+            tree_id.with(("tile_name", name))
+        } else {
+            tile_id.egui_id(tree_id)
+        }
+    }
+
     pub fn toggle_visibility(&mut self, tile_id: TileId) {
         self.set_visible(tile_id, !self.is_visible(tile_id));
     }
@@ -174,7 +586,46 @@ impl<Pane> Tiles<Pane> {
         }
     }
 
+    /// The visible pane at the given position, using the rects of the last layout.
+    ///
+    /// Only follows the active children that contain the position,
+    /// so this is cheap also for large trees.
+    pub(super) fn pane_at(&self, root: TileId, pos: Pos2) -> Option<TileId> {
+        let mut tile_id = root;
+        loop {
+            if !self.rect(tile_id)?.contains(pos) {
+                return None;
+            }
+            match self.get(tile_id)? {
+                Tile::Pane(_) | Tile::Placeholder => return Some(tile_id),
+                Tile::Container(container) => {
+                    tile_id = *container
+                        .active_children()
+                        .find(|&&child| self.rect(child).is_some_and(|rect| rect.contains(pos)))?;
+                }
+            }
+        }
+    }
+
+    /// The deepest visible pane under the given tile, following the active tab of each [`crate::Tabs`].
+    ///
+    /// For other containers, the first visible child with a visible pane is followed.
+    /// If the given tile is itself a visible pane, it is returned.
+    pub fn active_leaf_of(&self, tile_id: TileId) -> Option<TileId> {
+        if !self.is_visible(tile_id) {
+            return None;
+        }
+        match self.get(tile_id)? {
+            Tile::Pane(_) | Tile::Placeholder => Some(tile_id),
+            Tile::Container(Container::Tabs(tabs)) => self.active_leaf_of(tabs.active_child()?),
+            Tile::Container(container) => container
+                .children()
+                .find_map(|&child_id| self.active_leaf_of(child_id)),
+        }
+    }
+
     pub fn insert(&mut self, id: TileId, tile: Tile<Pane>) {
+        self.released.remove(&id.index());
         self.tiles.insert(id, tile);
     }
 
@@ -187,19 +638,81 @@ impl<Pane> Tiles<Pane> {
         self.tiles.remove(&id)
     }
 
-    pub fn next_free_id(&mut self) -> TileId {
-        let mut id = TileId::from_u64(self.next_tile_id);
+    /// Remove a tile for good, so that its index can be reused by [`Self::next_free_id`]
+    /// (with a newer generation).
+    ///
+    /// Unlike [`Self::remove`], this must not be used for a tile that is put back later.
+    pub(crate) fn release(&mut self, id: TileId) -> Option<Tile<Pane>> {
+        let tile = self.tiles.remove(&id)?;
+        self.release_index(id);
+        Some(tile)
+    }
 
-        // Make sure it doesn't collide with an existing id
-        while self.tiles.contains_key(&id) {
-            self.next_tile_id += 1;
-            id = TileId::from_u64(self.next_tile_id);
+    fn release_index(&mut self, id: TileId) {
+        // An index whose generations are all used up is never reused.
+        if let Some(generation) = id.generation().checked_add(1) {
+            self.released.insert(id.index(), generation);
         }
+    }
 
-        // Final increment the next_id
-        self.next_tile_id += 1;
+    /// The user asked to close a tab: close it if [`Behavior::on_tab_close`] agrees,
+    /// or wait for the user to confirm.
+    pub(crate) fn request_tab_close(
+        &mut self,
+        behavior: &mut (impl Behavior<Pane> + ?Sized),
+        tile_id: TileId,
+    ) {
+        log::debug!("Tab close requested for tile: {tile_id:?}");
+        match behavior.on_tab_close(self, tile_id) {
+            CloseResponse::Close => {
+                log::debug!("Implementation confirmed close request for tile: {tile_id:?}");
+                self.close_tab(behavior, tile_id);
+            }
+            CloseResponse::Keep => {
+                log::debug!("Implementation denied close request for tile: {tile_id:?}");
+            }
+            CloseResponse::Pending => {
+                log::debug!("Waiting for the user to confirm closing tile: {tile_id:?}");
+                self.pending_close = Some(tile_id);
+            }
+        }
+    }
 
-        id
+    /// Hide or remove a closed tab, see [`Behavior::hide_on_close`].
+    pub(crate) fn close_tab(&mut self, behavior: &(impl Behavior<Pane> + ?Sized), tile_id: TileId) {
+        if behavior.hide_on_close(self, tile_id) {
+            self.set_visible(tile_id, false);
+        } else {
+            self.release(tile_id);
+        }
+    }
+
+    /// A new id, not used by any tile.
+    ///
+    /// This reuses the index of a tile that was removed for good (e.g. with [`crate::Tree::remove_recursively`]),
+    /// with a newer generation, so that ids of removed tiles never refer to new tiles.
+    pub fn next_free_id(&mut self) -> TileId {
+        while let Some((index, generation)) = self.released.pop_first() {
+            let id = TileId::new(index, generation);
+            if !self.tiles.contains_key(&id) {
+                return id;
+            }
+        }
+
+        loop {
+            let Some(id) = TileId::checked_new(self.next_tile_id, 0) else {
+                // E.g. a tree deserialized with a huge `next_tile_id`:
+                log::warn!("Ran out of tile indices, looking for free ones from the start");
+                self.next_tile_id = 1;
+                continue;
+            };
+            self.next_tile_id += 1;
+
+            // Make sure it doesn't collide with an existing id
+            if !self.tiles.contains_key(&id) {
+                return id;
+            }
+        }
     }
 
     #[must_use]
@@ -214,6 +727,13 @@ impl<Pane> Tiles<Pane> {
         self.insert_new(Tile::Pane(pane))
     }
 
+    /// Insert a [`Tile::Placeholder`], whose pane is created by [`Behavior::instantiate_pane`]
+    /// the first time it is shown.
+    #[must_use]
+    pub fn insert_placeholder(&mut self) -> TileId {
+        self.insert_new(Tile::Placeholder)
+    }
+
     #[must_use]
     pub fn insert_container(&mut self, container: impl Into<Container>) -> TileId {
         self.insert_new(Tile::Container(container.into()))
@@ -247,7 +767,7 @@ impl<Pane> Tiles<Pane> {
 
     pub fn parent_of(&self, child_id: TileId) -> Option<TileId> {
         #[allow(clippy::iter_over_hash_type)] // Each tile can only have one parent
-        for (tile_id, tile) in &self.tiles {
+        for (tile_id, tile) in self.tiles.iter() {
             if let Tile::Container(container) = tile {
                 if container.has_child(child_id) {
                     return Some(*tile_id);
@@ -261,7 +781,15 @@ impl<Pane> Tiles<Pane> {
         self.parent_of(tile_id).is_none()
     }
 
-    pub(super) fn insert_at(&mut self, insertion_point: InsertionPoint, inserted_id: TileId) {
+    /// Insert a tile at the given insertion point.
+    ///
+    /// If the parent is not a container of the right kind, a new container is created in its place,
+    /// taking over its id, and the new id of the previous parent is returned.
+    pub(super) fn insert_at(
+        &mut self,
+        insertion_point: InsertionPoint,
+        inserted_id: TileId,
+    ) -> Option<TileId> {
         let InsertionPoint {
             parent_id,
             insertion,
@@ -269,57 +797,64 @@ impl<Pane> Tiles<Pane> {
 
         let Some(mut parent_tile) = self.tiles.remove(&parent_id) else {
             log::debug!("Failed to insert: could not find parent {parent_id:?}");
-            return;
+            return None;
         };
 
+        let mut wrapped_parent = None;
+
         match insertion {
             ContainerInsertion::Tabs(index) => {
                 if let Tile::Container(Container::Tabs(tabs)) = &mut parent_tile {
-                    let index = index.min(tabs.children.len());
-                    tabs.children.insert(index, inserted_id);
+                    tabs.insert_child_impl(index, inserted_id);
                     tabs.set_active(inserted_id);
                     self.tiles.insert(parent_id, parent_tile);
                 } else {
                     let new_tile_id = self.insert_new(parent_tile);
+                    self.rehome(parent_id, new_tile_id);
+                    wrapped_parent = Some(new_tile_id);
                     let mut tabs = Tabs::new(vec![new_tile_id]);
-                    tabs.children.insert(index.min(1), inserted_id);
+                    tabs.insert_child_impl(index, inserted_id);
                     tabs.set_active(inserted_id);
                     self.tiles
                         .insert(parent_id, Tile::Container(Container::Tabs(tabs)));
                 }
             }
             ContainerInsertion::Horizontal(index) => {
-                if let Tile::Container(Container::Linear(Linear {
-                    dir: LinearDir::Horizontal,
-                    children,
-                    ..
-                })) = &mut parent_tile
+                if let Tile::Container(Container::Linear(
+                    linear @ Linear {
+                        dir: LinearDir::Horizontal,
+                        ..
+                    },
+                )) = &mut parent_tile
                 {
-                    let index = index.min(children.len());
-                    children.insert(index, inserted_id);
+                    linear.insert_child_at(index, inserted_id, None);
                     self.tiles.insert(parent_id, parent_tile);
                 } else {
                     let new_tile_id = self.insert_new(parent_tile);
+                    self.rehome(parent_id, new_tile_id);
+                    wrapped_parent = Some(new_tile_id);
                     let mut linear = Linear::new(LinearDir::Horizontal, vec![new_tile_id]);
-                    linear.children.insert(index.min(1), inserted_id);
+                    linear.insert_child_at(index, inserted_id, None);
                     self.tiles
                         .insert(parent_id, Tile::Container(Container::Linear(linear)));
                 }
             }
             ContainerInsertion::Vertical(index) => {
-                if let Tile::Container(Container::Linear(Linear {
-                    dir: LinearDir::Vertical,
-                    children,
-                    ..
-                })) = &mut parent_tile
+                if let Tile::Container(Container::Linear(
+                    linear @ Linear {
+                        dir: LinearDir::Vertical,
+                        ..
+                    },
+                )) = &mut parent_tile
                 {
-                    let index = index.min(children.len());
-                    children.insert(index, inserted_id);
+                    linear.insert_child_at(index, inserted_id, None);
                     self.tiles.insert(parent_id, parent_tile);
                 } else {
                     let new_tile_id = self.insert_new(parent_tile);
+                    self.rehome(parent_id, new_tile_id);
+                    wrapped_parent = Some(new_tile_id);
                     let mut linear = Linear::new(LinearDir::Vertical, vec![new_tile_id]);
-                    linear.children.insert(index.min(1), inserted_id);
+                    linear.insert_child_at(index, inserted_id, None);
                     self.tiles
                         .insert(parent_id, Tile::Container(Container::Linear(linear)));
                 }
@@ -330,12 +865,35 @@ impl<Pane> Tiles<Pane> {
                     self.tiles.insert(parent_id, parent_tile);
                 } else {
                     let new_tile_id = self.insert_new(parent_tile);
+                    self.rehome(parent_id, new_tile_id);
+                    wrapped_parent = Some(new_tile_id);
                     let grid = Grid::new(vec![new_tile_id, inserted_id]);
                     self.tiles
                         .insert(parent_id, Tile::Container(Container::Grid(grid)));
                 }
             }
         }
+
+        wrapped_parent
+    }
+
+    /// The tile `from` was moved to `to`, because a new container took over its id.
+    ///
+    /// Moves the name and visibility of the tile along with it.
+    fn rehome(&mut self, from: TileId, to: TileId) {
+        if let Some(name) = self.names.remove(&from) {
+            self.names.insert(to, name);
+        }
+        if self.invisible.remove(&from) {
+            self.invisible.insert(to);
+        }
+        if let Some(animation) = self.visibility_animations.remove(&from) {
+            self.visibility_animations.insert(to, animation);
+        }
+        if self.pending_close == Some(from) {
+            self.pending_close = Some(to);
+        }
+        self.rehomed.push((from, to));
     }
 
     /// Detect cycles, duplications, and other invalid state, and fix it.
@@ -343,29 +901,102 @@ impl<Pane> Tiles<Pane> {
     /// Will also call [`Behavior::retain_pane`] to check if a users wants to remove a pane.
     ///
     /// Finally free up any tiles that are no longer reachable from the root.
-    pub(super) fn gc_root(&mut self, behavior: &mut dyn Behavior<Pane>, root_id: Option<TileId>) {
+    ///
+    /// Returns the panes that were turned into placeholders, see [`Behavior::keep_placeholder`].
+    pub(super) fn gc_root(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        root_id: Option<TileId>,
+    ) -> Vec<(TileId, Pane)> {
         let mut visited = Default::default();
+        let mut replaced = vec![];
 
         if let Some(root_id) = root_id {
             // We ignore the returned root action, because we will never remove the root.
-            let _root_action = self.gc_tile_id(behavior, &mut visited, root_id);
+            let _root_action = self.gc_tile_id(behavior, &mut visited, &mut replaced, root_id);
         }
 
         if visited.len() < self.tiles.len() {
             // This should only happen if the user set up the tree in a bad state,
             // or if it was restored from a bad state via serde.
             // …or if there is a bug somewhere 😜
-            log::debug!(
-                "GC collecting tiles: {:?}",
-                self.tiles
-                    .keys()
-                    .filter(|id| !visited.contains(id))
-                    .collect::<Vec<_>>()
-            );
+            self.handle_orphans(behavior, &mut visited, &mut replaced);
         }
 
         self.invisible.retain(|tile_id| visited.contains(tile_id));
-        self.tiles.retain(|tile_id, _| visited.contains(tile_id));
+        self.names.retain(|tile_id, _| visited.contains(tile_id));
+        let mut removed = vec![];
+        self.tiles.retain(|tile_id, _| {
+            let keep = visited.contains(tile_id);
+            if !keep {
+                removed.push(*tile_id);
+            }
+            keep
+        });
+        for tile_id in removed {
+            self.release_index(tile_id);
+        }
+
+        replaced
+    }
+
+    /// Let the behavior decide what to do with tiles that were not reachable from the root.
+    fn handle_orphans(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        visited: &mut ahash::HashSet<TileId>,
+        replaced: &mut Vec<(TileId, Pane)>,
+    ) {
+        let unreachable: ahash::HashSet<TileId> = self
+            .tiles
+            .keys()
+            .filter(|id| !visited.contains(id))
+            .copied()
+            .collect();
+
+        // The top-most orphans are the ones that no other orphan has as a child:
+        let orphan_children: ahash::HashSet<TileId> = unreachable
+            .iter()
+            .filter_map(|id| self.get_container(*id))
+            .flat_map(|container| container.children().copied())
+            .collect();
+        let mut orphans: Vec<TileId> = unreachable
+            .iter()
+            .copied()
+            .filter(|id| !orphan_children.contains(id))
+            .collect();
+        orphans.sort_by_key(|id| id.0);
+
+        match behavior.on_unreachable_tiles(self, orphans.clone()) {
+            OrphanPolicy::Drop => {
+                log::debug!("GC collecting tiles: {unreachable:?}");
+            }
+            OrphanPolicy::Adopt(orphanage_id) => {
+                if !visited.contains(&orphanage_id)
+                    || !matches!(self.get_container(orphanage_id), Some(Container::Tabs(_)))
+                {
+                    log::warn!(
+                        "Cannot adopt orphans into {orphanage_id:?}: not a Tabs container in the tree"
+                    );
+                    return;
+                }
+
+                let adopted: Vec<TileId> = orphans
+                    .into_iter()
+                    .filter(|&orphan| {
+                        self.gc_tile_id(behavior, visited, replaced, orphan) == GcAction::Keep
+                    })
+                    .collect();
+                if let Some(Tile::Container(Container::Tabs(orphanage))) =
+                    self.tiles.get_mut(&orphanage_id)
+                {
+                    orphanage.children.extend(adopted);
+                }
+            }
+            OrphanPolicy::Keep => {
+                visited.extend(unreachable);
+            }
+        }
     }
 
     /// Detect cycles, duplications, and other invalid state, and remove them.
@@ -373,6 +1004,7 @@ impl<Pane> Tiles<Pane> {
         &mut self,
         behavior: &mut dyn Behavior<Pane>,
         visited: &mut ahash::HashSet<TileId>,
+        replaced: &mut Vec<(TileId, Pane)>,
         tile_id: TileId,
     ) -> GcAction {
         let Some(mut tile) = self.tiles.remove(&tile_id) else {
@@ -383,21 +1015,93 @@ impl<Pane> Tiles<Pane> {
             return GcAction::Remove;
         }
 
-        match &mut tile {
-            Tile::Pane(pane) => {
-                if !behavior.retain_pane(pane) {
+        match tile {
+            Tile::Pane(pane) if !behavior.retain_pane(&pane) => {
+                if !behavior.keep_placeholder(&pane) {
+                    self.release_index(tile_id);
                     return GcAction::Remove;
                 }
+                replaced.push((tile_id, pane));
+                tile = Tile::Placeholder;
             }
-            Tile::Container(container) => {
-                container
-                    .retain(|child| self.gc_tile_id(behavior, visited, child) == GcAction::Keep);
+            Tile::Container(ref mut container) => {
+                container.retain(|child| {
+                    self.gc_tile_id(behavior, visited, replaced, child) == GcAction::Keep
+                });
             }
+            Tile::Pane(_) | Tile::Placeholder => {}
         }
         self.tiles.insert(tile_id, tile);
         GcAction::Keep
     }
 
+    /// Turn a pane into a [`Tile::Placeholder`], and return the pane.
+    pub(super) fn replace_with_placeholder(&mut self, tile_id: TileId) -> Option<Pane> {
+        let tile = self.tiles.get_mut(&tile_id)?;
+        match std::mem::replace(tile, Tile::Placeholder) {
+            Tile::Pane(pane) => Some(pane),
+            other => {
+                *tile = other;
+                None
+            }
+        }
+    }
+
+    /// Lay out the whole tree in `rect`, starting with the root.
+    ///
+    /// With dense storage the tree is walked by slot index using a [`ChildArena`],
+    /// so that each container lays out its children without recursing into them.
+    pub(super) fn layout_root(
+        &mut self,
+        style: &egui::Style,
+        behavior: &mut dyn Behavior<Pane>,
+        rect: Rect,
+        root: TileId,
+    ) {
+        self.rects.set_num_slots(self.tiles.num_slots());
+        if !self.tiles.is_dense() {
+            self.layout_tile(style, behavior, rect, root);
+            return;
+        }
+
+        let mut arena = self.layout_arena.take().unwrap_or_default();
+        arena.rebuild(&self.tiles);
+        self.layout_arena = Some(arena);
+
+        self.layout_tile(style, behavior, rect, root);
+        let mut stack: Vec<u32> = crate::tile_store::slot_of(root).into_iter().collect();
+        while let Some(slot) = stack.pop() {
+            let Some((tile_id, rect)) = self.rects.slot(slot) else {
+                continue;
+            };
+            let has_children =
+                (self.layout_arena.as_ref()).is_some_and(|arena| !arena.children(slot).is_empty());
+            if !has_children {
+                continue; // a pane, or an empty container
+            }
+            let Some(Tile::Container(mut container)) = self.tiles.remove(&tile_id) else {
+                continue;
+            };
+            container.layout(self, style, behavior, rect, tile_id);
+
+            // The children of a grid are laid out right away, since subgrids need the column shares:
+            if container.kind() != ContainerKind::Grid {
+                if let Some(arena) = &self.layout_arena {
+                    stack.extend(
+                        arena
+                            .children(slot)
+                            .iter()
+                            .rev()
+                            .filter(|&&child| self.rects.slot(child).is_some()),
+                    );
+                }
+            }
+            self.tiles.insert(tile_id, Tile::Container(container));
+        }
+
+        self.layout_arena = None;
+    }
+
     pub(super) fn layout_tile(
         &mut self,
         style: &egui::Style,
@@ -405,19 +1109,91 @@ impl<Pane> Tiles<Pane> {
         rect: Rect,
         tile_id: TileId,
     ) {
+        // A tree squeezed into a tiny space (e.g. a collapsing panel) can produce negative sizes:
+        let rect = crate::sanitize_rect(rect);
+
+        if self.layout_arena.is_some() && self.grid_col_shares.is_empty() {
+            // `Self::layout_root` lays out the children once we return to it.
+            if self.tiles.contains_key(&tile_id) {
+                self.rects.insert(tile_id, rect);
+            } else {
+                log::debug!("Failed to find tile {tile_id:?} during layout");
+            }
+            return;
+        }
+
         let Some(mut tile) = self.tiles.remove(&tile_id) else {
             log::debug!("Failed to find tile {tile_id:?} during layout");
             return;
         };
+
         self.rects.insert(tile_id, rect);
 
         if let Tile::Container(container) = &mut tile {
-            container.layout(self, style, behavior, rect);
+            container.layout(self, style, behavior, rect, tile_id);
         }
 
         self.tiles.insert(tile_id, tile);
     }
 
+    /// The smallest size the tile can be laid out in,
+    /// without any pane getting smaller than its [`Behavior::min_pane_size`].
+    ///
+    /// Takes the current shares into account, since they decide how the space is split.
+    pub(super) fn min_size(
+        &self,
+        style: &egui::Style,
+        behavior: &dyn Behavior<Pane>,
+        tile_id: TileId,
+    ) -> Vec2 {
+        match self.get(tile_id) {
+            None => Vec2::ZERO,
+            Some(Tile::Pane(_) | Tile::Placeholder) => behavior.min_pane_size(self, tile_id),
+            Some(Tile::Container(Container::Tabs(tabs))) => {
+                let mut size = tabs
+                    .active
+                    .filter(|&active| self.is_visible(active))
+                    .map_or(Vec2::ZERO, |active| self.min_size(style, behavior, active));
+                if !behavior.tab_bar_overlay(self, tile_id) {
+                    size.y += behavior.tab_bar_height(style);
+                }
+                size
+            }
+            Some(Tile::Container(Container::Linear(linear))) => {
+                let children = linear
+                    .children
+                    .iter()
+                    .copied()
+                    .filter(|&child| self.is_visible(child))
+                    .collect_vec();
+                let total_shares: f32 = children.iter().map(|&child| linear.shares[child]).sum();
+
+                // Each child gets its share of the space after the gaps:
+                let mut along = 0.0_f32;
+                let mut across = 0.0_f32;
+                for &child in &children {
+                    let min_size = self.min_size(style, behavior, child);
+                    let (min_along, min_across) = match linear.dir {
+                        LinearDir::Horizontal => (min_size.x, min_size.y),
+                        LinearDir::Vertical => (min_size.y, min_size.x),
+                    };
+                    let fraction = linear.shares[child] / total_shares;
+                    if 0.0 < fraction {
+                        along = along.max(min_along / fraction);
+                    }
+                    across = across.max(min_across);
+                }
+                along += behavior.gap_width(style) * children.len().saturating_sub(1) as f32;
+
+                match linear.dir {
+                    LinearDir::Horizontal => vec2(along, across),
+                    LinearDir::Vertical => vec2(across, along),
+                }
+            }
+            Some(Tile::Container(Container::Grid(grid))) => grid.min_size(self, style, behavior),
+        }
+    }
+
     /// Simplify the tree, perhaps culling empty containers,
     /// and/or merging single-child containers into their parent.
     ///
@@ -440,7 +1216,13 @@ impl<Pane> Tiles<Pane> {
             let kind = container.kind();
             container.simplify_children(|child| self.simplify(options, child, Some(kind)));
 
-            if kind == ContainerKind::Tabs {
+            if let Container::Tabs(Tabs {
+                document_area: true,
+                ..
+            }) = container
+            {
+                // Always kept, even when empty:
+            } else if kind == ContainerKind::Tabs {
                 if options.prune_empty_tabs && container.is_empty() {
                     log::trace!("Simplify: removing empty tabs container");
                     return SimplifyAction::Remove;
@@ -448,7 +1230,10 @@ impl<Pane> Tiles<Pane> {
 
                 if options.prune_single_child_tabs {
                     if let Some(only_child) = container.only_child() {
-                        let child_is_pane = matches!(self.get(only_child), Some(Tile::Pane(_)));
+                        let child_is_pane = matches!(
+                            self.get(only_child),
+                            Some(Tile::Pane(_) | Tile::Placeholder)
+                        );
 
                         if options.all_panes_must_have_tabs
                             && child_is_pane
@@ -462,6 +1247,21 @@ impl<Pane> Tiles<Pane> {
                     }
                 }
             } else {
+                if options.join_single_line_grids {
+                    if let Container::Grid(grid) = &*container {
+                        if let Some(dir) = grid.single_line_dir() {
+                            if !grid.subgrid && grid.frozen_rows == 0 && grid.frozen_cols == 0 {
+                                log::trace!("Simplify: converting single-line grid to {dir:?}");
+                                let kind = match dir {
+                                    LinearDir::Horizontal => ContainerKind::Horizontal,
+                                    LinearDir::Vertical => ContainerKind::Vertical,
+                                };
+                                container.convert_kind(self, kind);
+                            }
+                        }
+                    }
+                }
+
                 if options.join_nested_linear_containers {
                     if let Container::Linear(parent) = container {
                         let mut new_children = Vec::with_capacity(parent.children.len());
@@ -488,7 +1288,7 @@ impl<Pane> Tiles<Pane> {
                                             child.shares[grandchild] * share_normalizer;
                                     }
 
-                                    self.tiles.remove(&child_id);
+                                    self.release(child_id);
                                 } else {
                                     // keep the child
                                     new_children.push(child_id);
@@ -525,11 +1325,12 @@ impl<Pane> Tiles<Pane> {
         };
 
         match &mut tile {
-            Tile::Pane(_) => {
+            Tile::Pane(_) | Tile::Placeholder => {
                 if !parent_is_tabs {
                     // Add tabs to this pane:
                     log::trace!("Auto-adding Tabs-parent to pane {it:?}");
                     let new_id = self.insert_new(tile);
+                    self.rehome(it, new_id);
                     self.tiles
                         .insert(it, Tile::Container(Container::new_tabs(vec![new_id])));
                     return;
@@ -596,3 +1397,127 @@ impl<Pane: PartialEq> Tiles<Pane> {
             .map(|(tile_id, _)| *tile_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tree, UiResponse};
+
+    /// Can only build odd panes.
+    struct OddBehavior;
+
+    impl Behavior<u32> for OddBehavior {
+        fn pane_ui(&mut self, _ui: &mut egui::Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+
+        fn retain_pane(&mut self, pane: &u32) -> bool {
+            pane % 2 == 1
+        }
+
+        fn keep_placeholder(&mut self, _pane: &u32) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_gc_keeps_placeholders() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2, 3]);
+        let root = tree.root().unwrap();
+        let children = tree.tiles.get_container(root).unwrap().children_vec();
+
+        tree.gc(&mut OddBehavior);
+        assert_eq!(
+            tree.tiles.get_container(root).unwrap().children_vec(),
+            children,
+            "No pane was removed"
+        );
+        assert_eq!(tree.tiles.get(children[1]), Some(&Tile::Placeholder));
+        assert_eq!(tree.tiles.get_pane(&children[2]), Some(&3));
+    }
+
+    #[test]
+    fn test_name_follows_wrapped_pane() {
+        let mut tree = Tree::new_tabs("tree", vec![1, 2]);
+        let root = tree.root().unwrap();
+        let [editor, other] = tree.tiles.get_container(root).unwrap().children_vec()[..] else {
+            panic!("Expected two tabs");
+        };
+        tree.tiles.set_name(editor, "editor");
+        let egui_id = tree.tile_egui_id(editor);
+
+        // Split the editor by dropping the other pane to its right:
+        let insertion = InsertionPoint::new(editor, ContainerInsertion::Horizontal(1));
+        let moved_editor = tree.move_tile(other, insertion, false).unwrap();
+        assert!(matches!(
+            tree.tiles.get(editor),
+            Some(Tile::Container(Container::Linear(_)))
+        ));
+        assert_eq!(tree.tiles.get_pane(&moved_editor), Some(&1));
+        assert_eq!(tree.tiles.find_by_name("editor"), Some(moved_editor));
+        assert_eq!(tree.tile_egui_id(moved_editor), egui_id);
+    }
+
+    #[test]
+    fn test_only_changed_tiles_animate_visibility() {
+        let mut tree = Tree::new_horizontal("tree", vec![1, 2, 3]);
+        let root = tree.root().unwrap();
+        let [first, second, _] = tree.tiles.get_container(root).unwrap().children_vec()[..] else {
+            panic!("Expected three children");
+        };
+
+        tree.tiles.set_visible(first, true); // already visible
+        tree.tiles.set_visible(second, false);
+        assert_eq!(tree.tiles.visibility_animations.len(), 1);
+
+        let ctx = egui::Context::default();
+        let animation_time = 1.0;
+        ctx.begin_pass(egui::RawInput::default());
+        tree.tiles
+            .update_visibility_animations(&ctx, animation_time);
+        assert_eq!(tree.tiles.visibility_factor(first), 1.0);
+        assert_eq!(
+            tree.tiles.visibility_factor(second),
+            1.0,
+            "Starts fully visible"
+        );
+        let _output = ctx.end_pass();
+
+        ctx.begin_pass(egui::RawInput {
+            time: Some(0.5),
+            ..Default::default()
+        });
+        tree.tiles
+            .update_visibility_animations(&ctx, animation_time);
+        let halfway = tree.tiles.visibility_factor(second);
+        assert!(0.4 < halfway && halfway < 0.6, "Halfway through: {halfway}");
+        let _output = ctx.end_pass();
+
+        ctx.begin_pass(egui::RawInput {
+            time: Some(2.0),
+            ..Default::default()
+        });
+        tree.tiles
+            .update_visibility_animations(&ctx, animation_time);
+        assert_eq!(tree.tiles.visibility_factor(second), 0.0);
+        assert!(
+            tree.tiles.visibility_animations.is_empty(),
+            "Settled animations should be dropped"
+        );
+        let _output = ctx.end_pass();
+    }
+
+    #[test]
+    fn test_next_free_id_survives_huge_next_tile_id() {
+        let mut tiles: Tiles<u32> = Tiles::default();
+        let first = tiles.insert_pane(1);
+        tiles.next_tile_id = u64::MAX - 1;
+        let id = tiles.insert_pane(2);
+        assert_ne!(id, first);
+        assert_eq!(tiles.get_pane(&id), Some(&2));
+    }
+}
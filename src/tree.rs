@@ -1,13 +1,62 @@
 use egui::{NumExt as _, Rect, Ui};
 
-use crate::behavior::EditAction;
-use crate::{ContainerInsertion, ContainerKind, UiResponse};
+use crate::behavior::{
+    AttentionLevel, DragEnd, DragOrigin, DropOutsideAction, DropResult, EditAction, GrabOffset,
+    GridDrop, GridDropPreview, OverflowPolicy,
+};
+use crate::command::CommandQueue;
+use crate::container::FixedSizes;
+use crate::{
+    ContainerInsertion, ContainerKind, Grid, IdScheme, Layout, LayoutEvent, LayoutScript, Side,
+    Tabs, TreeKeymap, UiResponse,
+};
 
 use super::{
-    Behavior, Container, DropContext, InsertionPoint, SimplificationOptions, SimplifyAction, Tile,
-    TileId, Tiles,
+    Behavior, Container, DropContext, InsertionPoint, PaneDrop, SimplificationOptions,
+    SimplifyAction, Tile, TileId, TileUiTag, Tiles,
 };
 
+/// Where a tab was when a drag of it started, so that we can restore the active tab
+/// if the drag is cancelled, or the tab is dropped back where it was.
+#[derive(Clone, Copy, Debug)]
+struct TabDragStart {
+    tabs_id: TileId,
+    dragged_tile_id: TileId,
+    index: usize,
+    previous_active: Option<TileId>,
+}
+
+/// Remembered while a grid column or row is dragged as a new [`Linear`] container.
+#[derive(Clone)]
+struct GridLineDrag {
+    grid_id: TileId,
+    group_id: TileId,
+
+    /// Where in [`crate::Grid::children`] the new container was put.
+    group_slot: usize,
+
+    /// The children of the grid before the line was put in the new container.
+    grid_children: Vec<Option<TileId>>,
+}
+
+/// What happened when a [`Tree`] was shown, returned by [`Tree::show`].
+#[derive(Clone, Debug)]
+pub struct TreeResponse {
+    /// The response of the whole area of the tree.
+    ///
+    /// It senses the clicks that no widget inside the tree used,
+    /// e.g. on empty parts of the tab bars, or on panes without interactive widgets.
+    pub response: egui::Response,
+
+    /// The tile that was being dragged at the start of the frame, if any.
+    pub dragged_tile_id: Option<TileId>,
+
+    /// The shown pane under the mouse pointer, if any.
+    ///
+    /// See also [`Behavior::hover_highlight_stroke`].
+    pub hovered_pane: Option<TileId>,
+}
+
 /// The top level type. Contains all persistent state, including layouts and sizes.
 ///
 /// You'll usually construct this once and then store it, calling [`Tree::ui`] each frame.
@@ -26,6 +75,9 @@ use super::{
 ///
 /// let tree = Tree::new("my_tree", root, tiles);
 /// ```
+///
+/// You can also describe the layout as plain data with [`Layout`] and use [`Tree::from_layout_fn`],
+/// or load it from a text [`crate::LayoutPreset`].
 #[derive(Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct Tree<Pane> {
@@ -53,6 +105,69 @@ pub struct Tree<Pane> {
         serde(deserialize_with = "deserialize_f32_null_as_infinity")
     )]
     width: f32,
+
+    /// Set during [`Self::ui_readonly`]: no resizing, dragging or closing, only tab switching.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) read_only: bool,
+
+    /// Paint into the parent [`Ui`] without allocating space in it, see [`Self::set_paint_only`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    paint_only: bool,
+
+    /// How the ids of the widgets are derived, see [`IdScheme`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    id_scheme: IdScheme,
+
+    /// The id all widget ids are derived from, set by [`Self::ui`] from [`Self::id_scheme`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    base_id: Option<egui::Id>,
+
+    /// Tiles that want the attention of the user, see [`Self::request_attention`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    attention: ahash::HashMap<TileId, AttentionLevel>,
+
+    /// The highest [`Self::attention`] of each tile and its descendants, updated by [`Self::ui`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subtree_attention: ahash::HashMap<TileId, AttentionLevel>,
+
+    /// The sidebars created by [`Self::new_with_sidebars`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) sidebars: ahash::HashMap<Side, TileId>,
+
+    /// Groups of [`crate::Linear`] containers whose shares are kept in sync, see [`Self::link_shares`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    share_groups: Vec<Vec<TileId>>,
+
+    /// Commands waiting for the next [`Self::ui`], see [`Self::queue`].
+    #[cfg_attr(feature = "serde", serde(skip, default = "CommandQueue::default"))]
+    pub(crate) commands: CommandQueue<Pane>,
+
+    /// The user interactions recorded since [`Self::start_recording`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) recording: Option<LayoutScript>,
+
+    /// The tiles that were laid out last frame, for [`Behavior::on_visibility_changed`].
+    #[cfg_attr(feature = "serde", serde(skip))]
+    shown_tiles: ShownTiles,
+
+    /// Pane states saved by [`Self::save_pane_states`], waiting to be restored.
+    #[cfg(feature = "serde")]
+    #[serde(default, skip_serializing_if = "ahash::HashMap::is_empty")]
+    pane_states: ahash::HashMap<TileId, serde_json::Value>,
+}
+
+/// The tiles that were laid out last frame, see [`Tree::notify_visibility_changes`].
+#[derive(Clone, Default)]
+struct ShownTiles(ahash::HashSet<TileId>);
+
+/// Which tiles were shown is not part of the state of the tree.
+impl PartialEq for ShownTiles {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
 }
 
 // Workaround for JSON which doesn't support infinity, because JSON is stupid.
@@ -86,9 +201,13 @@ impl<Pane: std::fmt::Debug> std::fmt::Debug for Tree<Pane> {
             tile_id: TileId,
         ) -> std::fmt::Result {
             write!(f, "{} {tile_id:?}: ", "  ".repeat(indent))?;
+            if let Some(name) = tiles.name(tile_id) {
+                write!(f, "{name:?} ")?;
+            }
             if let Some(tile) = tiles.get(tile_id) {
                 match tile {
                     Tile::Pane(pane) => writeln!(f, "Pane {pane:?}"),
+                    Tile::Placeholder => writeln!(f, "Placeholder"),
                     Tile::Container(container) => {
                         writeln!(
                             f,
@@ -116,6 +235,19 @@ impl<Pane: std::fmt::Debug> std::fmt::Debug for Tree<Pane> {
             tiles,
             width,
             height,
+            read_only: _, // transient state
+            paint_only: _,
+            id_scheme: _,
+            base_id: _, // transient state
+            attention: _,
+            subtree_attention: _,
+            sidebars: _,
+            share_groups: _,
+            commands: _,
+            recording: _,
+            shown_tiles: _, // transient state
+            #[cfg(feature = "serde")]
+                pane_states: _,
         } = self;
 
         if let Some(root) = root {
@@ -145,6 +277,19 @@ impl<Pane> Tree<Pane> {
             tiles: Default::default(),
             width: f32::INFINITY,
             height: f32::INFINITY,
+            read_only: false,
+            paint_only: false,
+            id_scheme: IdScheme::default(),
+            base_id: None,
+            attention: Default::default(),
+            subtree_attention: Default::default(),
+            sidebars: Default::default(),
+            share_groups: Default::default(),
+            commands: Default::default(),
+            recording: None,
+            shown_tiles: Default::default(),
+            #[cfg(feature = "serde")]
+            pane_states: Default::default(),
         }
     }
 
@@ -160,6 +305,19 @@ impl<Pane> Tree<Pane> {
             tiles,
             width: f32::INFINITY,
             height: f32::INFINITY,
+            read_only: false,
+            paint_only: false,
+            id_scheme: IdScheme::default(),
+            base_id: None,
+            attention: Default::default(),
+            subtree_attention: Default::default(),
+            sidebars: Default::default(),
+            share_groups: Default::default(),
+            commands: Default::default(),
+            recording: None,
+            shown_tiles: Default::default(),
+            #[cfg(feature = "serde")]
+            pane_states: Default::default(),
         }
     }
 
@@ -209,6 +367,187 @@ impl<Pane> Tree<Pane> {
         Self::new(id, root, tiles)
     }
 
+    /// Create a tree from a plain-data [`Layout`] description.
+    ///
+    /// `key` is called for each pane, and the returned keys map to the [`TileId`]s of the panes
+    /// in the returned map. If two panes have the same key, the last one wins.
+    ///
+    /// The `id` must be _globally_ unique (!).
+    /// This is so that the same tree can be added to different [`egui::Ui`]s (if you want).
+    pub fn from_layout_fn<Key: std::hash::Hash + Eq>(
+        id: impl Into<egui::Id>,
+        layout: Layout<Pane>,
+        mut key: impl FnMut(&Pane) -> Key,
+    ) -> (Self, ahash::HashMap<Key, TileId>) {
+        let mut tiles = Tiles::default();
+        let mut tile_ids = ahash::HashMap::default();
+        let root = layout.insert_into(&mut tiles, &mut |pane, tile_id| {
+            tile_ids.insert(key(pane), tile_id);
+        });
+        (Self::new(id, root, tiles), tile_ids)
+    }
+
+    /// Convert all panes to a different type, keeping the structure, tile ids, shares and visibility.
+    ///
+    /// This lets you save a tree of panes that can't be serialized (e.g. because they hold textures)
+    /// by first converting them to a serializable description, and then back again after loading:
+    ///
+    /// ```
+    /// # use egui_tiles::Tree;
+    /// struct Pane { name: String /* , texture: … */ }
+    ///
+    /// let tree = Tree::new_tabs("my_tree", vec![Pane { name: "a".to_owned() }]);
+    ///
+    /// let saved: Tree<String> = tree.map_panes_ref(|_tile_id, pane| pane.name.clone());
+    /// let loaded: Tree<Pane> = saved.map_panes(|_tile_id, name| Pane { name });
+    /// ```
+    pub fn map_panes<NewPane>(self, f: impl FnMut(TileId, Pane) -> NewPane) -> Tree<NewPane> {
+        let Self {
+            id,
+            root,
+            tiles,
+            height,
+            width,
+            read_only,
+            paint_only,
+            id_scheme,
+            base_id,
+            attention,
+            subtree_attention,
+            sidebars,
+            share_groups,
+            commands: _, // can't convert queued commands
+            recording,
+            shown_tiles,
+            #[cfg(feature = "serde")]
+            pane_states,
+        } = self;
+        Tree {
+            id,
+            root,
+            tiles: tiles.map_panes(f),
+            height,
+            width,
+            read_only,
+            paint_only,
+            id_scheme,
+            base_id,
+            attention,
+            subtree_attention,
+            sidebars,
+            share_groups,
+            commands: Default::default(),
+            recording,
+            shown_tiles,
+            #[cfg(feature = "serde")]
+            pane_states,
+        }
+    }
+
+    /// Like [`Self::map_panes`], but leaves `self` untouched.
+    pub fn map_panes_ref<NewPane>(&self, f: impl FnMut(TileId, &Pane) -> NewPane) -> Tree<NewPane> {
+        Tree {
+            id: self.id,
+            root: self.root,
+            tiles: self.tiles.map_panes_ref(f),
+            height: self.height,
+            width: self.width,
+            read_only: self.read_only,
+            paint_only: self.paint_only,
+            id_scheme: self.id_scheme,
+            base_id: self.base_id,
+            attention: self.attention.clone(),
+            subtree_attention: self.subtree_attention.clone(),
+            sidebars: self.sidebars.clone(),
+            share_groups: self.share_groups.clone(),
+            commands: Default::default(),
+            recording: self.recording.clone(),
+            shown_tiles: self.shown_tiles.clone(),
+            #[cfg(feature = "serde")]
+            pane_states: self.pane_states.clone(),
+        }
+    }
+
+    /// Store the state of each pane from [`Behavior::save_pane_state`] in the tree,
+    /// so that it is serialized with it.
+    ///
+    /// Call this right before serializing the tree, and [`Self::restore_pane_states`]
+    /// after deserializing it.
+    ///
+    /// If your panes are not serializable, you can serialize e.g. `tree.map_panes_ref(|_, _| ())`,
+    /// which keeps the saved states, and recreate the panes with [`Self::map_panes`]
+    /// before restoring their states.
+    #[cfg(feature = "serde")]
+    pub fn save_pane_states(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        // States of placeholders are kept until they are turned into panes:
+        let tiles = &self.tiles;
+        self.pane_states
+            .retain(|&tile_id, _| tiles.get(tile_id).is_some_and(Tile::is_placeholder));
+
+        for (&tile_id, tile) in self.tiles.iter() {
+            if let Tile::Pane(pane) = tile {
+                if let Some(state) = behavior.save_pane_state(pane) {
+                    self.pane_states.insert(tile_id, state);
+                }
+            }
+        }
+    }
+
+    /// Give the states stored by [`Self::save_pane_states`] back to [`Behavior::restore_pane_state`].
+    ///
+    /// States of [`Tile::Placeholder`]s are kept until the placeholder is turned into a pane.
+    #[cfg(feature = "serde")]
+    pub fn restore_pane_states(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        #[allow(clippy::iter_over_hash_type)] // Each state is restored independently
+        for (tile_id, state) in std::mem::take(&mut self.pane_states) {
+            match self.tiles.get_mut(tile_id) {
+                Some(Tile::Pane(pane)) => behavior.restore_pane_state(pane, state),
+                Some(Tile::Placeholder) => {
+                    self.pane_states.insert(tile_id, state);
+                }
+                Some(Tile::Container(_)) | None => {
+                    log::debug!("No pane to restore the state of {tile_id:?} to");
+                }
+            }
+        }
+    }
+
+    /// Turn a pane into a [`Tile::Placeholder`], e.g. because the plugin providing it was unloaded.
+    ///
+    /// The tile keeps its place in the tree, also when the tree is serialized.
+    /// It is shown with [`Behavior::missing_pane_ui`] until [`Behavior::instantiate_pane`]
+    /// can create the pane again.
+    /// With the `serde` feature, the state from [`Behavior::save_pane_state`] is kept for that new pane.
+    ///
+    /// Returns the removed pane, or `None` if the tile is not a pane.
+    #[cfg_attr(
+        not(feature = "serde"),
+        allow(unused_variables, clippy::needless_pass_by_ref_mut)
+    )]
+    pub fn replace_pane_with_placeholder(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+    ) -> Option<Pane> {
+        let pane = self.tiles.replace_with_placeholder(tile_id)?;
+        #[cfg(feature = "serde")]
+        self.save_placeholder_state(behavior, tile_id, &pane);
+        Some(pane)
+    }
+
+    /// Keep the state of a pane that was turned into a placeholder, for when it is instantiated again.
+    #[cfg(feature = "serde")]
+    fn save_placeholder_state(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        pane: &Pane,
+    ) {
+        if let Some(state) = behavior.save_pane_state(pane) {
+            self.pane_states.insert(tile_id, state);
+        }
+    }
+
     /// Remove the given tile and all child tiles, recursively.
     ///
     /// This also removes the tile id from the parent's list of children.
@@ -224,10 +563,10 @@ impl<Pane> Tree<Pane> {
     }
 
     fn remove_recursively_impl(&mut self, id: TileId, removed_tiles: &mut Vec<Tile<Pane>>) {
-        // We can safely use the raw `tiles.remove` API here because either the parent was cleaned
+        // We can safely use the raw `tiles.release` API here because either the parent was cleaned
         // up explicitly from `remove_recursively` or the parent is also being removed so there's
         // no reason to clean it up.
-        if let Some(tile) = self.tiles.remove(id) {
+        if let Some(tile) = self.tiles.release(id) {
             if let Tile::Container(container) = &tile {
                 for &child_id in container.children() {
                     self.remove_recursively_impl(child_id, removed_tiles);
@@ -237,12 +576,109 @@ impl<Pane> Tree<Pane> {
         }
     }
 
+    /// Remove all panes for which `keep` returns `false`, e.g. all panes of a project that is closed.
+    ///
+    /// The containers left empty or with a single child are then cleaned up with [`Self::simplify`],
+    /// according to the given options.
+    ///
+    /// Returns the removed panes, in unspecified order.
+    ///
+    /// ```
+    /// # use egui_tiles::*;
+    /// let mut tree = Tree::new_tabs("tree", vec![1, 2, 3, 4]);
+    /// let mut removed = tree.retain_panes(&SimplificationOptions::default(), |_, &pane| pane % 2 == 0);
+    /// removed.sort();
+    /// assert_eq!(removed, vec![1, 3]);
+    /// ```
+    pub fn retain_panes(
+        &mut self,
+        options: &SimplificationOptions,
+        mut keep: impl FnMut(TileId, &Pane) -> bool,
+    ) -> Vec<Pane> {
+        let removed_ids: ahash::HashSet<TileId> = self
+            .tiles
+            .iter()
+            .filter_map(|(&tile_id, tile)| match tile {
+                Tile::Pane(pane) if !keep(tile_id, pane) => Some(tile_id),
+                _ => None,
+            })
+            .collect();
+        if removed_ids.is_empty() {
+            return vec![];
+        }
+
+        for tile in self.tiles.tiles_mut() {
+            if let Tile::Container(container) = tile {
+                container.retain(|child| !removed_ids.contains(&child));
+            }
+        }
+        if self.root.is_some_and(|root| removed_ids.contains(&root)) {
+            self.root = None;
+        }
+
+        let mut removed_ids = removed_ids.into_iter().collect::<Vec<_>>();
+        removed_ids.sort_by_key(|tile_id| tile_id.as_u64());
+        let removed = removed_ids
+            .into_iter()
+            .filter_map(|tile_id| match self.tiles.release(tile_id) {
+                Some(Tile::Pane(pane)) => Some(pane),
+                _ => None,
+            })
+            .collect();
+
+        self.simplify(options);
+        removed
+    }
+
     /// The globally unique id used by this `Tree`.
     #[inline]
     pub fn id(&self) -> egui::Id {
         self.id
     }
 
+    /// How the ids of the widgets of this tree are derived.
+    #[inline]
+    pub fn id_scheme(&self) -> IdScheme {
+        self.id_scheme
+    }
+
+    /// Set how the ids of the widgets of this tree are derived.
+    ///
+    /// To show the same tree in several places in the same frame,
+    /// use [`IdScheme::Ui`] and call [`Self::ui`] once for each place,
+    /// with a different [`Ui`] each time.
+    pub fn set_id_scheme(&mut self, id_scheme: IdScheme) {
+        self.id_scheme = id_scheme;
+    }
+
+    /// Should the tree be painted into the parent [`Ui`] without allocating space in it?
+    #[inline]
+    pub fn is_paint_only(&self) -> bool {
+        self.paint_only
+    }
+
+    /// Paint the tree into the available space of the parent [`Ui`] without allocating it.
+    ///
+    /// By default the tree allocates its rect in the parent [`Ui`], like any other widget,
+    /// so that widgets added after it are placed after it.
+    /// With `paint_only`, those widgets are placed on top of the tree instead,
+    /// which is useful for overlays.
+    pub fn set_paint_only(&mut self, paint_only: bool) {
+        self.paint_only = paint_only;
+    }
+
+    /// The id all the widget ids and per-frame state of this tree are derived from.
+    ///
+    /// With [`IdScheme::Ui`] this is the one of the last [`Ui`] the tree was shown in.
+    pub(crate) fn base_id(&self) -> egui::Id {
+        self.base_id.unwrap_or(self.id)
+    }
+
+    /// The [`egui::Id`] of the tab button of a tile, also used when dragging the tile.
+    pub(crate) fn tile_egui_id(&self, tile_id: TileId) -> egui::Id {
+        self.tiles.egui_id(self.base_id(), tile_id)
+    }
+
     /// Check if [`Self::root`] is [`None`].
     #[inline]
     pub fn is_empty(&self) -> bool {
@@ -269,10 +705,48 @@ impl<Pane> Tree<Pane> {
     /// Tiles are visible by default.
     ///
     /// Invisible tiles still retain their place in the tile hierarchy.
+    /// [`Behavior::on_visibility_changed`] is called for the tile and its children during the next [`Self::ui`].
     pub fn set_visible(&mut self, tile_id: TileId, visible: bool) {
         self.tiles.set_visible(tile_id, visible);
     }
 
+    /// Is the tile visible, and so are all its ancestors, and is it in the active tab of every [`crate::Tabs`] above it?
+    ///
+    /// Unlike [`Self::is_visible`], which only checks the flag of the tile itself.
+    /// Tiles that are not reachable from the root are not effectively visible.
+    ///
+    /// See [`Self::effective_visibility_map`] for checking many tiles at once.
+    pub fn is_effectively_visible(&self, tile_id: TileId) -> bool {
+        let mut tile_id = tile_id;
+        loop {
+            if !self.tiles.is_visible(tile_id) || self.tiles.get(tile_id).is_none() {
+                return false;
+            }
+            if self.is_root(tile_id) {
+                return true;
+            }
+            let Some(parent_id) = self.tiles.parent_of(tile_id) else {
+                return false;
+            };
+            let Some(Tile::Container(parent)) = self.tiles.get(parent_id) else {
+                return false;
+            };
+            if !parent.active_children().any(|&child| child == tile_id) {
+                return false; // e.g. an inactive tab
+            }
+            tile_id = parent_id;
+        }
+    }
+
+    /// [`Self::is_effectively_visible`] for every tile in the tree.
+    pub fn effective_visibility_map(&self) -> ahash::HashMap<TileId, bool> {
+        let active: ahash::HashSet<TileId> = self.active_tiles().into_iter().collect();
+        self.tiles
+            .tile_ids()
+            .map(|tile_id| (tile_id, active.contains(&tile_id)))
+            .collect()
+    }
+
     /// All visible tiles.
     ///
     /// This excludes all tiles that invisible or are inactive tabs, recursively.
@@ -288,115 +762,793 @@ impl<Pane> Tree<Pane> {
         tiles
     }
 
+    /// The deepest visible pane under the given tile, following the active tab of each [`crate::Tabs`].
+    ///
+    /// Useful for acting on "the currently shown pane" in some part of the tree.
+    /// See [`Tiles::active_leaf_of`].
+    pub fn active_leaf_of(&self, tile_id: TileId) -> Option<TileId> {
+        self.tiles.active_leaf_of(tile_id)
+    }
+
     /// Show the tree in the given [`Ui`].
     ///
     /// The tree will use upp all the available space - nothing more, nothing less.
+    ///
+    /// See [`Self::show`] for a version that returns a [`TreeResponse`].
     pub fn ui(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) {
-        self.simplify(&behavior.simplification_options());
+        self.show(behavior, ui);
+    }
+
+    /// Show the tree in the given [`Ui`], like [`Self::ui`], and return the response of its area.
+    ///
+    /// Use it to e.g. show a context menu when the user right-clicks an empty part of the tree,
+    /// or to check if the pointer is anywhere over the tree.
+    ///
+    /// If the [`Ui`] is disabled (see [`Ui::disable`]), e.g. while a modal dialog is open,
+    /// the tree is shown with disabled visuals and ignores all input.
+    pub fn show(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) -> TreeResponse {
+        self.show_impl(behavior, ui, None)
+    }
+
+    /// Show the tree with the given size, even if it is larger than the available space.
+    ///
+    /// Use this to put the tree in a [`egui::ScrollArea`]:
+    /// nothing can be dropped on the parts of the tree that are scrolled out of view.
+    ///
+    /// See [`Self::show_sized`] for a version that returns a [`TreeResponse`].
+    pub fn ui_sized(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        desired_size: egui::Vec2,
+    ) {
+        self.show_sized(behavior, ui, desired_size);
+    }
+
+    /// Like [`Self::ui_sized`], but returns the response of the area of the tree.
+    pub fn show_sized(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        desired_size: egui::Vec2,
+    ) -> TreeResponse {
+        self.show_impl(behavior, ui, Some(desired_size))
+    }
+
+    fn show_impl(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        ui: &mut Ui,
+        desired_size: Option<egui::Vec2>,
+    ) -> TreeResponse {
+        self.base_id = Some(self.id_scheme.base_id(self.id, ui.id()));
+        self.apply_queued_commands(behavior);
+
+        // A disabled tree can't be edited, and can't switch tabs either:
+        let was_read_only = self.read_only;
+        if !ui.is_enabled() {
+            self.read_only = true;
+        }
+
+        if behavior.auto_simplify() {
+            self.record(LayoutEvent::Simplified);
+            self.simplify(&behavior.simplification_options());
 
-        self.gc(behavior);
+            self.gc(behavior);
+        }
+        self.follow_rehomed_tiles(behavior);
+
+        self.restore_active_tab_after_drag(ui.ctx());
+        self.update_attention();
+
+        if ui.is_enabled() {
+            // Uses the rects from the previous frame:
+            self.update_focused_pane(ui);
+            self.handle_keybindings(behavior, ui);
+        }
 
         self.tiles.rects.clear();
+        self.tiles.clip_rects.clear();
+        // A `ScrollArea` clips a bit outside of what it shows:
+        self.tiles.viewport = Some(ui.clip_rect().shrink(ui.visuals().clip_rect_margin));
+        self.tiles.pixels_per_point = behavior.pixel_snap().then(|| ui.ctx().pixels_per_point());
+        self.tiles
+            .update_visibility_animations(ui.ctx(), behavior.visibility_animation_time());
 
         // Check if anything is being dragged:
+        let dragged_tile_id = if self.read_only {
+            None
+        } else {
+            self.dragged_id(ui.ctx())
+        };
+        self.notify_drag_changes(behavior, ui.ctx(), dragged_tile_id);
+        if self.tiles.is_dragging && dragged_tile_id.is_none() {
+            self.tiles.drag_ended_at = Some(ui.input(|i| i.time));
+        }
+        self.tiles.is_dragging = dragged_tile_id.is_some();
+        let allowed_kinds = if let Some(dragged_tile_id) = dragged_tile_id {
+            let origin = self.drag_origin(ui.ctx()).unwrap_or_default();
+            ContainerKind::ALL
+                .into_iter()
+                .filter(|&kind| {
+                    behavior.is_drop_allowed(&self.tiles, dragged_tile_id, origin, kind)
+                })
+                .collect()
+        } else {
+            vec![]
+        };
+        let previous_insertion_id = self.base_id().with("previous_drop_insertion");
+        let (previous_insertion, drag_preview_rect) = if let Some(dragged_tile_id) = dragged_tile_id
+        {
+            (
+                ui.data(|data| data.get_temp::<InsertionPoint>(previous_insertion_id)),
+                ui.ctx()
+                    .memory(|mem| mem.area_rect(ui.id().with((dragged_tile_id, "preview")))),
+            )
+        } else {
+            (None, None)
+        };
+        let mut rect = ui.available_rect_before_wrap();
+        if let Some(desired_size) = desired_size {
+            rect.set_width(desired_size.x);
+            rect.set_height(desired_size.y);
+        } else {
+            if self.height.is_finite() {
+                rect.set_height(self.height);
+            }
+            if self.width.is_finite() {
+                rect.set_width(self.width);
+            }
+        }
+
         let mut drop_context = DropContext {
             enabled: true,
-            dragged_tile_id: self.dragged_id(ui.ctx()),
-            mouse_pos: ui.input(|i| i.pointer.interact_pos()),
-            best_dist_sq: f32::INFINITY,
+            dragged_tile_id,
+            // Don't drop anything outside the tree, where it is scrolled out of view,
+            // or beneath a window that is in front of the tree:
+            mouse_pos: ui.input(|i| i.pointer.interact_pos()).filter(|&pos| {
+                rect.contains(pos)
+                    && ui.clip_rect().contains(pos)
+                    && !crate::is_covered_by_other_layer(ui, pos)
+            }),
+            best_score: f32::INFINITY,
             best_insertion: None,
             preview_rect: None,
+            previous_insertion,
+            drag_preview_rect,
+            pane_target: None,
+            ghost_tab: None,
+            empty_tree_root: None,
+            allowed_kinds,
         };
 
-        let mut rect = ui.available_rect_before_wrap();
-        if self.height.is_finite() {
-            rect.set_height(self.height);
+        // Sense this before the tiles, so that any widget inside the tree takes priority:
+        let response = if self.paint_only {
+            ui.interact(rect, self.base_id().with("tree_area"), egui::Sense::click())
+        } else {
+            ui.allocate_rect(rect, egui::Sense::click())
+        };
+
+        let min_size = match (self.root, behavior.overflow_policy()) {
+            (Some(root), OverflowPolicy::Scroll) => self.tiles.min_size(ui.style(), behavior, root),
+            _ => egui::Vec2::ZERO,
+        };
+        let drag_end = if rect.width() < min_size.x || rect.height() < min_size.y {
+            // Scroll instead of squishing the panes below their minimum size:
+            let mut scroll_ui = ui.new_child(egui::UiBuilder::new().max_rect(rect));
+            egui::ScrollArea::both()
+                .id_salt(self.base_id().with("overflow"))
+                .auto_shrink(false)
+                .drag_to_scroll(false) // Dragging is for tiles
+                .show(&mut scroll_ui, |ui| {
+                    let content_rect =
+                        Rect::from_min_size(ui.max_rect().min, rect.size().max(min_size));
+                    self.tiles.viewport =
+                        Some(ui.clip_rect().shrink(ui.visuals().clip_rect_margin));
+                    let drag_end =
+                        self.layout_and_show(behavior, &mut drop_context, ui, content_rect);
+                    ui.advance_cursor_after_rect(content_rect);
+                    drag_end
+                })
+                .inner
+        } else {
+            self.layout_and_show(behavior, &mut drop_context, ui, rect)
+        };
+
+        let hovered_pane = ui
+            .input(|i| i.pointer.hover_pos())
+            .filter(|&pos| {
+                rect.contains(pos)
+                    && ui.clip_rect().contains(pos)
+                    && !crate::is_covered_by_other_layer(ui, pos)
+            })
+            .and_then(|pos| self.tiles.pane_at(self.root?, pos));
+        let painter = ui.painter_at(rect);
+        if let (Some(hovered_pane), None) = (hovered_pane, dragged_tile_id) {
+            let fill = behavior.hover_highlight_fill(ui.visuals());
+            let stroke = behavior.hover_highlight_stroke(ui.visuals());
+            if fill != egui::Color32::TRANSPARENT || !stroke.is_empty() {
+                let pane_rect = self.tiles.rect_or_die(hovered_pane);
+                painter.rect(pane_rect.shrink(0.5 * stroke.width), 0.0, fill, stroke);
+            }
         }
-        if self.width.is_finite() {
-            rect.set_width(self.width);
+        if behavior.paints_on_top_of_tree() {
+            behavior.paint_on_top_of_tree(&painter, ui.style(), &self.geometry_snapshot());
         }
-        if let Some(root) = self.root {
-            self.tiles.layout_tile(ui.style(), behavior, rect, root);
+        if let (Some(dragged_tile_id), Some(drag_end)) = (dragged_tile_id, drag_end) {
+            ui.data_mut(|data| data.remove::<TileId>(self.drag_lifecycle_id()));
+            behavior.on_drag_ended(&self.tiles, dragged_tile_id, drag_end);
+        }
+        self.restore_grid_line_after_drag(ui.ctx());
+        self.close_confirmation_ui(behavior, ui.ctx());
+        self.notify_visibility_changes(behavior);
+
+        ui.data_mut(|data| match drop_context.best_insertion {
+            Some(insertion) if dragged_tile_id.is_some() => {
+                data.insert_temp(previous_insertion_id, insertion);
+            }
+            _ => {
+                data.remove::<InsertionPoint>(previous_insertion_id);
+            }
+        });
 
-            self.tile_ui(behavior, &mut drop_context, ui, root);
+        if self.read_only {
+            // A tab button may have picked up a drag this frame - cancel it.
+            if self.dragged_id(ui.ctx()).is_some() {
+                ui.ctx().stop_dragging();
+            }
         }
+        self.read_only = was_read_only;
 
-        self.preview_dragged_tile(behavior, &drop_context, ui);
-        ui.advance_cursor_after_rect(rect);
+        TreeResponse {
+            response,
+            dragged_tile_id,
+            hovered_pane,
+        }
     }
 
-    /// Sets the exact height that can be used by the tree.
-    ///
-    /// Determines the height that will be used by the tree component.
-    /// By default, the tree occupies all the available space in the parent container.
-    pub fn set_height(&mut self, height: f32) {
-        if height.is_sign_positive() && height.is_finite() {
-            self.height = height;
-        } else {
-            self.height = f32::INFINITY;
+    /// Call [`Behavior::on_visibility_changed`] for the tiles that were shown last frame but not this frame,
+    /// and vice versa.
+    fn notify_visibility_changes(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        let shown = &self.tiles.rects;
+        let previous = &mut self.shown_tiles.0;
+
+        // Skip the common case of nothing having changed since last frame:
+        let mut num_shown = 0;
+        let unchanged = shown.keys().all(|tile_id| {
+            num_shown += 1;
+            previous.contains(&tile_id)
+        });
+        if unchanged && num_shown == previous.len() {
+            return;
         }
-    }
 
-    /// Sets the exact width that can be used by the tree.
-    ///
-    /// Determines the width that will be used by the tree component.
-    /// By default, the tree occupies all the available space in the parent container.
-    pub fn set_width(&mut self, width: f32) {
-        if width.is_sign_positive() && width.is_finite() {
-            self.width = width;
-        } else {
-            self.width = f32::INFINITY;
+        let mut changes: Vec<(TileId, bool)> = shown
+            .keys()
+            .filter(|tile_id| !previous.contains(tile_id))
+            .map(|tile_id| (tile_id, true))
+            .collect();
+        #[allow(clippy::iter_over_hash_type)] // sorted below
+        for &tile_id in previous.iter() {
+            // Removed tiles are gone for good, so only report the ones that are still around:
+            if shown.get(tile_id).is_none() && self.tiles.get(tile_id).is_some() {
+                changes.push((tile_id, false));
+            }
         }
+        changes.sort_by_key(|&(tile_id, _)| tile_id.0);
+        for (tile_id, visible) in changes {
+            behavior.on_visibility_changed(&self.tiles, tile_id, visible);
+        }
+
+        // Reuse the allocation for next frame:
+        let previous = &mut self.shown_tiles.0;
+        previous.clear();
+        previous.extend(self.tiles.rects.keys());
     }
 
-    pub(super) fn tile_ui(
+    /// Lay out the tree in the given rect and show it, including the preview of any dragged tile.
+    ///
+    /// Returns how the drag ended, if the dragged tile was released this frame.
+    fn layout_and_show(
         &mut self,
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &mut DropContext,
-        ui: &Ui,
-        tile_id: TileId,
-    ) {
-        if !self.is_visible(tile_id) {
-            return;
+        ui: &mut Ui,
+        rect: Rect,
+    ) -> Option<DragEnd> {
+        if let Some(root) = self.root {
+            let fixed_sizes_id = self.base_id().with("fixed_sizes");
+            self.tiles.fixed_sizes = ui.data_mut(|data| {
+                std::mem::take(
+                    data.get_temp_mut_or_default::<ahash::HashMap<TileId, FixedSizes>>(
+                        fixed_sizes_id,
+                    ),
+                )
+            });
+            self.tiles.layout_root(ui.style(), behavior, rect, root);
+            let mut fixed_sizes = std::mem::take(&mut self.tiles.fixed_sizes);
+            fixed_sizes.retain(|&tile_id, _| self.tiles.rect(tile_id).is_some());
+            ui.data_mut(|data| data.insert_temp(fixed_sizes_id, fixed_sizes));
+
+            self.tile_ui(behavior, drop_context, ui, root);
+        } else if let Some(kind) = behavior.empty_tree_root_kind() {
+            drop_context.on_empty_tree(kind, rect);
         }
-        // NOTE: important that we get the rect and tile in two steps,
-        // otherwise we could loose the tile when there is no rect.
-        let Some(rect) = self.tiles.rect(tile_id) else {
-            log::debug!("Failed to find rect for tile {tile_id:?} during ui");
+
+        self.preview_dragged_tile(behavior, drop_context, ui)
+    }
+
+    /// Ask the user to confirm the closing of a tab, see [`crate::CloseResponse::Pending`].
+    fn close_confirmation_ui(&mut self, behavior: &mut dyn Behavior<Pane>, ctx: &egui::Context) {
+        let Some(tile_id) = self.tiles.pending_close else {
             return;
         };
-        let Some(mut tile) = self.tiles.remove(tile_id) else {
-            log::debug!("Failed to find tile {tile_id:?} during ui");
+        if self.tiles.get(tile_id).is_none() {
+            self.tiles.pending_close = None;
             return;
-        };
-
-        let drop_context_was_enabled = drop_context.enabled;
-        if Some(tile_id) == drop_context.dragged_tile_id {
-            // Can't drag a tile onto self or any children
-            drop_context.enabled = false;
         }
-        drop_context.on_tile(behavior, ui.style(), tile_id, rect, &tile);
-
-        // Each tile gets its own `Ui`, nested inside each other, with proper clip rectangles.
-        let enabled = ui.is_enabled();
-        let mut ui = egui::Ui::new(
-            ui.ctx().clone(),
-            ui.id().with(tile_id),
-            egui::UiBuilder::new()
-                .layer_id(ui.layer_id())
-                .max_rect(rect),
-        );
+
+        let title = behavior.close_confirmation_title(&self.tiles, tile_id);
+        let message = behavior.close_confirmation_message(&self.tiles, tile_id);
+        let mut confirmed = None;
+        let modal = egui::Modal::new(self.base_id().with("close_confirmation")).show(ctx, |ui| {
+            ui.label(title.heading());
+            ui.label(message);
+            ui.add_space(8.0);
+            ui.horizontal(|ui| {
+                if ui.button("Close").clicked() {
+                    confirmed = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    confirmed = Some(false);
+                }
+            });
+        });
+        if modal.should_close() {
+            // Escape or a click outside the dialog:
+            confirmed.get_or_insert(false);
+        }
+
+        if let Some(confirmed) = confirmed {
+            self.tiles.pending_close = None;
+            behavior.on_close_confirmation(&mut self.tiles, tile_id, confirmed);
+            if confirmed {
+                self.tiles.close_tab(behavior, tile_id);
+            }
+        }
+    }
+
+    /// Call [`Behavior::on_drag_started`], and [`Behavior::on_drag_ended`] for drags that
+    /// stopped without the tile being released over the tree (e.g. the drag was cancelled).
+    fn notify_drag_changes(
+        &self,
+        behavior: &mut dyn Behavior<Pane>,
+        ctx: &egui::Context,
+        dragged_tile_id: Option<TileId>,
+    ) {
+        let lifecycle_id = self.drag_lifecycle_id();
+        let previous = ctx.data(|data| data.get_temp::<TileId>(lifecycle_id));
+        if previous == dragged_tile_id {
+            return;
+        }
+
+        if let Some(previous) = previous {
+            behavior.on_drag_ended(&self.tiles, previous, DragEnd::Cancelled);
+        }
+        if let Some(dragged_tile_id) = dragged_tile_id {
+            let origin = self.drag_origin(ctx).unwrap_or_default();
+            behavior.on_drag_started(&self.tiles, dragged_tile_id, origin);
+        }
+        ctx.data_mut(|data| match dragged_tile_id {
+            Some(dragged_tile_id) => data.insert_temp(lifecycle_id, dragged_tile_id),
+            None => data.remove::<TileId>(lifecycle_id),
+        });
+    }
+
+    /// Where we remember which tile [`Behavior::on_drag_started`] was called for.
+    fn drag_lifecycle_id(&self) -> egui::Id {
+        self.base_id().with("drag_lifecycle")
+    }
+
+    /// Show the tree in the given [`Ui`], without allowing the user to edit the layout.
+    ///
+    /// Resize handles, drag-and-drop and tab close buttons are all disabled,
+    /// but clicking a tab still switches to it.
+    /// This is useful for viewer or presentation modes.
+    ///
+    /// To freeze the tabs as well, disable the whole [`Ui`] instead.
+    pub fn ui_readonly(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &mut Ui) {
+        self.read_only = true;
+        self.ui(behavior, ui);
+        self.read_only = false;
+    }
+
+    /// The pane the user last clicked in, if it is still in the tree.
+    ///
+    /// This is the pane that the [`TreeKeymap`] shortcuts act on.
+    pub fn focused_pane(&self, ctx: &egui::Context) -> Option<TileId> {
+        let tile_id = ctx.data(|data| data.get_temp::<TileId>(self.focused_pane_id()))?;
+        self.tiles.get_pane(&tile_id).is_some().then_some(tile_id)
+    }
+
+    /// Set the pane that the [`TreeKeymap`] shortcuts act on.
+    pub fn set_focused_pane(&self, ctx: &egui::Context, tile_id: TileId) {
+        ctx.data_mut(|data| data.insert_temp(self.focused_pane_id(), tile_id));
+    }
+
+    fn focused_pane_id(&self) -> egui::Id {
+        self.base_id().with("focused_pane")
+    }
+
+    /// Make the tab of this tile flash or show a badge until the tile is shown.
+    ///
+    /// If the tile is hidden inside an inactive tab, the tabs of its ancestors get the indicator too,
+    /// so the user can find it.
+    /// The attention is cleared once the tile is visible, so requesting attention
+    /// for a tile that is already visible has no effect.
+    pub fn request_attention(&mut self, tile_id: TileId, level: AttentionLevel) {
+        self.attention.insert(tile_id, level);
+    }
+
+    /// Stop asking for attention for this tile, see [`Self::request_attention`].
+    pub fn clear_attention(&mut self, tile_id: TileId) {
+        self.attention.remove(&tile_id);
+    }
+
+    /// The attention requested for this tile or any of its descendants, if any.
+    ///
+    /// This is what the tab of the tile shows, and is updated by [`Self::ui`].
+    pub fn attention(&self, tile_id: TileId) -> Option<AttentionLevel> {
+        self.subtree_attention.get(&tile_id).copied()
+    }
+
+    /// Clear the attention of visible and removed tiles, and bubble up the rest to the ancestors.
+    fn update_attention(&mut self) {
+        self.subtree_attention.clear();
+        if self.attention.is_empty() {
+            return;
+        }
+
+        for tile_id in self.active_tiles() {
+            self.attention.remove(&tile_id);
+        }
+        self.attention
+            .retain(|tile_id, _| self.tiles.get(*tile_id).is_some());
+
+        fn collect(
+            tiles: &Tiles<impl Sized>,
+            attention: &ahash::HashMap<TileId, AttentionLevel>,
+            subtree_attention: &mut ahash::HashMap<TileId, AttentionLevel>,
+            tile_id: TileId,
+        ) -> Option<AttentionLevel> {
+            let mut level = attention.get(&tile_id).copied();
+            if let Some(Tile::Container(container)) = tiles.get(tile_id) {
+                for &child_id in container.children() {
+                    level = level.max(collect(tiles, attention, subtree_attention, child_id));
+                }
+            }
+            if let Some(level) = level {
+                subtree_attention.insert(tile_id, level);
+            }
+            level
+        }
+
+        if let Some(root) = self.root {
+            collect(
+                &self.tiles,
+                &self.attention,
+                &mut self.subtree_attention,
+                root,
+            );
+        }
+    }
+
+    /// Visible panes, in tree order.
+    fn visible_panes(&self) -> Vec<TileId> {
+        self.active_tiles()
+            .into_iter()
+            .filter(|tile_id| self.tiles.get_pane(tile_id).is_some())
+            .collect()
+    }
+
+    fn update_focused_pane(&self, ui: &Ui) {
+        let Some(press_pos) = ui.input(|i| {
+            if i.pointer.any_pressed() {
+                i.pointer.press_origin()
+            } else {
+                None
+            }
+        }) else {
+            return;
+        };
+        let covered = crate::is_covered_by_other_layer(ui, press_pos);
+        let pressed_tree = !covered
+            && self
+                .root_rect()
+                .is_some_and(|rect| rect.contains(press_pos));
+        ui.data_mut(|data| data.insert_temp(self.base_id().with("has_focus"), pressed_tree));
+        if covered {
+            return;
+        }
+
+        let pressed_pane = self.visible_panes().into_iter().find(|&tile_id| {
+            self.tiles
+                .rect(tile_id)
+                .is_some_and(|rect| rect.contains(press_pos))
+        });
+        if let Some(tile_id) = pressed_pane {
+            self.set_focused_pane(ui.ctx(), tile_id);
+        }
+    }
+
+    /// The rect of the root tile last frame.
+    fn root_rect(&self) -> Option<Rect> {
+        self.tiles.rect(self.root?)
+    }
+
+    /// Should the tree handle keyboard shortcuts?
+    ///
+    /// Only if it is hovered, or was the last thing clicked,
+    /// and no widget (e.g. a text edit) wants the keyboard.
+    fn has_keyboard_focus(&self, ui: &Ui) -> bool {
+        if ui.ctx().wants_keyboard_input() {
+            return false;
+        }
+        let hovered = ui.input(|i| i.pointer.hover_pos()).is_some_and(|pos| {
+            !crate::is_covered_by_other_layer(ui, pos)
+                && self.root_rect().is_some_and(|rect| rect.contains(pos))
+        });
+        hovered || ui.data(|data| data.get_temp(self.base_id().with("has_focus"))) == Some(true)
+    }
+
+    fn handle_keybindings(&mut self, behavior: &mut dyn Behavior<Pane>, ui: &Ui) {
+        let keymap = behavior.keybindings();
+        if keymap == TreeKeymap::default() || !self.has_keyboard_focus(ui) {
+            return;
+        }
+
+        let ctx = ui.ctx().clone();
+        let consume = |shortcut: Option<&egui::KeyboardShortcut>| {
+            shortcut.is_some_and(|shortcut| ctx.input_mut(|i| i.consume_shortcut(shortcut)))
+        };
+
+        if consume(keymap.focus_next_pane.as_ref()) {
+            let panes = self.visible_panes();
+            let focused_index = self
+                .focused_pane(&ctx)
+                .and_then(|focused| panes.iter().position(|&pane| pane == focused));
+            let next = match focused_index {
+                Some(index) => panes.get((index + 1) % panes.len()),
+                None => panes.first(),
+            };
+            if let Some(&next) = next {
+                self.set_focused_pane(&ctx, next);
+            }
+            return;
+        }
+
+        // Find the closest tab container above the focused pane:
+        let mut tabs_id = self.focused_pane(&ctx);
+        while let Some(tile_id) = tabs_id {
+            if self.tiles.get(tile_id).and_then(|tile| tile.kind()) == Some(ContainerKind::Tabs) {
+                break;
+            }
+            tabs_id = self.tiles.parent_of(tile_id);
+        }
+        let Some(tabs_id) = tabs_id else {
+            return;
+        };
+        let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get(tabs_id) else {
+            return;
+        };
+        let visible_tabs: Vec<TileId> = tabs
+            .children
+            .iter()
+            .copied()
+            .filter(|&child| self.tiles.is_visible(child))
+            .collect();
+        if visible_tabs.is_empty() {
+            return;
+        }
+        let active_index = tabs
+            .active
+            .and_then(|active| visible_tabs.iter().position(|&child| child == active));
+
+        // NOTE: consume the shortcuts with more modifiers first.
+        let mut new_active = None;
+        if consume(keymap.previous_tab.as_ref()) {
+            let index = active_index.unwrap_or_default();
+            new_active = visible_tabs.get((index + visible_tabs.len() - 1) % visible_tabs.len());
+        } else if consume(keymap.next_tab.as_ref()) {
+            let index = active_index.map_or(0, |index| index + 1);
+            new_active = visible_tabs.get(index % visible_tabs.len());
+        } else {
+            for (index, shortcut) in keymap.activate_tab.iter().enumerate() {
+                if consume(Some(shortcut)) {
+                    new_active = visible_tabs.get(index);
+                }
+            }
+        }
+        if let Some(&new_active) = new_active {
+            if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(tabs_id) {
+                tabs.set_active(new_active);
+                behavior.on_edit(EditAction::TabSelected);
+            }
+            return;
+        }
+
+        if self.read_only {
+            return;
+        }
+        let Some(active) = active_index.map(|index| visible_tabs[index]) else {
+            return;
+        };
+
+        if consume(keymap.close_tab.as_ref()) {
+            if behavior.is_tab_closable(&self.tiles, active) {
+                self.tiles.request_tab_close(behavior, active);
+            }
+        } else if 2 <= visible_tabs.len() {
+            let insertion = if consume(keymap.split_down.as_ref()) {
+                Some(ContainerInsertion::Vertical(1))
+            } else if consume(keymap.split_right.as_ref()) {
+                Some(ContainerInsertion::Horizontal(1))
+            } else {
+                None
+            };
+            if let Some(insertion) = insertion {
+                self.move_tile(active, InsertionPoint::new(tabs_id, insertion), false);
+                behavior.on_edit(EditAction::TileMoved);
+            }
+        }
+    }
+
+    /// Sets the exact height that can be used by the tree.
+    ///
+    /// Determines the height that will be used by the tree component.
+    /// By default, the tree occupies all the available space in the parent container.
+    pub fn set_height(&mut self, height: f32) {
+        if height.is_sign_positive() && height.is_finite() {
+            self.height = height;
+        } else {
+            self.height = f32::INFINITY;
+        }
+    }
+
+    /// Sets the exact width that can be used by the tree.
+    ///
+    /// Determines the width that will be used by the tree component.
+    /// By default, the tree occupies all the available space in the parent container.
+    pub fn set_width(&mut self, width: f32) {
+        if width.is_sign_positive() && width.is_finite() {
+            self.width = width;
+        } else {
+            self.width = f32::INFINITY;
+        }
+    }
+
+    pub(super) fn tile_ui(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        drop_context: &mut DropContext,
+        ui: &Ui,
+        tile_id: TileId,
+    ) {
+        // Tiles that were just hidden are still shown while they animate out:
+        if self.tiles.visibility_factor(tile_id) <= 0.0 {
+            return;
+        }
+        // NOTE: important that we get the rect and tile in two steps,
+        // otherwise we could loose the tile when there is no rect.
+        let Some(rect) = self.tiles.rect(tile_id) else {
+            log::debug!("Failed to find rect for tile {tile_id:?} during ui");
+            return;
+        };
+        let Some(mut tile) = self.tiles.remove(tile_id) else {
+            log::debug!("Failed to find tile {tile_id:?} during ui");
+            return;
+        };
+        if tile.is_placeholder() {
+            if let Some(pane) = behavior.instantiate_pane(tile_id) {
+                tile = Tile::Pane(pane);
+                #[cfg(feature = "serde")]
+                if let (Tile::Pane(pane), Some(state)) =
+                    (&mut tile, self.pane_states.remove(&tile_id))
+                {
+                    behavior.restore_pane_state(pane, state);
+                }
+            }
+        }
+
+        let drop_context_was_enabled = drop_context.enabled;
+        if Some(tile_id) == drop_context.dragged_tile_id {
+            // Can't drag a tile onto self or any children
+            drop_context.enabled = false;
+        }
+        drop_context.on_tile(behavior, ui.style(), tile_id, rect, &tile);
+
+        // Each tile gets its own `Ui`, nested inside each other, with proper clip rectangles.
+        let enabled = ui.is_enabled() && self.is_visible(tile_id);
+        let tag = TileUiTag {
+            tree_id: self.id,
+            tile_id,
+        };
+        let mut ui_stack_info = egui::UiStackInfo::default().with_tag_value(TileUiTag::KEY, tag);
+        if let Some(pane_drop) = self.pane_drop(behavior, drop_context, &tile, tile_id, rect, ui) {
+            drop_context.pane_target = Some(tile_id);
+            ui_stack_info = ui_stack_info.with_tag_value(PaneDrop::KEY, pane_drop);
+        }
+        let mut ui_builder = egui::UiBuilder::new()
+            .layer_id(ui.layer_id())
+            .max_rect(rect)
+            .ui_stack_info(ui_stack_info);
+        if matches!(tile, Tile::Pane(_)) {
+            if let Some(style) = behavior.style_for_pane(tile_id) {
+                ui_builder = ui_builder.style(style);
+            }
+            behavior.configure_pane_ui(&mut ui_builder, tile_id);
+        }
+        // Named tiles get the same widget ids across sessions:
+        let ui_id = if let Some(name) = self.tiles.name(tile_id) {
+            ui.id().with(("tile_name", name))
+        } else {
+            ui.id().with(tile_id)
+        };
+        let parent_clip_rect = ui.clip_rect();
+        let mut ui = egui::Ui::new(ui.ctx().clone(), ui_id, ui_builder);
+
+        // Stay within the parent, e.g. when the tree is in a `ScrollArea`,
+        // and out of the frozen rows and columns of a grid:
+        let mut clip_rect = ui.clip_rect().intersect(parent_clip_rect);
+        if let Some(&extra_clip_rect) = self.tiles.clip_rects.get(&tile_id) {
+            clip_rect = clip_rect.intersect(extra_clip_rect);
+        }
+        ui.set_clip_rect(clip_rect);
+
+        if behavior.fade_in_appearing_tiles() {
+            if let Some(&factor) = self.tiles.visibility_factors.get(&tile_id) {
+                ui.multiply_opacity(factor);
+            }
+        }
 
         ui.add_enabled_ui(enabled, |ui| {
             match &mut tile {
                 Tile::Pane(pane) => {
-                    if behavior.pane_ui(ui, tile_id, pane) == UiResponse::DragStarted {
-                        ui.ctx().set_dragged_id(tile_id.egui_id(self.id));
+                    if behavior.pane_ui(ui, tile_id, pane) == UiResponse::DragStarted
+                        && !self.read_only
+                    {
+                        self.start_drag_from(ui.ctx(), tile_id, DragOrigin::PaneBody);
                     }
                 }
                 Tile::Container(container) => {
+                    let shares_before = match &*container {
+                        Container::Linear(linear) if self.share_group(tile_id).is_some() => {
+                            Some(linear.shares.clone())
+                        }
+                        _ => None,
+                    };
+                    let resize_before = if self.is_recording() {
+                        LayoutEvent::resized(tile_id, container)
+                    } else {
+                        None
+                    };
                     container.ui(self, behavior, drop_context, ui, rect, tile_id);
+                    if let (Some(shares_before), Container::Linear(linear)) =
+                        (shares_before, &*container)
+                    {
+                        if linear.shares != shares_before {
+                            self.sync_linked_shares(tile_id, linear);
+                        }
+                    }
+                    if let Some(resize_before) = resize_before {
+                        if let Some(resize_after) = LayoutEvent::resized(tile_id, container) {
+                            if resize_after != resize_before {
+                                self.record(resize_after);
+                            }
+                        }
+                    }
                 }
+                Tile::Placeholder => behavior.missing_pane_ui(ui, tile_id),
             };
 
             behavior.paint_on_top_of_tile(ui.painter(), ui.style(), tile_id, rect);
@@ -406,6 +1558,31 @@ impl<Pane> Tree<Pane> {
         });
     }
 
+    /// Is a tile being dragged over this pane, and does the pane accept it?
+    fn pane_drop(
+        &self,
+        behavior: &dyn Behavior<Pane>,
+        drop_context: &DropContext,
+        tile: &Tile<Pane>,
+        tile_id: TileId,
+        rect: Rect,
+        ui: &Ui,
+    ) -> Option<PaneDrop> {
+        let dragged_tile_id = drop_context.dragged_tile_id?;
+        let is_hovered = drop_context
+            .mouse_pos
+            .is_some_and(|mouse_pos| rect.contains(mouse_pos));
+        let accepts_drop = drop_context.enabled
+            && tile.is_pane()
+            && is_hovered
+            && behavior.pane_accepts_drop(&self.tiles, tile_id, dragged_tile_id);
+
+        accepts_drop.then(|| PaneDrop {
+            dragged_tile_id,
+            released: ui.input(|i| i.pointer.any_released()),
+        })
+    }
+
     /// Recursively "activate" the ancestors of the tiles that matches the given predicate.
     ///
     /// This means making the matching tiles and its ancestors the active tab in any tab layout.
@@ -427,44 +1604,100 @@ impl<Pane> Tree<Pane> {
         behavior: &mut dyn Behavior<Pane>,
         drop_context: &DropContext,
         ui: &mut Ui,
-    ) {
-        let (Some(mouse_pos), Some(dragged_tile_id)) =
-            (drop_context.mouse_pos, drop_context.dragged_tile_id)
-        else {
-            return;
+    ) -> Option<DragEnd> {
+        // Also follow the pointer where it is covered by other layers, where nothing can be dropped:
+        let (Some(mouse_pos), Some(dragged_tile_id)) = (
+            ui.input(|i| i.pointer.interact_pos()),
+            drop_context.dragged_tile_id,
+        ) else {
+            return None;
         };
 
-        ui.output_mut(|o| o.cursor_icon = egui::CursorIcon::Grabbing);
+        // Holding down Ctrl (Cmd on Mac) or Alt copies the dragged pane instead of moving it:
+        let copy_drop = ui.input(|i| i.modifiers.command || i.modifiers.alt)
+            && self.tiles.get_pane(&dragged_tile_id).is_some()
+            && behavior.can_clone_pane(&self.tiles, dragged_tile_id);
+
+        ui.output_mut(|o| {
+            o.cursor_icon = if copy_drop {
+                behavior.copy_drag_cursor()
+            } else {
+                behavior.drag_cursor()
+            };
+        });
 
-        // Preview what is being dragged:
+        // Preview what is being dragged, keeping the point where a tab was grabbed under the pointer:
+        let grab_offset = self.grab_offset(ui.ctx());
+        let (pivot, preview_pos) = match grab_offset {
+            Some(GrabOffset(offset)) => (egui::Align2::LEFT_TOP, mouse_pos - offset),
+            None => (egui::Align2::CENTER_CENTER, mouse_pos),
+        };
         egui::Area::new(ui.id().with((dragged_tile_id, "preview")))
-            .pivot(egui::Align2::CENTER_CENTER)
-            .current_pos(mouse_pos)
+            .pivot(pivot)
+            .current_pos(preview_pos)
             .interactable(false)
             .show(ui.ctx(), |ui| {
-                behavior.drag_ui(&self.tiles, ui, dragged_tile_id);
+                let origin = self.drag_origin(ui.ctx()).unwrap_or_default();
+                let mut stack_info =
+                    egui::UiStackInfo::default().with_tag_value(DragOrigin::KEY, origin);
+                if let Some(grab_offset) = grab_offset {
+                    stack_info = stack_info.with_tag_value(GrabOffset::KEY, grab_offset);
+                }
+                let ui_builder = egui::UiBuilder::new().ui_stack_info(stack_info);
+                ui.scope_builder(ui_builder, |ui| {
+                    behavior.drag_ui(&self.tiles, ui, dragged_tile_id);
+                });
             });
 
+        if let Some(pane_target) = drop_context.pane_target {
+            // The pane handles the drop itself:
+            if let Some(pane_rect) = self.tiles.rect(pane_target) {
+                behavior.paint_drag_preview(ui.visuals(), ui.painter(), None, pane_rect);
+            }
+            if ui.input(|i| i.pointer.any_released()) {
+                clear_smooth_preview_rect(ui.ctx(), self.base_id(), dragged_tile_id);
+                return Some(DragEnd::DroppedOnPane(pane_target));
+            }
+            return None;
+        }
+
         if let Some(preview_rect) = drop_context.preview_rect {
-            let preview_rect = smooth_preview_rect(ui.ctx(), dragged_tile_id, preview_rect);
+            let preview_rect =
+                smooth_preview_rect(ui.ctx(), self.base_id(), dragged_tile_id, preview_rect);
 
             let parent_rect = drop_context
                 .best_insertion
                 .and_then(|insertion_point| self.tiles.rect(insertion_point.parent_id));
 
-            behavior.paint_drag_preview(ui.visuals(), ui.painter(), parent_rect, preview_rect);
+            // A ghost tab button already shows where the tile will end up:
+            let is_ghost_tab = drop_context.ghost_tab.is_some()
+                && drop_context.ghost_tab == drop_context.best_insertion;
+
+            if !is_ghost_tab {
+                behavior.paint_drag_preview(ui.visuals(), ui.painter(), parent_rect, preview_rect);
+            }
+            if copy_drop {
+                behavior.paint_copy_drop_indicator(ui.visuals(), ui.painter(), preview_rect);
+            }
 
-            if behavior.preview_dragged_panes() {
+            if let Some(grid_preview) =
+                self.grid_drop_preview(dragged_tile_id, drop_context.best_insertion, copy_drop)
+            {
+                behavior.paint_grid_drop_preview(ui.visuals(), ui.painter(), &grid_preview);
+            }
+
+            if behavior.preview_dragged_panes() && !is_ghost_tab {
                 // TODO(emilk): add support for previewing containers too.
                 if preview_rect.width() > 32.0 && preview_rect.height() > 32.0 {
                     if let Some(Tile::Pane(pane)) = self.tiles.get_mut(dragged_tile_id) {
+                        let mut ui_builder = egui::UiBuilder::new().max_rect(preview_rect);
+                        if let Some(style) = behavior.style_for_pane(dragged_tile_id) {
+                            ui_builder = ui_builder.style(style);
+                        }
                         // Intentionally ignore the response, since the user cannot possibly
                         // begin a drag on the preview pane.
-                        let _ignored: UiResponse = behavior.pane_ui(
-                            &mut ui.new_child(egui::UiBuilder::new().max_rect(preview_rect)),
-                            dragged_tile_id,
-                            pane,
-                        );
+                        let _ignored: UiResponse =
+                            behavior.pane_ui(&mut ui.new_child(ui_builder), dragged_tile_id, pane);
                     }
                 }
             }
@@ -472,16 +1705,159 @@ impl<Pane> Tree<Pane> {
 
         if ui.input(|i| i.pointer.any_released()) {
             if let Some(insertion_point) = drop_context.best_insertion {
-                behavior.on_edit(EditAction::TileDropped);
-                self.move_tile(dragged_tile_id, insertion_point, false);
+                self.drop_tile(behavior, dragged_tile_id, insertion_point, copy_drop);
+            } else if let Some(kind) = drop_context.empty_tree_root {
+                let root = self.create_empty_root(kind);
+                let insertion_point = InsertionPoint::new(root, ContainerInsertion::new(kind, 0));
+                self.drop_tile(behavior, dragged_tile_id, insertion_point, copy_drop);
             }
-            clear_smooth_preview_rect(ui.ctx(), dragged_tile_id);
+            clear_smooth_preview_rect(ui.ctx(), self.base_id(), dragged_tile_id);
+
+            return Some(
+                if drop_context.best_insertion.is_some() || drop_context.empty_tree_root.is_some() {
+                    DragEnd::Dropped
+                } else {
+                    self.drop_outside(behavior, dragged_tile_id, mouse_pos)
+                },
+            );
         }
+
+        None
+    }
+
+    /// Create an empty root container, for a tile to be dropped into an empty tree.
+    pub(crate) fn create_empty_root(&mut self, kind: ContainerKind) -> TileId {
+        self.record(LayoutEvent::RootCreated { kind });
+        let root = self.tiles.insert_container(Container::new(kind, vec![]));
+        self.root = Some(root);
+        root
+    }
+
+    /// Drop the dragged tile at the given insertion point, moving it there (or a copy of it).
+    pub(crate) fn drop_tile(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        dragged_tile_id: TileId,
+        insertion_point: InsertionPoint,
+        copy_drop: bool,
+    ) {
+        self.record(LayoutEvent::Dropped {
+            tile_id: dragged_tile_id,
+            parent_id: insertion_point.parent_id,
+            kind: insertion_point.insertion.kind(),
+            index: insertion_point.insertion.index(),
+            copy: copy_drop,
+        });
+
+        behavior.on_edit(EditAction::TileDropped);
+        let copy = if copy_drop {
+            self.tiles
+                .get_pane(&dragged_tile_id)
+                .and_then(|pane| behavior.clone_pane(pane))
+        } else {
+            None
+        };
+        let (tile_id, wrapped_tile) = if let Some(copy) = copy {
+            let copy_id = self.tiles.insert_pane(copy);
+            (copy_id, self.tiles.insert_at(insertion_point, copy_id))
+        } else {
+            (
+                dragged_tile_id,
+                self.move_tile(dragged_tile_id, insertion_point, false),
+            )
+        };
+        let drop_result = DropResult {
+            tile_id,
+            parent_id: insertion_point.parent_id,
+            wrapped_tile,
+        };
+        behavior.on_tile_dropped(&self.tiles, &drop_result);
+    }
+
+    /// The dragged tile was released where it can't be dropped.
+    ///
+    /// If that is outside the tree, ask [`Behavior::on_drop_outside`] what to do with it.
+    fn drop_outside(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        dragged_tile_id: TileId,
+        pointer_pos: egui::Pos2,
+    ) -> DragEnd {
+        let root_rect = self.root.and_then(|root| self.tiles.rect(root));
+        if root_rect.is_some_and(|rect| rect.contains(pointer_pos)) {
+            return DragEnd::Cancelled;
+        }
+
+        let action = behavior.on_drop_outside(&self.tiles, dragged_tile_id, pointer_pos);
+        if action == DropOutsideAction::Ignore {
+            return DragEnd::Cancelled;
+        }
+        self.apply_drop_outside(behavior, dragged_tile_id, action);
+        DragEnd::DroppedOutside(action)
+    }
+
+    pub(crate) fn apply_drop_outside(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        dragged_tile_id: TileId,
+        action: DropOutsideAction,
+    ) {
+        self.record(LayoutEvent::DroppedOutside {
+            tile_id: dragged_tile_id,
+            action,
+        });
+        match action {
+            DropOutsideAction::Ignore => return,
+            DropOutsideAction::Remove => {
+                self.remove_recursively(dragged_tile_id);
+            }
+            DropOutsideAction::Detach => {
+                self.remove_tile_id_from_parent(dragged_tile_id);
+            }
+        }
+        behavior.on_edit(EditAction::TileDropped);
+    }
+
+    /// If the dragged tile is about to be dropped into a grid, what will happen?
+    fn grid_drop_preview(
+        &self,
+        dragged_tile_id: TileId,
+        insertion_point: Option<InsertionPoint>,
+        copy_drop: bool,
+    ) -> Option<GridDropPreview> {
+        let InsertionPoint {
+            parent_id: grid_id,
+            insertion: ContainerInsertion::Grid(index),
+        } = insertion_point?
+        else {
+            return None;
+        };
+        let Some(Container::Grid(grid)) = self.tiles.get_container(grid_id) else {
+            return None;
+        };
+        let (cell_rect, occupant) = grid.drop_cell(index)?;
+
+        let drop = match occupant {
+            None => GridDrop::Empty,
+            Some(occupant) if occupant == dragged_tile_id => GridDrop::Empty,
+            Some(_) if !copy_drop && grid.children().any(|&child| child == dragged_tile_id) => {
+                GridDrop::Swap
+            }
+            Some(_) => GridDrop::Insert,
+        };
+
+        Some(GridDropPreview {
+            grid_id,
+            cell_rect,
+            occupant,
+            occupant_rect: occupant.and_then(|occupant| self.tiles.rect(occupant)),
+            drop,
+        })
     }
 
     /// Simplify and normalize the tree using the given options.
     ///
-    /// This is also called at the start of [`Self::ui`].
+    /// This is also called at the start of [`Self::ui`], unless [`Behavior::auto_simplify`] is `false`.
     pub fn simplify(&mut self, options: &SimplificationOptions) {
         if let Some(root) = self.root {
             match self.tiles.simplify(options, root, None) {
@@ -502,20 +1878,132 @@ impl<Pane> Tree<Pane> {
         }
     }
 
-    /// Simplify all of the children of the given container tile recursively.
-    pub fn simplify_children_of_tile(&mut self, tile_id: TileId, options: &SimplificationOptions) {
-        if let Some(Tile::Container(mut container)) = self.tiles.remove(tile_id) {
-            let kind = container.kind();
-            container.simplify_children(|child| self.tiles.simplify(options, child, Some(kind)));
-            self.tiles.insert(tile_id, Tile::Container(container));
+    /// Simplify all of the children of the given container tile recursively.
+    pub fn simplify_children_of_tile(&mut self, tile_id: TileId, options: &SimplificationOptions) {
+        if let Some(Tile::Container(mut container)) = self.tiles.remove(tile_id) {
+            let kind = container.kind();
+            container.simplify_children(|child| self.tiles.simplify(options, child, Some(kind)));
+            self.tiles.insert(tile_id, Tile::Container(container));
+        }
+    }
+
+    /// Garbage-collect tiles that are no longer reachable from the root tile.
+    ///
+    /// This is also called by [`Self::ui`], so usually you don't need to call this yourself.
+    pub fn gc(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        self.follow_rehomed_tiles(behavior);
+        let placeholders = self.tiles.gc_root(behavior, self.root);
+        #[cfg(feature = "serde")]
+        for (tile_id, pane) in placeholders {
+            self.save_placeholder_state(behavior, tile_id, &pane);
+        }
+        #[cfg(not(feature = "serde"))]
+        drop(placeholders);
+
+        if !self.share_groups.is_empty() {
+            for group in &mut self.share_groups {
+                group.retain(|tile_id| self.tiles.get(*tile_id).is_some());
+            }
+            self.share_groups.retain(|group| 2 <= group.len());
+        }
+    }
+
+    /// Move the state kept for tiles that got a new id, see [`Behavior::on_tile_id_changed`].
+    fn follow_rehomed_tiles(&mut self, behavior: &mut dyn Behavior<Pane>) {
+        for (old, new) in std::mem::take(&mut self.tiles.rehomed) {
+            if let Some(level) = self.attention.remove(&old) {
+                self.attention.insert(new, level);
+            }
+            #[cfg(feature = "serde")]
+            if let Some(state) = self.pane_states.remove(&old) {
+                self.pane_states.insert(new, state);
+            }
+            behavior.on_tile_id_changed(old, new);
+        }
+    }
+
+    /// Keep the shares of these [`crate::Linear`] containers in sync,
+    /// so that resizing one of them resizes all of them the same way.
+    ///
+    /// Useful for e.g. two stacked horizontal splits whose columns must stay aligned.
+    /// Shares are matched by child index, so only containers with
+    /// the same number of children are synced.
+    ///
+    /// Any groups the containers were already in are merged into one.
+    /// All containers immediately get the shares of the first one.
+    pub fn link_shares(&mut self, containers: impl IntoIterator<Item = TileId>) {
+        let mut group: Vec<TileId> = vec![];
+        for tile_id in containers {
+            if let Some(index) = self
+                .share_groups
+                .iter()
+                .position(|group| group.contains(&tile_id))
+            {
+                for member in self.share_groups.swap_remove(index) {
+                    if !group.contains(&member) {
+                        group.push(member);
+                    }
+                }
+            }
+            if !group.contains(&tile_id) {
+                group.push(tile_id);
+            }
+        }
+        if group.len() < 2 {
+            return;
+        }
+
+        let first = group[0];
+        self.share_groups.push(group);
+        if let Some(Tile::Container(Container::Linear(linear))) = self.tiles.get(first) {
+            let linear = linear.clone();
+            self.sync_linked_shares(first, &linear);
+        }
+    }
+
+    /// Stop syncing the shares of this container with others, see [`Self::link_shares`].
+    pub fn unlink_shares(&mut self, tile_id: TileId) {
+        for group in &mut self.share_groups {
+            group.retain(|&member| member != tile_id);
         }
+        self.share_groups.retain(|group| 2 <= group.len());
     }
 
-    /// Garbage-collect tiles that are no longer reachable from the root tile.
-    ///
-    /// This is also called by [`Self::ui`], so usually you don't need to call this yourself.
-    pub fn gc(&mut self, behavior: &mut dyn Behavior<Pane>) {
-        self.tiles.gc_root(behavior, self.root);
+    /// All the containers whose shares are synced with this one, including itself.
+    pub fn share_group(&self, tile_id: TileId) -> Option<&[TileId]> {
+        self.share_groups
+            .iter()
+            .find(|group| group.contains(&tile_id))
+            .map(|group| group.as_slice())
+    }
+
+    /// Copy the shares of `source` to the other containers in its share group.
+    fn sync_linked_shares(&mut self, source_id: TileId, source: &crate::Linear) {
+        let Some(group) = self
+            .share_groups
+            .iter()
+            .find(|group| group.contains(&source_id))
+        else {
+            return;
+        };
+        for &other_id in group {
+            if other_id == source_id {
+                continue;
+            }
+            let Some(Tile::Container(Container::Linear(other))) = self.tiles.get_mut(other_id)
+            else {
+                continue;
+            };
+            if other.children.len() != source.children.len() {
+                log::debug!(
+                    "Not syncing the shares of {source_id:?} to {other_id:?}: different number of children"
+                );
+                continue;
+            }
+            for (&from, &to) in source.children.iter().zip(&other.children) {
+                other.shares.set_share(to, source.shares[from]);
+            }
+        }
     }
 
     /// Move a tile to a new container, at the specified insertion index.
@@ -566,15 +2054,371 @@ impl<Pane> Tree<Pane> {
         }
     }
 
+    /// Move a tile into the given container, or put it in tabs next to `target` if that is a pane.
+    ///
+    /// The tabs take over the id of `target`, which keeps its place in the tree.
+    pub(crate) fn insert_into_tile(&mut self, tile_id: TileId, target: TileId, index: usize) {
+        if self.tiles.get_container(target).is_some() {
+            self.move_tile_to_container(tile_id, target, index, false);
+        } else {
+            let insertion = InsertionPoint::new(target, ContainerInsertion::Tabs(index));
+            self.tiles.insert_at(insertion, tile_id);
+        }
+    }
+
+    /// Open a pane as a new tab in the [`crate::Tabs::document_area`], and make it active.
+    ///
+    /// Without a document area the pane is added to the root (a pane root is put in tabs first),
+    /// and an empty tree gets a new document area with the pane as its root.
+    ///
+    /// Returns the id of the new pane.
+    pub fn open_pane(&mut self, pane: Pane) -> TileId {
+        let tile_id = self.tiles.insert_pane(pane);
+        if let Some(document_area) = self.tiles.document_area() {
+            self.move_tile_to_container(tile_id, document_area, usize::MAX, false);
+        } else if let Some(root) = self.root {
+            log::debug!("No document area in the tree, so opening {tile_id:?} in the root");
+            self.insert_into_tile(tile_id, root, usize::MAX);
+        } else {
+            self.root = Some(
+                self.tiles
+                    .insert_container(Tabs::new_document_area(vec![tile_id])),
+            );
+        }
+        self.make_active(|id, _| id == tile_id);
+        tile_id
+    }
+
+    /// Insert a new container of the given kind between a tile and its parent.
+    ///
+    /// The new container takes the place of the tile in its parent, including its share,
+    /// and the tile becomes its only child.
+    /// If the tile is the root, the new container becomes the new root.
+    ///
+    /// Returns the id of the new container.
+    /// If the tile does not exist, nothing happens and `tile_id` is returned.
+    ///
+    /// Note that [`Self::simplify`] will remove single-child containers again,
+    /// unless that is turned off in [`SimplificationOptions`]
+    /// (e.g. with [`SimplificationOptions::all_panes_must_have_tabs`] for tabs).
+    ///
+    /// Calls [`Behavior::on_edit`] with [`EditAction::ContainerWrapped`].
+    pub fn wrap_in_container(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        kind: ContainerKind,
+    ) -> TileId {
+        let container_id = self.wrap_in_container_impl(tile_id, kind);
+        if container_id != tile_id {
+            behavior.on_edit(EditAction::ContainerWrapped);
+        }
+        container_id
+    }
+
+    pub(crate) fn wrap_in_container_impl(
+        &mut self,
+        tile_id: TileId,
+        kind: ContainerKind,
+    ) -> TileId {
+        if self.tiles.get(tile_id).is_none() {
+            log::warn!("Cannot wrap unknown tile {tile_id:?}");
+            return tile_id;
+        }
+
+        let parent_id = self.tiles.parent_of(tile_id);
+        let container_id = self
+            .tiles
+            .insert_new(Tile::Container(Container::new(kind, vec![tile_id])));
+
+        if let Some(parent_id) = parent_id {
+            if let Some(Tile::Container(parent)) = self.tiles.get_mut(parent_id) {
+                parent.simplify_children(|child_id| {
+                    if child_id == tile_id {
+                        SimplifyAction::Replace(container_id)
+                    } else {
+                        SimplifyAction::Keep
+                    }
+                });
+            }
+        } else if self.is_root(tile_id) {
+            self.root = Some(container_id);
+        }
+
+        container_id
+    }
+
+    /// Give all children of the given container the same share,
+    /// and optionally those of all containers below it too.
+    ///
+    /// Pass in the root to reset the proportions of the whole tree.
+    /// Calls [`Behavior::on_edit`] with [`EditAction::TileResized`] if any share changed.
+    ///
+    /// Returns `false` if nothing was changed.
+    pub fn equalize_shares(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        recursive: bool,
+    ) -> bool {
+        let changed = self.equalize_shares_impl(tile_id, recursive);
+        if changed {
+            behavior.on_edit(EditAction::TileResized);
+        }
+        changed
+    }
+
+    fn equalize_shares_impl(&mut self, tile_id: TileId, recursive: bool) -> bool {
+        let Some(Tile::Container(container)) = self.tiles.get_mut(tile_id) else {
+            return false;
+        };
+
+        let mut changed = false;
+        match container {
+            Container::Tabs(_) => {}
+            Container::Linear(linear) => {
+                for &child in &linear.children {
+                    if linear.shares[child] != 1.0 {
+                        linear.shares.set_share(child, 1.0);
+                        changed = true;
+                    }
+                }
+            }
+            Container::Grid(grid) => {
+                for share in grid.col_shares.iter_mut().chain(&mut grid.row_shares) {
+                    if *share != 1.0 {
+                        *share = 1.0;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        if recursive {
+            for child in container.children_vec() {
+                changed |= self.equalize_shares_impl(child, recursive);
+            }
+        }
+        changed
+    }
+
+    /// Convert a container to a different kind, e.g. from tabs to a horizontal split.
+    ///
+    /// Unlike [`Container::set_kind`], this keeps the proportions of the children where possible,
+    /// e.g. the shares of a horizontal [`crate::Linear`] become the column shares of a [`crate::Grid`].
+    ///
+    /// The change must be allowed by [`Behavior::allow_kind_change`].
+    /// Calls [`Behavior::on_edit`] with [`EditAction::ContainerKindChanged`].
+    ///
+    /// Returns `false` if nothing was changed.
+    pub fn set_container_kind(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        tile_id: TileId,
+        kind: ContainerKind,
+    ) -> bool {
+        let Some(from) = self
+            .tiles
+            .get_container(tile_id)
+            .map(|container| container.kind())
+        else {
+            log::warn!("Cannot change the kind of {tile_id:?}: not a container");
+            return false;
+        };
+        if from == kind || !behavior.allow_kind_change(&self.tiles, tile_id, from, kind) {
+            return false;
+        }
+
+        let Some(Tile::Container(mut container)) = self.tiles.remove(tile_id) else {
+            return false;
+        };
+        container.convert_kind(&self.tiles, kind);
+        self.tiles.insert(tile_id, Tile::Container(container));
+
+        behavior.on_edit(EditAction::ContainerKindChanged);
+        true
+    }
+
+    /// Remove a container, putting its children in its place in its parent.
+    ///
+    /// This is the inverse of [`Self::wrap_in_container`].
+    /// If the parent is a [`crate::Linear`], the share of the container is split among the children,
+    /// in proportion to their shares within the container.
+    ///
+    /// The root can only be flattened if it has exactly one child, which then becomes the new root.
+    ///
+    /// Calls [`Behavior::on_edit`] with [`EditAction::ContainerFlattened`].
+    ///
+    /// Returns `false` if nothing was changed.
+    pub fn flatten_container(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        container_id: TileId,
+    ) -> bool {
+        let flattened = self.flatten_container_impl(container_id);
+        if flattened {
+            behavior.on_edit(EditAction::ContainerFlattened);
+        }
+        flattened
+    }
+
+    fn flatten_container_impl(&mut self, container_id: TileId) -> bool {
+        let Some(Tile::Container(container)) = self.tiles.get(container_id) else {
+            log::warn!("Cannot flatten {container_id:?}: not a container");
+            return false;
+        };
+
+        let children: Vec<TileId> = container.children().copied().collect();
+        let weights: Vec<f32> = match container {
+            Container::Linear(linear) => {
+                children.iter().map(|&child| linear.shares[child]).collect()
+            }
+            Container::Tabs(_) | Container::Grid(_) => vec![1.0; children.len()],
+        };
+        let active_child = match container {
+            Container::Tabs(tabs) => tabs.active_child(),
+            Container::Linear(_) | Container::Grid(_) => None,
+        };
+
+        let Some(parent_id) = self.tiles.parent_of(container_id) else {
+            if self.is_root(container_id) && children.len() == 1 {
+                self.root = Some(children[0]);
+                self.tiles.release(container_id);
+                return true;
+            }
+            log::debug!("Cannot flatten {container_id:?}: it has no parent");
+            return false;
+        };
+
+        let Some(Tile::Container(parent)) = self.tiles.get_mut(parent_id) else {
+            return false;
+        };
+
+        match parent {
+            Container::Tabs(tabs) => {
+                let was_pinned = tabs.is_pinned(container_id);
+                let Some(index) = tabs.children.iter().position(|&id| id == container_id) else {
+                    return false;
+                };
+                tabs.children
+                    .splice(index..=index, children.iter().copied());
+                if tabs.active == Some(container_id) {
+                    tabs.active = active_child.or_else(|| children.first().copied());
+                }
+                if was_pinned {
+                    for &child in &children {
+                        tabs.pin(child);
+                    }
+                }
+            }
+            Container::Linear(linear) => {
+                let Some(index) = linear.children.iter().position(|&id| id == container_id) else {
+                    return false;
+                };
+                linear
+                    .children
+                    .splice(index..=index, children.iter().copied());
+
+                let share = linear.shares[container_id];
+                let total_weight: f32 = weights.iter().sum();
+                for (&child, &weight) in children.iter().zip(&weights) {
+                    let fraction = if 0.0 < total_weight {
+                        weight / total_weight
+                    } else {
+                        1.0 / children.len() as f32
+                    };
+                    linear.shares.set_share(child, share * fraction);
+                }
+                linear.shares.retain(|id| id != container_id);
+
+                if linear.flexible.remove(&container_id) {
+                    linear.flexible.extend(children.iter().copied());
+                }
+            }
+            Container::Grid(grid) => {
+                let Some(index) = grid.remove_child(container_id) else {
+                    return false;
+                };
+                for (i, &child) in children.iter().enumerate() {
+                    grid.insert_at(index + i, child);
+                }
+            }
+        }
+
+        self.tiles.release(container_id);
+        true
+    }
+
+    /// Move a whole column of the given [`crate::Grid`] to a new index, see [`crate::Grid::move_col`].
+    ///
+    /// Calls [`Behavior::on_edit`] with [`EditAction::GridReordered`].
+    ///
+    /// Returns `false` if `grid_id` is not a grid, or if the indices are out of bounds.
+    pub fn move_grid_col(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        grid_id: TileId,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        let moved = self.edit_grid(grid_id, |grid, tiles| grid.move_col(tiles, from, to));
+        if moved {
+            behavior.on_edit(EditAction::GridReordered);
+        }
+        moved
+    }
+
+    /// Move a whole row of the given [`crate::Grid`] to a new index, see [`crate::Grid::move_row`].
+    ///
+    /// See [`Self::move_grid_col`].
+    pub fn move_grid_row(
+        &mut self,
+        behavior: &mut dyn Behavior<Pane>,
+        grid_id: TileId,
+        from: usize,
+        to: usize,
+    ) -> bool {
+        let moved = self.edit_grid(grid_id, |grid, tiles| grid.move_row(tiles, from, to));
+        if moved {
+            behavior.on_edit(EditAction::GridReordered);
+        }
+        moved
+    }
+
+    /// Run `edit` on the given grid, with access to the other tiles.
+    ///
+    /// Returns `false` if `grid_id` is not a grid, and otherwise what `edit` returned.
+    fn edit_grid(
+        &mut self,
+        grid_id: TileId,
+        edit: impl FnOnce(&mut Grid, &Tiles<Pane>) -> bool,
+    ) -> bool {
+        let Some(mut tile) = self.tiles.remove(grid_id) else {
+            log::warn!("Failed to find grid {grid_id:?}");
+            return false;
+        };
+        let edited = if let Tile::Container(Container::Grid(grid)) = &mut tile {
+            edit(grid, &self.tiles)
+        } else {
+            log::warn!("Tile {grid_id:?} is not a grid");
+            false
+        };
+        self.tiles.insert(grid_id, tile);
+        edited
+    }
+
     /// Move the given tile to the given insertion point.
     ///
     /// See [`Self::move_tile_to_container()`] for details on `reflow_grid`.
+    ///
+    /// If a new container had to be created, the new id of the tile that was at the insertion point is returned
+    /// (see [`Tiles::insert_at`]).
     pub(super) fn move_tile(
         &mut self,
         moved_tile_id: TileId,
         insertion_point: InsertionPoint,
         reflow_grid: bool,
-    ) {
+    ) -> Option<TileId> {
         log::trace!(
             "Moving {moved_tile_id:?} into {:?}",
             insertion_point.insertion
@@ -604,9 +2448,8 @@ impl<Pane> Tree<Pane> {
 
                         match container {
                             Container::Tabs(tabs) => {
-                                let insertion_index = adjusted_index.min(tabs.children.len());
-                                tabs.children.insert(insertion_index, moved_tile_id);
-                                tabs.active = Some(moved_tile_id);
+                                tabs.insert_child_impl(adjusted_index, moved_tile_id);
+                                tabs.set_active(moved_tile_id);
                             }
                             Container::Linear(linear) => {
                                 let insertion_index = adjusted_index.min(linear.children.len());
@@ -623,14 +2466,143 @@ impl<Pane> Tree<Pane> {
                                 };
                             }
                         }
-                        return; // done
+                        return None; // done
                     }
                 }
             }
         }
 
         // Moving to a new parent
-        self.tiles.insert_at(insertion_point, moved_tile_id);
+        self.tiles.insert_at(insertion_point, moved_tile_id)
+    }
+
+    /// Start dragging the given tile, as if the user had dragged its tab.
+    ///
+    /// Use this to start a drag from your own widgets, e.g. a list of panels outside the tree.
+    /// The drag then uses the normal preview and drop machinery of [`Self::ui`],
+    /// and ends when the mouse button is released.
+    ///
+    /// The root tile cannot be dragged.
+    pub fn start_drag(&self, ctx: &egui::Context, tile_id: TileId) {
+        self.start_drag_from(ctx, tile_id, DragOrigin::External);
+    }
+
+    pub(crate) fn start_drag_from(&self, ctx: &egui::Context, tile_id: TileId, origin: DragOrigin) {
+        if self.tiles.get(tile_id).is_none() {
+            log::warn!("Cannot drag unknown tile {tile_id:?}");
+        } else if self.is_root(tile_id) {
+            log::debug!("Cannot drag the root tile");
+        } else {
+            ctx.set_dragged_id(self.tile_egui_id(tile_id));
+            self.set_drag_origin(ctx, origin);
+        }
+    }
+
+    /// Remember what tab was active when the drag of a tab started.
+    pub(crate) fn remember_tab_drag_start(
+        &self,
+        ctx: &egui::Context,
+        tabs_id: TileId,
+        dragged_tile_id: TileId,
+        index: usize,
+        previous_active: Option<TileId>,
+    ) {
+        let start = TabDragStart {
+            tabs_id,
+            dragged_tile_id,
+            index,
+            previous_active,
+        };
+        ctx.data_mut(|data| data.insert_temp(self.base_id().with("tab_drag_start"), start));
+    }
+
+    /// Remember how a grid looked before one of its columns or rows was dragged out of it.
+    pub(crate) fn remember_grid_line_drag(
+        &self,
+        ctx: &egui::Context,
+        grid_id: TileId,
+        group_id: TileId,
+        group_slot: usize,
+        grid_children: Vec<Option<TileId>>,
+    ) {
+        let drag = GridLineDrag {
+            grid_id,
+            group_id,
+            group_slot,
+            grid_children,
+        };
+        ctx.data_mut(|data| data.insert_temp(self.base_id().with("grid_line_drag"), drag));
+    }
+
+    /// If the column or row dragged out of a grid did not move anywhere
+    /// (e.g. the drag was cancelled, or it was dropped back where it was, or onto a pane),
+    /// put its tiles back into the grid.
+    fn restore_grid_line_after_drag(&mut self, ctx: &egui::Context) {
+        let drag_id = self.base_id().with("grid_line_drag");
+        let Some(drag) = ctx.data(|data| data.get_temp::<GridLineDrag>(drag_id)) else {
+            return;
+        };
+        if self.dragged_id(ctx) == Some(drag.group_id) {
+            return; // Still dragging
+        }
+        ctx.data_mut(|data| data.remove::<GridLineDrag>(drag_id));
+
+        if let Some(Tile::Container(Container::Grid(grid))) = self.tiles.get_mut(drag.grid_id) {
+            if grid.restore_children(drag.group_slot, drag.group_id, drag.grid_children) {
+                self.tiles.release(drag.group_id);
+            }
+        }
+    }
+
+    /// If a tab drag was cancelled, or the tab was dropped back where it was,
+    /// activate the tab that was active before the drag.
+    fn restore_active_tab_after_drag(&mut self, ctx: &egui::Context) {
+        let start_id = self.base_id().with("tab_drag_start");
+        let Some(start) = ctx.data(|data| data.get_temp::<TabDragStart>(start_id)) else {
+            return;
+        };
+        if self.dragged_id(ctx).is_some() {
+            return; // Still dragging
+        }
+        ctx.data_mut(|data| data.remove::<TabDragStart>(start_id));
+
+        let Some(previous_active) = start.previous_active else {
+            return;
+        };
+        if !self.tiles.is_visible(previous_active) {
+            return;
+        }
+        if let Some(Tile::Container(Container::Tabs(tabs))) = self.tiles.get_mut(start.tabs_id) {
+            let returned = tabs.children.get(start.index) == Some(&start.dragged_tile_id);
+            if returned && tabs.children.contains(&previous_active) {
+                tabs.set_active(previous_active);
+            }
+        }
+    }
+
+    /// How the current drag was started, if a tile is being dragged.
+    pub fn drag_origin(&self, ctx: &egui::Context) -> Option<DragOrigin> {
+        self.dragged_id(ctx)?;
+        Some(
+            ctx.data(|data| data.get_temp(self.base_id().with("drag_origin")))
+                .unwrap_or_default(),
+        )
+    }
+
+    pub(crate) fn set_drag_origin(&self, ctx: &egui::Context, origin: DragOrigin) {
+        ctx.data_mut(|data| {
+            data.insert_temp(self.base_id().with("drag_origin"), origin);
+            data.remove::<GrabOffset>(self.base_id().with("grab_offset"));
+        });
+    }
+
+    /// Remember where the dragged tab was grabbed, after [`Self::set_drag_origin`].
+    pub(crate) fn set_grab_offset(&self, ctx: &egui::Context, grab_offset: GrabOffset) {
+        ctx.data_mut(|data| data.insert_temp(self.base_id().with("grab_offset"), grab_offset));
+    }
+
+    fn grab_offset(&self, ctx: &egui::Context) -> Option<GrabOffset> {
+        ctx.data(|data| data.get_temp(self.base_id().with("grab_offset")))
     }
 
     /// Find the currently dragged tile, if any.
@@ -640,7 +2612,7 @@ impl<Pane> Tree<Pane> {
                 continue; // not allowed to drag root
             }
 
-            let is_tile_being_dragged = crate::is_being_dragged(ctx, self.id, tile_id);
+            let is_tile_being_dragged = crate::is_being_dragged(ctx, self, tile_id);
             if is_tile_being_dragged {
                 // Abort drags on escape:
                 if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
@@ -696,18 +2668,23 @@ impl<Pane> Tree<Pane> {
 
 /// We store the preview rect in egui temp storage so that it is not serialized,
 /// and so that a user could re-create the [`Tree`] each frame and still get smooth previews.
-fn smooth_preview_rect_id(dragged_tile_id: TileId) -> egui::Id {
-    egui::Id::new((dragged_tile_id, "smoothed_preview_rect"))
+fn smooth_preview_rect_id(base_id: egui::Id, dragged_tile_id: TileId) -> egui::Id {
+    base_id.with((dragged_tile_id, "smoothed_preview_rect"))
 }
 
-fn clear_smooth_preview_rect(ctx: &egui::Context, dragged_tile_id: TileId) {
-    let data_id = smooth_preview_rect_id(dragged_tile_id);
+fn clear_smooth_preview_rect(ctx: &egui::Context, base_id: egui::Id, dragged_tile_id: TileId) {
+    let data_id = smooth_preview_rect_id(base_id, dragged_tile_id);
     ctx.data_mut(|data| data.remove::<Rect>(data_id));
 }
 
 /// Take the preview rectangle and smooth it over time.
-fn smooth_preview_rect(ctx: &egui::Context, dragged_tile_id: TileId, new_rect: Rect) -> Rect {
-    let data_id = smooth_preview_rect_id(dragged_tile_id);
+fn smooth_preview_rect(
+    ctx: &egui::Context,
+    base_id: egui::Id,
+    dragged_tile_id: TileId,
+    new_rect: Rect,
+) -> Rect {
+    let data_id = smooth_preview_rect_id(base_id, dragged_tile_id);
 
     let dt = ctx.input(|input| input.stable_dt).at_most(0.1);
 
@@ -735,3 +2712,209 @@ fn smooth_preview_rect(ctx: &egui::Context, dragged_tile_id: TileId, new_rect: R
 
     smoothed
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct TestBehavior {
+        edits: Vec<EditAction>,
+    }
+
+    impl Behavior<u32> for TestBehavior {
+        fn pane_ui(&mut self, _ui: &mut Ui, _tile_id: TileId, _pane: &mut u32) -> UiResponse {
+            UiResponse::None
+        }
+
+        fn tab_title_for_pane(&mut self, pane: &u32) -> egui::WidgetText {
+            pane.to_string().into()
+        }
+
+        fn on_edit(&mut self, edit_action: EditAction) {
+            self.edits.push(edit_action);
+        }
+    }
+
+    #[test]
+    fn test_flatten_into_linear() {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..3).map(|pane| tiles.insert_pane(pane)).collect();
+        let inner = tiles.insert_vertical_tile(vec![panes[1], panes[2]]);
+        let root = tiles.insert_horizontal_tile(vec![panes[0], inner]);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(inner) else {
+            panic!("Expected a linear container");
+        };
+        linear.shares.set_share(panes[1], 3.0);
+        linear.shares.set_share(panes[2], 1.0);
+        let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) else {
+            panic!("Expected a linear container");
+        };
+        linear.shares.set_share(panes[0], 1.0);
+        linear.shares.set_share(inner, 2.0);
+
+        let mut behavior = TestBehavior::default();
+        assert!(tree.flatten_container(&mut behavior, inner));
+        assert_eq!(behavior.edits, vec![EditAction::ContainerFlattened]);
+        assert!(tree.tiles.get(inner).is_none());
+
+        let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+            panic!("Expected a linear container");
+        };
+        assert_eq!(linear.children, panes);
+        assert_eq!(linear.shares[panes[0]], 1.0);
+        assert_eq!(
+            linear.shares[panes[1]], 1.5,
+            "The share is split by the inner shares"
+        );
+        assert_eq!(linear.shares[panes[2]], 0.5);
+
+        // Panes can't be flattened:
+        assert!(!tree.flatten_container(&mut behavior, panes[0]));
+        assert_eq!(behavior.edits.len(), 1);
+    }
+
+    #[test]
+    fn test_flatten_into_tabs() {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..3).map(|pane| tiles.insert_pane(pane)).collect();
+        let inner = tiles.insert_tab_tile(vec![panes[1], panes[2]]);
+        let root = tiles.insert_tab_tile(vec![inner, panes[0]]);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get_mut(inner) else {
+            panic!("Expected a tabs container");
+        };
+        tabs.set_active(panes[2]);
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get_mut(root) else {
+            panic!("Expected a tabs container");
+        };
+        tabs.set_active(inner);
+        tabs.pin(inner);
+
+        let mut behavior = TestBehavior::default();
+        assert!(tree.flatten_container(&mut behavior, inner));
+        assert_eq!(behavior.edits, vec![EditAction::ContainerFlattened]);
+
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get(root) else {
+            panic!("Expected a tabs container");
+        };
+        assert_eq!(tabs.children, [panes[1], panes[2], panes[0]]);
+        assert_eq!(
+            tabs.active,
+            Some(panes[2]),
+            "The active tab of the flattened container stays active"
+        );
+        assert!(tabs.is_pinned(panes[1]));
+        assert!(tabs.is_pinned(panes[2]));
+        assert!(!tabs.is_pinned(panes[0]));
+    }
+
+    #[test]
+    fn test_wrap_in_container() {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..2).map(|pane| tiles.insert_pane(pane)).collect();
+        let root = tiles.insert_horizontal_tile(panes.clone());
+        let mut tree = Tree::new("tree", root, tiles);
+        if let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) {
+            linear.shares[panes[1]] = 3.0;
+        }
+
+        let mut behavior = TestBehavior::default();
+        let tabs_id = tree.wrap_in_container(&mut behavior, panes[1], ContainerKind::Tabs);
+        assert_eq!(behavior.edits, vec![EditAction::ContainerWrapped]);
+
+        let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get(root) else {
+            panic!("Expected a linear container");
+        };
+        assert_eq!(linear.children, [panes[0], tabs_id]);
+        assert_eq!(
+            linear.shares[tabs_id], 3.0,
+            "The container takes over the share"
+        );
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get(tabs_id) else {
+            panic!("Expected a tabs container");
+        };
+        assert_eq!(tabs.children, [panes[1]]);
+
+        // Wrapping the root makes a new root:
+        let new_root = tree.wrap_in_container(&mut behavior, root, ContainerKind::Vertical);
+        assert_eq!(tree.root(), Some(new_root));
+        assert_eq!(tree.tiles.parent_of(root), Some(new_root));
+
+        // Unknown tiles are left alone:
+        let unknown = TileId::from_u64(1234);
+        behavior.edits.clear();
+        assert_eq!(
+            tree.wrap_in_container(&mut behavior, unknown, ContainerKind::Tabs),
+            unknown
+        );
+        assert!(behavior.edits.is_empty());
+    }
+
+    #[test]
+    fn test_equalize_shares() {
+        let mut tiles = Tiles::default();
+        let panes: Vec<TileId> = (0..2).map(|pane| tiles.insert_pane(pane)).collect();
+        let root = tiles.insert_horizontal_tile(panes.clone());
+        let mut tree = Tree::new("tree", root, tiles);
+        if let Some(Tile::Container(Container::Linear(linear))) = tree.tiles.get_mut(root) {
+            linear.shares[panes[1]] = 3.0;
+        }
+
+        let mut behavior = TestBehavior::default();
+        assert!(tree.equalize_shares(&mut behavior, root, true));
+        assert_eq!(behavior.edits, vec![EditAction::TileResized]);
+
+        assert!(
+            !tree.equalize_shares(&mut behavior, root, true),
+            "Already equal"
+        );
+        assert_eq!(behavior.edits.len(), 1, "No edit if nothing changed");
+    }
+
+    #[test]
+    fn test_flatten_root() {
+        let mut tree = Tree::new_tabs("tree", vec![0, 1]);
+        let root = tree.root().unwrap();
+        let mut behavior = TestBehavior::default();
+        assert!(
+            !tree.flatten_container(&mut behavior, root),
+            "A root with several children can't be flattened"
+        );
+
+        let mut tiles = Tiles::default();
+        let pane = tiles.insert_pane(0);
+        let root = tiles.insert_tab_tile(vec![pane]);
+        let mut tree = Tree::new("tree", root, tiles);
+        assert!(tree.flatten_container(&mut behavior, root));
+        assert_eq!(tree.root(), Some(pane));
+        assert!(tree.tiles.get(root).is_none());
+        assert_eq!(behavior.edits, vec![EditAction::ContainerFlattened]);
+    }
+
+    #[test]
+    fn test_open_pane_next_to_pane_root() {
+        let mut tiles = Tiles::default();
+        let root = tiles.insert_pane(0);
+        let mut tree = Tree::new("tree", root, tiles);
+
+        let opened = tree.open_pane(1);
+        assert_eq!(tree.root(), Some(root));
+        let Some(Tile::Container(Container::Tabs(tabs))) = tree.tiles.get(root) else {
+            panic!("Expected the root to be put in tabs");
+        };
+        assert_eq!(tabs.children.len(), 2);
+        assert_eq!(tabs.children[1], opened);
+        assert_eq!(tabs.active, Some(opened));
+
+        tree.gc(&mut TestBehavior::default());
+        assert_eq!(
+            tree.tiles.get_pane(&opened),
+            Some(&1),
+            "The pane is reachable"
+        );
+    }
+}
@@ -27,8 +27,10 @@ fn create_tree() -> Tree<Pane> {
         tiles.insert_grid_tile(cells)
     });
     tabs.push(tiles.insert_pane(gen_pane()));
+    tabs.push(tiles.insert_placeholder());
 
     let root = tiles.insert_tab_tile(tabs);
+    tiles.set_name(root, "main_tabs");
 
     Tree::new("my_tree", root, tiles)
 }
@@ -48,3 +50,93 @@ fn test_serialize_ron() {
     let restored = ron::from_str(&ron).expect("ron deserialize");
     assert_eq!(original, restored, "RON did not round-trip");
 }
+
+/// A pane that can't be serialized, but has some view state worth keeping.
+struct ViewPane {
+    scroll_offset: f32,
+}
+
+struct ViewBehavior;
+
+impl egui_tiles::Behavior<ViewPane> for ViewBehavior {
+    fn pane_ui(
+        &mut self,
+        _ui: &mut egui::Ui,
+        _tile_id: egui_tiles::TileId,
+        _pane: &mut ViewPane,
+    ) -> egui_tiles::UiResponse {
+        egui_tiles::UiResponse::None
+    }
+
+    fn tab_title_for_pane(&mut self, _pane: &ViewPane) -> egui::WidgetText {
+        "View".into()
+    }
+
+    fn save_pane_state(&mut self, pane: &ViewPane) -> Option<serde_json::Value> {
+        Some(pane.scroll_offset.into())
+    }
+
+    fn restore_pane_state(&mut self, pane: &mut ViewPane, state: serde_json::Value) {
+        pane.scroll_offset = state.as_f64().unwrap_or_default() as f32;
+    }
+}
+
+#[test]
+fn test_pane_states() {
+    let mut original = Tree::new_tabs(
+        "my_tree",
+        vec![
+            ViewPane { scroll_offset: 1.0 },
+            ViewPane {
+                scroll_offset: 42.0,
+            },
+        ],
+    );
+    original.save_pane_states(&mut ViewBehavior);
+
+    let json = serde_json::to_string(&original.map_panes_ref(|_, _| ())).expect("json serialize");
+    let restored: Tree<()> = serde_json::from_str(&json).expect("json deserialize");
+
+    let mut restored = restored.map_panes(|_, ()| ViewPane { scroll_offset: 0.0 });
+    restored.restore_pane_states(&mut ViewBehavior);
+
+    for (tile_id, tile) in original.tiles.iter() {
+        if let egui_tiles::Tile::Pane(pane) = tile {
+            assert_eq!(
+                restored
+                    .tiles
+                    .get_pane(tile_id)
+                    .map(|pane| pane.scroll_offset),
+                Some(pane.scroll_offset),
+                "The pane state should survive the round-trip"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_placeholder_keeps_its_place() {
+    let mut original = Tree::new_tabs(
+        "my_tree",
+        vec![
+            ViewPane { scroll_offset: 1.0 },
+            ViewPane {
+                scroll_offset: 42.0,
+            },
+        ],
+    );
+    let root = original.root().unwrap();
+    let missing = original.tiles.get_container(root).unwrap().children_vec()[1];
+    let pane = original.replace_pane_with_placeholder(&mut ViewBehavior, missing);
+    assert_eq!(pane.map(|pane| pane.scroll_offset), Some(42.0));
+
+    let json = serde_json::to_string(&original.map_panes_ref(|_, _| ())).expect("json serialize");
+    let restored: Tree<()> = serde_json::from_str(&json).expect("json deserialize");
+
+    assert!(restored.tiles.get(missing).unwrap().is_placeholder());
+    assert_eq!(
+        restored.tiles.get_container(root).unwrap().children_vec()[1],
+        missing,
+        "The placeholder should keep the place of the pane"
+    );
+}
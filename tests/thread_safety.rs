@@ -0,0 +1,39 @@
+use egui_tiles::{TileId, Tiles, Tree};
+
+/// Build a layout on a worker thread, and hand it to the (ui) thread that spawned it.
+#[test]
+fn build_tree_on_worker_thread() {
+    let (tree, panes) = std::thread::Builder::new()
+        .name("layout_builder".to_owned())
+        .spawn(|| {
+            let mut tiles = Tiles::default();
+            let panes: Vec<TileId> = (0..3)
+                .map(|nr| tiles.insert_pane(format!("Pane {nr}")))
+                .collect();
+            let root = tiles.insert_horizontal_tile(panes.clone());
+            (Tree::new("tree", root, tiles), panes)
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert_eq!(tree.active_tiles().len(), 4);
+    for (nr, pane) in panes.into_iter().enumerate() {
+        assert_eq!(tree.tiles.get_pane(&pane), Some(&format!("Pane {nr}")));
+    }
+}
+
+/// Read a shared tree from several threads at once.
+#[test]
+fn share_tree_between_threads() {
+    let tree = Tree::new_tabs("tree", vec![1, 2, 3]);
+    std::thread::scope(|scope| {
+        for _ in 0..2 {
+            std::thread::Builder::new()
+                .spawn_scoped(scope, || {
+                    assert_eq!(tree.tiles.len(), 4);
+                })
+                .unwrap();
+        }
+    });
+}